@@ -1,5 +1,36 @@
 use anchor_lang::prelude::*;
 
 #[constant]
-pub const EPOCHS_PER_CYCLE: usize = 5;
+pub const DEFAULT_EPOCHS_PER_CYCLE: u16 = 5;
+// Capacity of the VList, bounding the list size a pool can configure
 pub const MAX_VALIDATORS_IN_LIST: usize = 100;
+// Defaults and bounds for the scoring parameters in PoolParams
+pub const DEFAULT_LOOKBACK_EPOCHS: u16 = 5;
+pub const MAX_LOOKBACK_EPOCHS: u16 = 50;
+pub const MAX_EPOCHS_PER_CYCLE: u16 = 100;
+pub const DEFAULT_PERCENTILE: u8 = 10;
+pub const DEFAULT_MIN_EPOCHS: u16 = 1;
+pub const MAX_STAKE_ACCOUNTS: usize = 64;
+pub const BASIS_POINTS: u64 = 10_000;
+pub const EXCHANGE_RATE_PRECISION: u64 = 1_000_000_000;
+pub const POOL_MINT_DECIMALS: u8 = 9;
+// Number of epochs a fee change waits before it can take effect
+pub const FEE_CHANGE_DELAY_EPOCHS: u64 = 2;
+// Default rebalancing limits: a replacement must score 5% higher than the incumbent, and at most
+// 10% of the pool's stake moves per cycle
+pub const DEFAULT_MIN_SCORE_IMPROVEMENT_BPS: u16 = 500;
+pub const DEFAULT_MAX_STAKE_MOVED_BPS: u16 = 1_000;
+// Default reserve: 5% of the pool kept liquid, with instant unstakes costing 0.1% while the
+// reserve is at its target and up to 3% as it empties
+pub const DEFAULT_TARGET_RESERVE_BPS: u16 = 500;
+pub const DEFAULT_MIN_INSTANT_UNSTAKE_FEE_BPS: u16 = 10;
+pub const DEFAULT_MAX_INSTANT_UNSTAKE_FEE_BPS: u16 = 300;
+// Validators returned by get_top_validators, keeping its return data under the 1024 byte limit
+pub const MAX_TOP_VALIDATORS: usize = 20;
+pub const TOP_VALIDATORS_VERSION: u8 = 1;
+// Credits of a validator matching the cluster's highest in an epoch, when scores are normalized
+pub const NORMALIZED_CREDITS: u64 = 1_000_000;
+// Current layouts of the Pool and VList accounts, accounts from before versioning are version 0.
+// VList version 2 added score components to its entries
+pub const POOL_VERSION: u8 = 1;
+pub const VLIST_VERSION: u8 = 2;
@@ -5,6 +5,108 @@ pub enum ErrorCode {
     #[msg("Custom error message")]
     CustomError,
 
-    #[msg("Validator cannot be scored yet: less than 5 epochs have passed")]
+    #[msg("Validator cannot be scored yet: fewer than the configured minimum epochs have passed")]
     NotEnoughEpochs,
+
+    #[msg("Fees cannot exceed 100% of rewards")]
+    InvalidFee,
+
+    #[msg("Pool balance has already been updated this epoch")]
+    PoolAlreadyUpdated,
+
+    #[msg("Stake account is not owned by the pool")]
+    InvalidStakeAccount,
+
+    #[msg("Stake account is already registered with the pool")]
+    DuplicateStakeAccount,
+
+    #[msg("Arithmetic Error (overflow/underflow)")]
+    ArithmeticError,
+
+    #[msg("Pool balance must be updated this epoch first")]
+    PoolBalanceStale,
+
+    #[msg("Stake account is not registered with the pool")]
+    StakeAccountNotRegistered,
+
+    #[msg("Stake registry is full")]
+    StakeRegistryFull,
+
+    #[msg("Stake accounts do not match the pool's stake registry")]
+    StakeRegistryMismatch,
+
+    #[msg("Stake account lockup is in force")]
+    StakeLockupInForce,
+
+    #[msg("Stake amount is below the minimum delegation or rent-exempt reserve")]
+    InvalidStakeAmount,
+
+    #[msg("Stake account still has active or transitioning stake")]
+    StakeNotInactive,
+
+    #[msg("Stake account is a transient account in flight between validators")]
+    TransientStakeInFlight,
+
+    #[msg("Stake account is not a transient stake account")]
+    NotTransientStake,
+
+    #[msg("Vote account does not match the redelegation target")]
+    InvalidValidatorVote,
+
+    #[msg("Invalid rebalance parameters")]
+    InvalidRebalanceParams,
+
+    #[msg("Validator is not in the validator list")]
+    ValidatorNotInList,
+
+    #[msg("New validator does not improve enough on the incumbent's score")]
+    InsufficientScoreImprovement,
+
+    #[msg("Redelegation exceeds the stake moved allowed this cycle")]
+    RebalanceBudgetExceeded,
+
+    #[msg("Pool is paused")]
+    PoolPaused,
+
+    #[msg("Pool still manages stake or has pool tokens outstanding")]
+    PoolNotEmpty,
+
+    #[msg("Invalid pool parameters")]
+    InvalidPoolParams,
+
+    #[msg("Account is already at the current layout")]
+    AccountAlreadyMigrated,
+
+    #[msg("Stake is delegated to a validator that is still eligible")]
+    ValidatorStillEligible,
+
+    #[msg("Invalid withdrawal amount")]
+    InvalidWithdrawalAmount,
+
+    #[msg("Withdrawal ticket is not claimable yet")]
+    TicketNotClaimable,
+
+    #[msg("Reserve cannot cover the withdrawal yet")]
+    InsufficientReserve,
+
+    #[msg("Invalid reserve parameters")]
+    InvalidReserveParams,
+
+    #[msg("Instant unstake pays out less than the requested minimum")]
+    InstantUnstakeBelowMinimum,
+
+    #[msg("Stake account holds no lamports above its stake to harvest")]
+    NothingToHarvest,
+
+    #[msg("Not an SPL stake pool linked to this pool as its staker")]
+    InvalidStakePool,
+
+    #[msg("Invalid scoring parameters")]
+    InvalidScoringParams,
+
+    #[msg("Pool mint left by a closed pool cannot be reused")]
+    InvalidPoolMint,
+
+    #[msg("Transient stake account is missing or not derived from the split validator and nonce")]
+    InvalidTransientStakeAccount,
 }
@@ -0,0 +1,347 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    Fees, PoolParams, RebalanceParams, RebalanceRejection, RemovalReason, ReserveParams,
+    ScoreComponents, ScoringParams, StakeStatus,
+};
+
+#[event]
+pub struct ValidatorScoreUpdated {
+    pub pool: Pubkey,
+    pub validator: Pubkey,
+    pub epoch: u64,
+    // None while the validator was not in the VList
+    pub old_score: Option<u32>,
+    pub new_score: u32,
+    // Position in the VList, None when the validator is not listed
+    pub old_rank: Option<u32>,
+    pub new_rank: Option<u32>,
+    pub components: ScoreComponents,
+}
+
+// A validator entered or left the top percentile of the VList eligible for stake
+#[event]
+pub struct ValidatorSelectionChanged {
+    pub pool: Pubkey,
+    pub validator: Pubkey,
+    // Whether the validator is now eligible, having not been before
+    pub selected: bool,
+    // Position in the VList, None when the validator is not listed
+    pub old_rank: Option<u32>,
+    pub new_rank: Option<u32>,
+    // Validators eligible for stake
+    pub old_selected_count: u32,
+    pub new_selected_count: u32,
+}
+
+// A validator was taken out of the VList
+#[event]
+pub struct ValidatorRemoved {
+    pub pool: Pubkey,
+    pub validator: Pubkey,
+    pub reason: RemovalReason,
+    // Score and position in the VList before removal
+    pub score: u32,
+    pub rank: u32,
+    // The lookback that had missing epochs for MissingEpochs, otherwise how the score was reached
+    pub components: ScoreComponents,
+}
+
+#[event]
+pub struct StakeDeposited {
+    pub pool: Pubkey,
+    pub stake_account: Pubkey,
+    pub validator: Pubkey,
+    pub lamports: u64,
+    pub status: StakeStatus,
+    // Minted to the depositor
+    pub pool_tokens: u64,
+    pub old_total_lamports: u64,
+    pub new_total_lamports: u64,
+}
+
+#[event]
+pub struct StakeWithdrawn {
+    pub pool: Pubkey,
+    pub stake_account: Pubkey,
+    pub lamports: u64,
+    pub old_reserve_lamports: u64,
+    pub new_reserve_lamports: u64,
+}
+
+#[event]
+pub struct StakeDelegated {
+    pub pool: Pubkey,
+    pub stake_account: Pubkey,
+    pub old_validator: Pubkey,
+    pub new_validator: Pubkey,
+    pub lamports: u64,
+    pub old_status: StakeStatus,
+    pub new_status: StakeStatus,
+}
+
+#[event]
+pub struct StakeDeactivated {
+    pub pool: Pubkey,
+    pub stake_account: Pubkey,
+    pub validator: Pubkey,
+    pub lamports: u64,
+    pub old_status: StakeStatus,
+    pub new_status: StakeStatus,
+}
+
+#[event]
+pub struct StakeSplit {
+    pub pool: Pubkey,
+    pub stake_account: Pubkey,
+    pub split_stake_account: Pubkey,
+    pub lamports: u64,
+    // Lamports in the source stake account
+    pub old_lamports: u64,
+    pub new_lamports: u64,
+}
+
+#[event]
+pub struct StakeMerged {
+    pub pool: Pubkey,
+    pub destination_stake_account: Pubkey,
+    pub source_stake_account: Pubkey,
+    // Lamports in the destination stake account
+    pub old_lamports: u64,
+    pub new_lamports: u64,
+}
+
+#[event]
+pub struct StakeRedelegated {
+    pub pool: Pubkey,
+    pub stake_account: Pubkey,
+    pub transient_stake_account: Pubkey,
+    pub from_validator: Pubkey,
+    pub to_validator: Pubkey,
+    pub lamports: u64,
+    // VList scores the move was decided on, and the improvement they had to clear
+    pub from_score: u32,
+    pub to_score: u32,
+    pub min_score_improvement_bps: u16,
+    // Stake moved in the current cycle, including this move, against the cycle's budget
+    pub stake_moved_in_cycle: u64,
+    pub max_stake_moved: u64,
+}
+
+#[event]
+pub struct RebalanceRejected {
+    pub pool: Pubkey,
+    pub stake_account: Pubkey,
+    pub from_validator: Pubkey,
+    pub to_validator: Pubkey,
+    pub lamports: u64,
+    pub reason: RebalanceRejection,
+    pub from_score: u32,
+    pub to_score: u32,
+    pub min_score_improvement_bps: u16,
+    // Stake already moved in the current cycle, excluding this move, against the cycle's budget
+    pub stake_moved_in_cycle: u64,
+    pub max_stake_moved: u64,
+}
+
+#[event]
+pub struct PoolBalanceUpdated {
+    pub pool: Pubkey,
+    pub epoch: u64,
+    pub old_total_lamports: u64,
+    pub new_total_lamports: u64,
+    pub rewards: u64,
+    // Part of `rewards` harvested as MEV tips
+    pub mev_rewards: u64,
+    pub fee_lamports: u64,
+    pub fee_pool_tokens: u64,
+    pub old_high_water_mark: u64,
+    pub new_high_water_mark: u64,
+}
+
+#[event]
+pub struct FeesStaged {
+    pub pool: Pubkey,
+    // Fees already waiting for their delay, replaced by this change
+    pub old_next_fees: Option<Fees>,
+    pub new_next_fees: Fees,
+    pub effective_epoch: u64,
+}
+
+#[event]
+pub struct FeesChanged {
+    pub pool: Pubkey,
+    pub epoch: u64,
+    pub old_fees: Fees,
+    pub new_fees: Fees,
+}
+
+#[event]
+pub struct PoolPauseChanged {
+    pub pool: Pubkey,
+    pub guardian: Pubkey,
+    pub old_paused: bool,
+    pub new_paused: bool,
+}
+
+#[event]
+pub struct GuardianChanged {
+    pub pool: Pubkey,
+    pub old_guardian: Pubkey,
+    pub new_guardian: Pubkey,
+}
+
+#[event]
+pub struct PoolClosed {
+    pub pool: Pubkey,
+    pub admin: Pubkey,
+    // Lamports swept from the reserve to the admin, including its rent
+    pub reserve_lamports: u64,
+}
+
+#[event]
+pub struct PoolParamsChanged {
+    pub pool: Pubkey,
+    pub old_params: PoolParams,
+    pub new_params: PoolParams,
+}
+
+#[event]
+pub struct RebalanceParamsChanged {
+    pub pool: Pubkey,
+    pub old_params: RebalanceParams,
+    pub new_params: RebalanceParams,
+}
+
+#[event]
+pub struct AccountMigrated {
+    pub pool: Pubkey,
+    pub account: Pubkey,
+    pub old_version: u8,
+    pub new_version: u8,
+}
+
+#[event]
+pub struct ManagedStakeRegistered {
+    pub pool: Pubkey,
+    pub stake_account: Pubkey,
+    pub owner: Pubkey,
+    pub validator: Pubkey,
+    pub lamports: u64,
+}
+
+#[event]
+pub struct ManagedStakeDelegated {
+    pub pool: Pubkey,
+    pub stake_account: Pubkey,
+    pub old_validator: Pubkey,
+    pub new_validator: Pubkey,
+    pub lamports: u64,
+}
+
+#[event]
+pub struct ManagedStakeDeactivated {
+    pub pool: Pubkey,
+    pub stake_account: Pubkey,
+    pub validator: Pubkey,
+    pub lamports: u64,
+}
+
+#[event]
+pub struct ManagedStakeRevoked {
+    pub pool: Pubkey,
+    pub stake_account: Pubkey,
+    pub owner: Pubkey,
+}
+
+#[event]
+pub struct WithdrawalRequested {
+    pub pool: Pubkey,
+    pub ticket: Pubkey,
+    pub owner: Pubkey,
+    pub pool_tokens: u64,
+    pub lamports: u64,
+    pub requested_epoch: u64,
+}
+
+#[event]
+pub struct TicketClaimed {
+    pub pool: Pubkey,
+    pub ticket: Pubkey,
+    pub owner: Pubkey,
+    pub lamports: u64,
+}
+
+#[event]
+pub struct ReserveParamsChanged {
+    pub pool: Pubkey,
+    pub old_params: ReserveParams,
+    pub new_params: ReserveParams,
+}
+
+#[event]
+pub struct InstantUnstaked {
+    pub pool: Pubkey,
+    pub owner: Pubkey,
+    pub pool_tokens: u64,
+    // Paid to the owner, after the fee
+    pub lamports: u64,
+    pub fee_lamports: u64,
+    pub fee_bps: u16,
+    pub old_reserve_lamports: u64,
+    pub new_reserve_lamports: u64,
+}
+
+#[event]
+pub struct MevHarvested {
+    pub pool: Pubkey,
+    pub stake_account: Pubkey,
+    pub validator: Pubkey,
+    pub lamports: u64,
+    pub old_reserve_lamports: u64,
+    pub new_reserve_lamports: u64,
+}
+
+#[event]
+pub struct StakePoolChanged {
+    pub pool: Pubkey,
+    pub old_stake_pool: Pubkey,
+    pub new_stake_pool: Pubkey,
+}
+
+#[event]
+pub struct StakePoolValidatorAdded {
+    pub pool: Pubkey,
+    pub stake_pool: Pubkey,
+    pub validator: Pubkey,
+}
+
+#[event]
+pub struct StakePoolValidatorRemoved {
+    pub pool: Pubkey,
+    pub stake_pool: Pubkey,
+    pub validator: Pubkey,
+}
+
+#[event]
+pub struct StakePoolStakeIncreased {
+    pub pool: Pubkey,
+    pub stake_pool: Pubkey,
+    pub validator: Pubkey,
+    pub lamports: u64,
+}
+
+#[event]
+pub struct StakePoolStakeDecreased {
+    pub pool: Pubkey,
+    pub stake_pool: Pubkey,
+    pub validator: Pubkey,
+    pub lamports: u64,
+}
+
+#[event]
+pub struct ScoringParamsChanged {
+    pub pool: Pubkey,
+    pub old_params: ScoringParams,
+    pub new_params: ScoringParams,
+}
@@ -0,0 +1,122 @@
+use anchor_lang::{prelude::*, solana_program::stake_history::StakeHistory};
+use anchor_spl::token::{mint_to, Mint, MintTo, Token, TokenAccount};
+
+use crate::{
+    error::ErrorCode,
+    events::StakeDeposited,
+    utils::{get_pool_stake_account, get_stake_status},
+    Pool, StakeRegistry, StakeRegistryEntry,
+};
+
+#[derive(Accounts)]
+pub struct AddStakeAccount<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [Pool::SEED, pool.admin.as_ref()],
+        bump,
+        has_one = admin,
+        has_one = pool_mint,
+        constraint = !pool.paused @ ErrorCode::PoolPaused
+    )]
+    pub pool: Account<'info, Pool>,
+
+    #[account(
+        mut,
+        seeds = [StakeRegistry::SEED, pool.key().as_ref()],
+        bump = stake_registry.bump,
+        has_one = pool
+    )]
+    pub stake_registry: Box<Account<'info, StakeRegistry>>,
+
+    /// CHECK: checked to be a stake account with the pool as staker and withdrawer
+    pub stake_account: AccountInfo<'info>,
+
+    #[account(mut)]
+    pub pool_mint: Account<'info, Mint>,
+
+    // Receives the pool tokens minted for the deposit
+    #[account(mut, token::mint = pool_mint)]
+    pub depositor_token_account: Account<'info, TokenAccount>,
+
+    pub stake_history: Sysvar<'info, StakeHistory>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+/// Brings a stake account under management and mints its depositor pool tokens at the exchange
+/// rate of this epoch's balance update, which must already have run. Its lamports join the
+/// pool's principal, so they are not counted as rewards by the next `update_pool_balance`.
+pub fn handler(ctx: Context<AddStakeAccount>) -> Result<()> {
+    let AddStakeAccount {
+        pool,
+        stake_registry,
+        stake_account,
+        pool_mint,
+        depositor_token_account,
+        stake_history,
+        token_program,
+        ..
+    } = ctx.accounts;
+
+    let clock = Clock::get()?;
+    pool.check_balance_fresh(clock.epoch)?;
+    let stake = get_pool_stake_account(&pool.key(), stake_account)?;
+    match stake.authorized() {
+        Some(authorized) if authorized.staker == pool.key() => {}
+        _ => return Err(ErrorCode::InvalidStakeAccount.into()),
+    }
+    if let Some(lockup) = stake.lockup() {
+        if lockup.is_in_force(&clock, None) {
+            return Err(ErrorCode::StakeLockupInForce.into());
+        }
+    }
+
+    let entry = StakeRegistryEntry {
+        stake_account: stake_account.key(),
+        vote_account: stake
+            .delegation()
+            .map(|delegation| delegation.voter_pubkey)
+            .unwrap_or_default(),
+        lamports: stake_account.lamports(),
+        status: get_stake_status(&stake, clock.epoch, stake_history),
+        ..StakeRegistryEntry::default()
+    };
+    stake_registry.add(entry)?;
+
+    let pool_tokens =
+        Pool::pool_tokens_for_deposit(entry.lamports, pool.total_lamports, pool_mint.supply)
+            .ok_or(ErrorCode::ArithmeticError)?;
+    mint_to(
+        CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            MintTo {
+                mint: pool_mint.to_account_info(),
+                to: depositor_token_account.to_account_info(),
+                authority: pool.to_account_info(),
+            },
+            &[&[Pool::SEED, pool.admin.as_ref(), &[pool.bump]]],
+        ),
+        pool_tokens,
+    )?;
+
+    let total_lamports = pool
+        .total_lamports
+        .checked_add(entry.lamports)
+        .ok_or(ErrorCode::ArithmeticError)?;
+
+    emit!(StakeDeposited {
+        pool: pool.key(),
+        stake_account: entry.stake_account,
+        validator: entry.vote_account,
+        lamports: entry.lamports,
+        status: entry.status,
+        pool_tokens,
+        old_total_lamports: pool.total_lamports,
+        new_total_lamports: total_lamports,
+    });
+    pool.total_lamports = total_lamports;
+
+    Ok(())
+}
@@ -0,0 +1,136 @@
+use anchor_lang::{
+    prelude::*,
+    solana_program::{program::invoke_signed, stake, sysvar::stake_history},
+};
+use anchor_spl::stake::Stake as StakeProgram;
+
+use crate::{
+    error::ErrorCode,
+    events::StakePoolValidatorAdded,
+    spl_stake_pool::{self, SplStakePool},
+    Pool, VList,
+};
+
+#[derive(Accounts)]
+pub struct AddStakePoolValidator<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [Pool::SEED, pool.admin.as_ref()],
+        bump,
+        has_one = admin,
+        constraint = !pool.paused @ ErrorCode::PoolPaused
+    )]
+    pub pool: Account<'info, Pool>,
+
+    #[account(
+        seeds = [VList::SEED, pool.admin.as_ref(), pool.key().as_ref()],
+        bump = v_list.bump,
+        has_one = pool
+    )]
+    pub v_list: Box<Account<'info, VList>>,
+
+    /// CHECK: the stake pool linked with `set_stake_pool`
+    #[account(mut, constraint = stake_pool.key() == pool.stake_pool @ ErrorCode::InvalidStakePool)]
+    pub stake_pool: UncheckedAccount<'info>,
+
+    /// CHECK: checked by the stake pool program
+    #[account(mut)]
+    pub reserve_stake: UncheckedAccount<'info>,
+
+    /// CHECK: checked by the stake pool program
+    pub withdraw_authority: UncheckedAccount<'info>,
+
+    /// CHECK: checked by the stake pool program
+    #[account(mut)]
+    pub validator_list: UncheckedAccount<'info>,
+
+    /// CHECK: created by the stake pool program at its derived address
+    #[account(mut)]
+    pub validator_stake: UncheckedAccount<'info>,
+
+    /// CHECK: checked to be an eligible validator in the VList
+    pub validator_vote: UncheckedAccount<'info>,
+
+    pub rent: Sysvar<'info, Rent>,
+
+    pub clock: Sysvar<'info, Clock>,
+
+    /// CHECK:
+    #[account(address = stake_history::ID)]
+    pub stake_history: UncheckedAccount<'info>,
+
+    /// CHECK:
+    #[account(address = stake::config::ID)]
+    pub stake_config: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+
+    pub stake_program: Program<'info, StakeProgram>,
+
+    pub spl_stake_pool_program: Program<'info, SplStakePool>,
+}
+
+/// Adds a validator in the top percentile of the VList to the linked stake pool, which funds the
+/// new validator stake account from its reserve.
+pub fn handler(ctx: Context<AddStakePoolValidator>) -> Result<()> {
+    let AddStakePoolValidator {
+        pool,
+        v_list,
+        stake_pool,
+        reserve_stake,
+        withdraw_authority,
+        validator_list,
+        validator_stake,
+        validator_vote,
+        rent,
+        clock,
+        stake_history,
+        stake_config,
+        system_program,
+        stake_program,
+        spl_stake_pool_program,
+        ..
+    } = ctx.accounts;
+
+    if !v_list.is_eligible(validator_vote.key, &pool.params) {
+        return Err(ErrorCode::ValidatorNotInList.into());
+    }
+
+    invoke_signed(
+        &spl_stake_pool::add_validator_to_pool(
+            stake_pool.key,
+            &pool.key(),
+            reserve_stake.key,
+            withdraw_authority.key,
+            validator_list.key,
+            validator_stake.key,
+            validator_vote.key,
+        ),
+        &[
+            spl_stake_pool_program.to_account_info(),
+            stake_pool.to_account_info(),
+            pool.to_account_info(),
+            reserve_stake.to_account_info(),
+            withdraw_authority.to_account_info(),
+            validator_list.to_account_info(),
+            validator_stake.to_account_info(),
+            validator_vote.to_account_info(),
+            rent.to_account_info(),
+            clock.to_account_info(),
+            stake_history.to_account_info(),
+            stake_config.to_account_info(),
+            system_program.to_account_info(),
+            stake_program.to_account_info(),
+        ],
+        &[&[Pool::SEED, pool.admin.as_ref(), &[pool.bump]]],
+    )?;
+
+    emit!(StakePoolValidatorAdded {
+        pool: pool.key(),
+        stake_pool: stake_pool.key(),
+        validator: validator_vote.key(),
+    });
+
+    Ok(())
+}
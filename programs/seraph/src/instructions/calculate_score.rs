@@ -1,7 +1,12 @@
 use anchor_lang::prelude::*;
-use validator_history::ValidatorHistory;
+use validator_history::{ClusterHistory, ValidatorHistory, ValidatorHistoryEntry};
 
-use crate::{Pool, VList};
+use crate::{
+    error::ErrorCode,
+    events::{ValidatorRemoved, ValidatorScoreUpdated},
+    utils::emit_selection_changes,
+    MissingEpochPolicy, Pool, RemovalReason, ScoreComponents, ScoringParams, VList,
+};
 
 #[derive(Accounts)]
 pub struct CalculateScore<'info> {
@@ -14,10 +19,19 @@ pub struct CalculateScore<'info> {
     /// CHECK:
     pub vote_account: UncheckedAccount<'info>,
 
+    /// CHECK: only loaded while the pool normalizes credits, so it may not exist otherwise
+    #[account(
+        seeds = [ClusterHistory::SEED],
+        bump,
+        seeds::program = validator_history::ID
+    )]
+    pub cluster_history_account: UncheckedAccount<'info>,
+
     #[account(
         seeds = [Pool::SEED, pool.admin.as_ref()],
         bump,
-        has_one = admin
+        has_one = admin,
+        constraint = !pool.paused @ ErrorCode::PoolPaused
     )]
     pub pool: Account<'info, Pool>,
 
@@ -30,10 +44,19 @@ pub struct CalculateScore<'info> {
     pub v_list: Account<'info, VList>,
 }
 
+// One epoch's credits, what is left of them after commission, and after skipped leader slots
+struct EpochScore {
+    credits: u32,
+    after_commission: u32,
+    score: u32,
+}
+
 pub fn handler(ctx: Context<CalculateScore>) -> Result<()> {
     let CalculateScore {
         validator_history_account,
         vote_account,
+        cluster_history_account,
+        pool,
         v_list,
         ..
     } = ctx.accounts;
@@ -45,36 +68,170 @@ pub fn handler(ctx: Context<CalculateScore>) -> Result<()> {
     let mut validator_history_slice: &[u8] = &validator_history_data;
     let validator_history = ValidatorHistory::try_deserialize(&mut validator_history_slice)?;
 
-    // Calculate score based on the last 5 entries
+    // Calculate score based on the pool's lookback window
+    let params = pool.params;
     let current_epoch = clock.epoch;
-    let start_epoch = if current_epoch > 5 {
-        current_epoch - 5
-    } else {
-        0
-    };
-    let end_epoch = current_epoch;
+    let start_epoch = current_epoch.saturating_sub(params.lookback_epochs as u64);
+    let mut end_epoch = current_epoch;
 
     let epoch_entries = validator_history
         .history
         .epoch_range(start_epoch as u16, end_epoch as u16);
 
-    let mut total_score = 0;
-    let mut entries_count = 0;
+    let scoring_params = pool.scoring_params;
+    let skip_missing = scoring_params.missing_epoch_policy == MissingEpochPolicy::Skip;
+
+    // The cluster's highest credits for each epoch, to normalize against
+    let max_epoch_credits = if scoring_params.normalize_credits {
+        let cluster_history =
+            AccountLoader::<ClusterHistory>::try_from(&cluster_history_account.to_account_info())?;
+        let max_epoch_credits = cluster_history
+            .load()?
+            .history
+            .max_epoch_credits_range(start_epoch as u16, end_epoch as u16);
+        Some(max_epoch_credits)
+    } else {
+        None
+    };
+
+    // Per-epoch scores, oldest first, None for epochs without history or, when normalizing, without
+    // a cluster reference. Unless missing epochs are skipped, so are epochs whose credits or
+    // commission the oracle never recorded.
+    let mut epoch_scores: Vec<Option<EpochScore>> = epoch_entries
+        .iter()
+        .enumerate()
+        .map(|(i, entry_option)| {
+            let entry = entry_option.filter(|entry| {
+                skip_missing
+                    || (entry.epoch_credits != ValidatorHistoryEntry::default().epoch_credits
+                        && entry.commission != ValidatorHistoryEntry::default().commission)
+            })?;
+            let credits = match &max_epoch_credits {
+                Some(max_epoch_credits) => {
+                    ScoringParams::normalized_credits(entry.epoch_credits, max_epoch_credits[i]?)?
+                }
+                None => entry.epoch_credits,
+            };
+            let commission_percentage = entry.commission as f64 / 100.0;
+            let after_commission = (credits as f64 * (1.0 - commission_percentage)) as u32;
 
-    for entry_option in epoch_entries {
-        if let Some(entry) = entry_option {
-            let epoch_credits = entry.epoch_credits;
-            let commission = entry.commission;
-            let commission_percentage = commission as f64 / 100.0;
-            total_score += (epoch_credits as f64 * (1.0 - commission_percentage)) as u32;
-            entries_count += 1;
+            // Block production the oracle never recorded is not penalized
+            let score = if entry.leader_slots == ValidatorHistoryEntry::default().leader_slots
+                || entry.blocks_produced == ValidatorHistoryEntry::default().blocks_produced
+            {
+                after_commission
+            } else {
+                scoring_params.apply_skip_rate(
+                    after_commission,
+                    entry.leader_slots,
+                    entry.blocks_produced,
+                )
+            };
+            Some(EpochScore {
+                credits,
+                after_commission,
+                score,
+            })
+        })
+        .collect();
+    // The current epoch is still in progress, so it is left out until it has a score rather than
+    // counted as missing
+    if current_epoch > start_epoch && matches!(epoch_scores.last(), Some(None)) {
+        epoch_scores.pop();
+        end_epoch -= 1;
+    }
+    let entries_count = epoch_scores.iter().flatten().count() as u32;
+    let old_ranking = v_list.ranking();
+
+    let mut lookback = ScoreComponents {
+        start_epoch: start_epoch as u16,
+        end_epoch: end_epoch as u16,
+        epochs_scored: entries_count as u8,
+        ..ScoreComponents::default()
+    };
+    if entries_count < epoch_scores.len() as u32 {
+        lookback.flags |= ScoreComponents::MISSING_EPOCHS;
+    }
+    if scoring_params.normalize_credits {
+        lookback.flags |= ScoreComponents::NORMALIZED;
+    }
+
+    if scoring_params.missing_epoch_policy == MissingEpochPolicy::Ineligible
+        && entries_count < epoch_scores.len() as u32
+    {
+        if let Some((rank, entry)) = v_list.remove(&vote_account.key()) {
+            v_list.refresh_eligibility(&params);
+            emit!(ValidatorRemoved {
+                pool: pool.key(),
+                validator: vote_account.key(),
+                reason: RemovalReason::MissingEpochs,
+                score: entry.score,
+                rank: rank as u32,
+                components: lookback,
+            });
+            emit_selection_changes(pool.key(), &old_ranking, &params, v_list, &params);
         }
+        return Ok(());
     }
 
-    // Calculate the average score if there are valid entries
-    if entries_count > 0 {
-        let average_score = total_score / entries_count;
-        v_list.insert_or_update(vote_account.key(), average_score, current_epoch);
+    // Calculate the average score if there is enough history
+    if entries_count >= params.min_epochs as u32 {
+        // Each term is averaged alike, so the components add up to the score
+        let average = |term: fn(&EpochScore) -> u32| {
+            let term_scores: Vec<Option<u32>> = epoch_scores
+                .iter()
+                .map(|epoch_score| epoch_score.as_ref().map(term))
+                .collect();
+            scoring_params.average(&term_scores)
+        };
+        let credits = average(|epoch_score| epoch_score.credits);
+        let after_commission = average(|epoch_score| epoch_score.after_commission);
+        let mut average_score = average(|epoch_score| epoch_score.score);
+
+        let mut components = ScoreComponents {
+            credits,
+            commission_penalty: credits.saturating_sub(after_commission),
+            skip_rate_penalty: after_commission.saturating_sub(average_score),
+            ..lookback
+        };
+
+        // Favour smaller validators by their most recently recorded stake and rank
+        let latest_stake = epoch_entries.iter().rev().flatten().find(|entry| {
+            entry.activated_stake_lamports
+                != ValidatorHistoryEntry::default().activated_stake_lamports
+                && entry.rank != ValidatorHistoryEntry::default().rank
+        });
+        if let Some(entry) = latest_stake {
+            let bonus_bps = scoring_params
+                .decentralization_bonus_bps(entry.activated_stake_lamports, entry.rank);
+            let bonus_score = ScoringParams::apply_bonus(average_score, bonus_bps);
+            components.decentralization_bonus = bonus_score - average_score;
+            average_score = bonus_score;
+        }
+
+        let update = v_list.insert_or_update(
+            vote_account.key(),
+            average_score,
+            components,
+            current_epoch,
+            params.max_validators as usize,
+        );
+        v_list.refresh_eligibility(&params);
+        if let Some(rank) = update.new_rank {
+            components = v_list.validators[rank].components;
+        }
+
+        emit!(ValidatorScoreUpdated {
+            pool: pool.key(),
+            validator: vote_account.key(),
+            epoch: current_epoch,
+            old_score: update.old_score,
+            new_score: average_score,
+            old_rank: update.old_rank.map(|rank| rank as u32),
+            new_rank: update.new_rank.map(|rank| rank as u32),
+            components,
+        });
+        emit_selection_changes(pool.key(), &old_ranking, &params, v_list, &params);
     }
 
     Ok(())
@@ -0,0 +1,85 @@
+use anchor_lang::{
+    prelude::*,
+    system_program::{transfer, Transfer},
+};
+
+use crate::{error::ErrorCode, events::TicketClaimed, Pool, WithdrawalTicket};
+
+#[derive(Accounts)]
+pub struct ClaimTicket<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [Pool::SEED, pool.admin.as_ref()],
+        bump
+    )]
+    pub pool: Account<'info, Pool>,
+
+    #[account(
+        mut,
+        close = owner,
+        seeds = [WithdrawalTicket::SEED, pool.key().as_ref(), &ticket.id.to_le_bytes()],
+        bump = ticket.bump,
+        has_one = pool,
+        has_one = owner
+    )]
+    pub ticket: Account<'info, WithdrawalTicket>,
+
+    #[account(
+        mut,
+        seeds = [Pool::RESERVE_SEED, pool.key().as_ref()],
+        bump = pool.reserve_bump
+    )]
+    pub reserve: SystemAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Pays a withdrawal ticket out of the reserve and closes it, once the unstake queue has
+/// deactivated stake to cover it and that stake has cooled down. Allowed while paused, as it is a
+/// user withdrawal.
+pub fn handler(ctx: Context<ClaimTicket>) -> Result<()> {
+    let ClaimTicket {
+        owner,
+        pool,
+        ticket,
+        reserve,
+        system_program,
+    } = ctx.accounts;
+
+    if !pool.is_ticket_claimable(ticket.id, Clock::get()?.epoch) {
+        return Err(ErrorCode::TicketNotClaimable.into());
+    }
+    if Pool::reserve_lamports(reserve)? < ticket.lamports {
+        return Err(ErrorCode::InsufficientReserve.into());
+    }
+
+    let pool_key = pool.key();
+    transfer(
+        CpiContext::new_with_signer(
+            system_program.to_account_info(),
+            Transfer {
+                from: reserve.to_account_info(),
+                to: owner.to_account_info(),
+            },
+            &[&[Pool::RESERVE_SEED, pool_key.as_ref(), &[pool.reserve_bump]]],
+        ),
+        ticket.lamports,
+    )?;
+
+    pool.pending_withdrawal_lamports = pool
+        .pending_withdrawal_lamports
+        .checked_sub(ticket.lamports)
+        .ok_or(ErrorCode::ArithmeticError)?;
+
+    emit!(TicketClaimed {
+        pool: pool_key,
+        ticket: ticket.key(),
+        owner: owner.key(),
+        lamports: ticket.lamports,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,158 @@
+use anchor_lang::{
+    prelude::*,
+    system_program::{transfer, Transfer},
+};
+use anchor_spl::token::{close_account, CloseAccount, Mint, Token, TokenAccount};
+
+use crate::{error::ErrorCode, events::PoolClosed, Pool, PoolHistory, StakeRegistry, VList};
+
+#[derive(Accounts)]
+pub struct ClosePool<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        close = admin,
+        seeds = [Pool::SEED, pool.admin.as_ref()],
+        bump,
+        has_one = admin,
+        has_one = pool_mint,
+        has_one = manager_fee_account,
+        constraint = !pool.paused @ ErrorCode::PoolPaused
+    )]
+    pub pool: Account<'info, Pool>,
+
+    #[account(
+        mut,
+        close = admin,
+        seeds = [VList::SEED, pool.admin.as_ref(), pool.key().as_ref()],
+        bump = v_list.bump,
+        has_one = pool
+    )]
+    pub v_list: Box<Account<'info, VList>>,
+
+    // required once initialized, as it is the only record of the pool's stake
+    #[account(
+        mut,
+        close = admin,
+        seeds = [StakeRegistry::SEED, pool.key().as_ref()],
+        bump = stake_registry.bump,
+        has_one = pool
+    )]
+    pub stake_registry: Option<Box<Account<'info, StakeRegistry>>>,
+
+    #[account(
+        mut,
+        close = admin,
+        seeds = [PoolHistory::SEED, pool.key().as_ref()],
+        bump,
+        has_one = pool
+    )]
+    pub pool_history: Option<AccountLoader<'info, PoolHistory>>,
+
+    pub pool_mint: Account<'info, Mint>,
+
+    #[account(mut)]
+    pub manager_fee_account: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [Pool::RESERVE_SEED, pool.key().as_ref()],
+        bump = pool.reserve_bump
+    )]
+    pub reserve: SystemAccount<'info>,
+
+    pub token_program: Program<'info, Token>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Tears down a pool that no longer manages stake, including stake of managed stake owners, is
+/// not linked to a stake pool and has no pool tokens outstanding. The pool, VList, stake
+/// registry, pool history and manager fee account are closed and their rent, along with whatever
+/// is left in the reserve, returned to the admin. The pool mint cannot be closed by the token
+/// program and is left behind for `initialize` to reuse.
+pub fn handler(ctx: Context<ClosePool>) -> Result<()> {
+    let ClosePool {
+        admin,
+        pool,
+        stake_registry,
+        pool_mint,
+        manager_fee_account,
+        reserve,
+        token_program,
+        system_program,
+        ..
+    } = ctx.accounts;
+
+    if pool_mint.supply > 0 {
+        msg!("{} pool tokens are outstanding", pool_mint.supply);
+        return Err(ErrorCode::PoolNotEmpty.into());
+    }
+    // owners could no longer revoke the staker authority held by the pool PDA
+    if pool.managed_stake_count > 0 {
+        msg!(
+            "{} managed stake accounts are still registered",
+            pool.managed_stake_count
+        );
+        return Err(ErrorCode::PoolNotEmpty.into());
+    }
+    // the pool PDA would otherwise remain the linked stake pool's staker
+    if pool.stake_pool != Pubkey::default() {
+        msg!("Pool is still the staker of stake pool {}", pool.stake_pool);
+        return Err(ErrorCode::PoolNotEmpty.into());
+    }
+    if pool.pending_withdrawal_lamports > 0 {
+        msg!(
+            "{} lamports are owed to withdrawal tickets",
+            pool.pending_withdrawal_lamports
+        );
+        return Err(ErrorCode::PoolNotEmpty.into());
+    }
+    match stake_registry {
+        Some(stake_registry) if !stake_registry.entries().is_empty() => {
+            msg!(
+                "{} stake accounts are still registered",
+                stake_registry.entries().len()
+            );
+            return Err(ErrorCode::PoolNotEmpty.into());
+        }
+        Some(_) => {}
+        // every registered lamport is booked into total_lamports, so a pool without a registry
+        // must never have managed stake
+        None if pool.total_lamports > 0 => return Err(ErrorCode::PoolNotEmpty.into()),
+        None => {}
+    }
+
+    close_account(CpiContext::new(
+        token_program.to_account_info(),
+        CloseAccount {
+            account: manager_fee_account.to_account_info(),
+            destination: admin.to_account_info(),
+            authority: admin.to_account_info(),
+        },
+    ))?;
+
+    let reserve_lamports = reserve.lamports();
+    let pool_key = pool.key();
+    transfer(
+        CpiContext::new_with_signer(
+            system_program.to_account_info(),
+            Transfer {
+                from: reserve.to_account_info(),
+                to: admin.to_account_info(),
+            },
+            &[&[Pool::RESERVE_SEED, pool_key.as_ref(), &[pool.reserve_bump]]],
+        ),
+        reserve_lamports,
+    )?;
+
+    emit!(PoolClosed {
+        pool: pool_key,
+        admin: admin.key(),
+        reserve_lamports,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,85 @@
+use anchor_lang::prelude::*;
+use anchor_spl::stake::{
+    deactivate_stake, DeactivateStake as DeactivateStakeAccount, Stake as StakeProgram,
+};
+
+use crate::{
+    error::ErrorCode, events::ManagedStakeDeactivated, utils::get_stake_account, ManagedStake,
+    Pool, VList,
+};
+
+#[derive(Accounts)]
+pub struct DeactivateManagedStake<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [Pool::SEED, pool.admin.as_ref()],
+        bump,
+        has_one = admin
+    )]
+    pub pool: Account<'info, Pool>,
+
+    #[account(
+        seeds = [VList::SEED, pool.admin.as_ref(), pool.key().as_ref()],
+        bump = v_list.bump,
+        has_one = pool
+    )]
+    pub v_list: Box<Account<'info, VList>>,
+
+    #[account(
+        seeds = [ManagedStake::SEED, pool.key().as_ref(), stake_account.key().as_ref()],
+        bump = managed_stake.bump,
+        has_one = pool,
+        has_one = stake_account
+    )]
+    pub managed_stake: Account<'info, ManagedStake>,
+
+    /// CHECK: checked by the stake program to have the pool as staker
+    #[account(mut)]
+    pub stake_account: AccountInfo<'info>,
+
+    pub clock: Sysvar<'info, Clock>,
+
+    pub stake_program: Program<'info, StakeProgram>,
+}
+
+/// Deactivates a managed stake account whose validator is no longer in the top percentile of the
+/// VList, so it can be delegated to an eligible one once inactive. Stake on an eligible
+/// validator is left alone.
+pub fn handler(ctx: Context<DeactivateManagedStake>) -> Result<()> {
+    let DeactivateManagedStake {
+        pool,
+        v_list,
+        stake_account,
+        clock,
+        stake_program,
+        ..
+    } = ctx.accounts;
+
+    let validator = get_stake_account(stake_account)?
+        .delegation()
+        .map(|delegation| delegation.voter_pubkey)
+        .ok_or(ErrorCode::InvalidStakeAccount)?;
+    if v_list.is_eligible(&validator, &pool.params) {
+        return Err(ErrorCode::ValidatorStillEligible.into());
+    }
+
+    deactivate_stake(CpiContext::new_with_signer(
+        stake_program.to_account_info(),
+        DeactivateStakeAccount {
+            stake: stake_account.to_account_info(),
+            staker: pool.to_account_info(),
+            clock: clock.to_account_info(),
+        },
+        &[&[Pool::SEED, pool.admin.as_ref(), &[pool.bump]]],
+    ))?;
+
+    emit!(ManagedStakeDeactivated {
+        pool: pool.key(),
+        stake_account: stake_account.key(),
+        validator,
+        lamports: stake_account.lamports(),
+    });
+
+    Ok(())
+}
@@ -3,7 +3,7 @@ use anchor_spl::stake::{
     deactivate_stake, DeactivateStake as DeactivateStakeAccount, Stake as StakeProgram,
 };
 
-use crate::Pool;
+use crate::{events::StakeDeactivated, Pool, StakeRegistry, StakeStatus};
 
 #[derive(Accounts)]
 pub struct DeactivateStake<'info> {
@@ -13,10 +13,19 @@ pub struct DeactivateStake<'info> {
     #[account(
         mut,
         seeds = [Pool::SEED, pool.admin.as_ref()],
-        bump
+        bump,
+        has_one = admin
     )]
     pub pool: Account<'info, Pool>,
 
+    #[account(
+        mut,
+        seeds = [StakeRegistry::SEED, pool.key().as_ref()],
+        bump = stake_registry.bump,
+        has_one = pool
+    )]
+    pub stake_registry: Box<Account<'info, StakeRegistry>>,
+
     /// CHECK:
     #[account(mut)]
     pub stake_account: AccountInfo<'info>,
@@ -30,8 +39,9 @@ pub struct DeactivateStake<'info> {
 
 pub fn handler<'info>(ctx: Context<'_, '_, '_, 'info, DeactivateStake>) -> Result<()> {
     let DeactivateStake {
-        admin,
+        admin: _,
         pool,
+        stake_registry,
         stake_account,
         clock,
         system_program: _,
@@ -44,11 +54,24 @@ pub fn handler<'info>(ctx: Context<'_, '_, '_, 'info, DeactivateStake>) -> Resul
         stake_program.to_account_info(),
         DeactivateStakeAccount {
             stake: stake_account.to_account_info(),
-            staker: admin.to_account_info(),
+            staker: pool.to_account_info(),
             clock: clock.to_account_info(),
         },
         &[&[Pool::SEED, pool.admin.as_ref(), &[pool.bump]]],
     ))?;
 
+    let entry = stake_registry.get_mut(stake_account.key)?;
+    let old_status = entry.status;
+    entry.status = StakeStatus::Deactivating;
+
+    emit!(StakeDeactivated {
+        pool: pool.key(),
+        stake_account: stake_account.key(),
+        validator: entry.vote_account,
+        lamports: entry.lamports,
+        old_status,
+        new_status: entry.status,
+    });
+
     Ok(())
 }
@@ -0,0 +1,88 @@
+use anchor_lang::{
+    prelude::*,
+    solana_program::{stake, stake::state::StakeState},
+};
+use anchor_spl::stake::{Stake as StakeProgram, StakeAccount};
+
+use crate::{utils::create_transient_stake, Pool, StakeRegistry};
+
+#[derive(Accounts)]
+#[instruction(lamports: u64, nonce: u64)]
+pub struct DecreaseStake<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [Pool::SEED, pool.admin.as_ref()],
+        bump,
+        has_one = admin
+    )]
+    pub pool: Account<'info, Pool>,
+
+    #[account(
+        mut,
+        seeds = [StakeRegistry::SEED, pool.key().as_ref()],
+        bump = stake_registry.bump,
+        has_one = pool
+    )]
+    pub stake_registry: Box<Account<'info, StakeRegistry>>,
+
+    /// CHECK: checked against the stake registry
+    #[account(mut)]
+    pub stake_account: AccountInfo<'info>,
+
+    /// CHECK: checked to be the validator `stake_account` is delegated to
+    pub validator_vote: AccountInfo<'info>,
+
+    // transient stake account cooling down before it is withdrawn to the reserve
+    #[account(
+        init,
+        payer = admin,
+        space = std::mem::size_of::<StakeState>(),
+        owner = stake::program::ID,
+        seeds = [
+            Pool::TRANSIENT_SEED,
+            pool.key().as_ref(),
+            validator_vote.key().as_ref(),
+            &nonce.to_le_bytes()
+        ],
+        bump
+    )]
+    pub transient_stake_account: Account<'info, StakeAccount>,
+
+    pub clock: Sysvar<'info, Clock>,
+
+    pub system_program: Program<'info, System>,
+
+    pub stake_program: Program<'info, StakeProgram>,
+}
+
+/// Starts returning `lamports` of delegated stake to the reserve through a deactivated transient
+/// stake account, withdrawn by `finalize_transient_stake` once it has cooled down.
+pub fn handler(ctx: Context<DecreaseStake>, lamports: u64, _nonce: u64) -> Result<()> {
+    let DecreaseStake {
+        pool,
+        stake_registry,
+        stake_account,
+        validator_vote,
+        transient_stake_account,
+        clock,
+        stake_program,
+        ..
+    } = ctx.accounts;
+
+    msg!("Decreasing stake by {} lamports", lamports);
+
+    create_transient_stake(
+        pool,
+        stake_registry,
+        stake_account,
+        validator_vote.key,
+        &transient_stake_account.to_account_info(),
+        &clock.to_account_info(),
+        &stake_program.to_account_info(),
+        lamports,
+        Pubkey::default(),
+    )
+}
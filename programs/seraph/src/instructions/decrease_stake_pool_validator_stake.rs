@@ -0,0 +1,128 @@
+use anchor_lang::{
+    prelude::*,
+    solana_program::{program::invoke_signed, sysvar::stake_history},
+};
+use anchor_spl::stake::Stake as StakeProgram;
+
+use crate::{
+    error::ErrorCode,
+    events::StakePoolStakeDecreased,
+    spl_stake_pool::{self, SplStakePool},
+    utils::get_stake_account,
+    Pool,
+};
+
+#[derive(Accounts)]
+pub struct DecreaseStakePoolValidatorStake<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [Pool::SEED, pool.admin.as_ref()],
+        bump,
+        has_one = admin
+    )]
+    pub pool: Account<'info, Pool>,
+
+    /// CHECK: the stake pool linked with `set_stake_pool`
+    #[account(constraint = stake_pool.key() == pool.stake_pool @ ErrorCode::InvalidStakePool)]
+    pub stake_pool: UncheckedAccount<'info>,
+
+    /// CHECK: checked by the stake pool program
+    pub withdraw_authority: UncheckedAccount<'info>,
+
+    /// CHECK: checked by the stake pool program
+    #[account(mut)]
+    pub validator_list: UncheckedAccount<'info>,
+
+    /// CHECK: checked by the stake pool program
+    #[account(mut)]
+    pub reserve_stake: UncheckedAccount<'info>,
+
+    /// CHECK: checked by the stake pool program to be the validator's stake account
+    #[account(mut)]
+    pub validator_stake: UncheckedAccount<'info>,
+
+    /// CHECK: created by the stake pool program at its derived address
+    #[account(mut)]
+    pub transient_stake: UncheckedAccount<'info>,
+
+    pub clock: Sysvar<'info, Clock>,
+
+    /// CHECK:
+    #[account(address = stake_history::ID)]
+    pub stake_history: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+
+    pub stake_program: Program<'info, StakeProgram>,
+
+    pub spl_stake_pool_program: Program<'info, SplStakePool>,
+}
+
+/// Starts moving `lamports` of a validator's stake in the linked stake pool back to its reserve,
+/// through a deactivated transient stake account. Like `decrease_stake`, this is allowed while
+/// paused and on eligible validators, so the admin can rebalance and wind down.
+pub fn handler(
+    ctx: Context<DecreaseStakePoolValidatorStake>,
+    lamports: u64,
+    transient_stake_seed: u64,
+) -> Result<()> {
+    let DecreaseStakePoolValidatorStake {
+        pool,
+        stake_pool,
+        withdraw_authority,
+        validator_list,
+        reserve_stake,
+        validator_stake,
+        transient_stake,
+        clock,
+        stake_history,
+        system_program,
+        stake_program,
+        spl_stake_pool_program,
+        ..
+    } = ctx.accounts;
+
+    let validator = get_stake_account(validator_stake)?
+        .delegation()
+        .map(|delegation| delegation.voter_pubkey)
+        .ok_or(ErrorCode::InvalidStakeAccount)?;
+
+    invoke_signed(
+        &spl_stake_pool::decrease_validator_stake(
+            stake_pool.key,
+            &pool.key(),
+            withdraw_authority.key,
+            validator_list.key,
+            reserve_stake.key,
+            validator_stake.key,
+            transient_stake.key,
+            lamports,
+            transient_stake_seed,
+        ),
+        &[
+            spl_stake_pool_program.to_account_info(),
+            stake_pool.to_account_info(),
+            pool.to_account_info(),
+            withdraw_authority.to_account_info(),
+            validator_list.to_account_info(),
+            reserve_stake.to_account_info(),
+            validator_stake.to_account_info(),
+            transient_stake.to_account_info(),
+            clock.to_account_info(),
+            stake_history.to_account_info(),
+            system_program.to_account_info(),
+            stake_program.to_account_info(),
+        ],
+        &[&[Pool::SEED, pool.admin.as_ref(), &[pool.bump]]],
+    )?;
+
+    emit!(StakePoolStakeDecreased {
+        pool: pool.key(),
+        stake_pool: stake_pool.key(),
+        validator,
+        lamports,
+    });
+
+    Ok(())
+}
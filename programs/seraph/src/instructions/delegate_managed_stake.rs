@@ -0,0 +1,110 @@
+use anchor_lang::{
+    prelude::*,
+    solana_program::{
+        program::invoke_signed,
+        stake::{self, instruction::delegate_stake},
+        sysvar::stake_history,
+    },
+};
+use anchor_spl::stake::Stake as StakeProgram;
+
+use crate::{
+    error::ErrorCode, events::ManagedStakeDelegated, utils::get_stake_account, ManagedStake, Pool,
+    VList,
+};
+
+#[derive(Accounts)]
+pub struct DelegateManagedStake<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [Pool::SEED, pool.admin.as_ref()],
+        bump,
+        has_one = admin,
+        constraint = !pool.paused @ ErrorCode::PoolPaused
+    )]
+    pub pool: Account<'info, Pool>,
+
+    #[account(
+        seeds = [VList::SEED, pool.admin.as_ref(), pool.key().as_ref()],
+        bump = v_list.bump,
+        has_one = pool
+    )]
+    pub v_list: Box<Account<'info, VList>>,
+
+    #[account(
+        seeds = [ManagedStake::SEED, pool.key().as_ref(), stake_account.key().as_ref()],
+        bump = managed_stake.bump,
+        has_one = pool,
+        has_one = stake_account
+    )]
+    pub managed_stake: Account<'info, ManagedStake>,
+
+    /// CHECK: checked by the stake program to have the pool as staker
+    #[account(mut)]
+    pub stake_account: AccountInfo<'info>,
+
+    /// CHECK: checked to be an eligible validator in the VList
+    pub validator_vote: AccountInfo<'info>,
+
+    pub clock: Sysvar<'info, Clock>,
+
+    /// CHECK:
+    #[account(address = stake_history::ID)]
+    pub stake_history: UncheckedAccount<'info>,
+
+    /// CHECK:
+    #[account(address = stake::config::ID)]
+    pub stake_config: UncheckedAccount<'info>,
+
+    pub stake_program: Program<'info, StakeProgram>,
+}
+
+/// Delegates a managed stake account to a validator in the top percentile of the VList. The
+/// account must be inactive, so stake on a validator that dropped out of the list is first
+/// deactivated with `deactivate_managed_stake`.
+pub fn handler(ctx: Context<DelegateManagedStake>) -> Result<()> {
+    let DelegateManagedStake {
+        pool,
+        v_list,
+        stake_account,
+        validator_vote,
+        clock,
+        stake_history,
+        stake_config,
+        stake_program,
+        ..
+    } = ctx.accounts;
+
+    if !v_list.is_eligible(validator_vote.key, &pool.params) {
+        return Err(ErrorCode::ValidatorNotInList.into());
+    }
+    let old_validator = get_stake_account(stake_account)?
+        .delegation()
+        .map(|delegation| delegation.voter_pubkey)
+        .unwrap_or_default();
+
+    invoke_signed(
+        &delegate_stake(&stake_account.key(), &pool.key(), validator_vote.key),
+        &[
+            stake_program.to_account_info(),
+            stake_account.to_account_info(),
+            pool.to_account_info(),
+            validator_vote.to_account_info(),
+            clock.to_account_info(),
+            stake_history.to_account_info(),
+            stake_config.to_account_info(),
+        ],
+        &[&[Pool::SEED, pool.admin.as_ref(), &[pool.bump]]],
+    )?;
+
+    emit!(ManagedStakeDelegated {
+        pool: pool.key(),
+        stake_account: stake_account.key(),
+        old_validator,
+        new_validator: validator_vote.key(),
+        lamports: stake_account.lamports(),
+    });
+
+    Ok(())
+}
@@ -8,7 +8,7 @@ use anchor_lang::{
 };
 use anchor_spl::stake::Stake as StakeProgram;
 
-use crate::Pool;
+use crate::{error::ErrorCode, events::StakeDelegated, Pool, StakeRegistry, StakeStatus};
 
 #[derive(Accounts)]
 pub struct DelegateStake<'info> {
@@ -18,10 +18,20 @@ pub struct DelegateStake<'info> {
     #[account(
         mut,
         seeds = [Pool::SEED, pool.admin.as_ref()],
-        bump
+        bump,
+        has_one = admin,
+        constraint = !pool.paused @ ErrorCode::PoolPaused
     )]
     pub pool: Account<'info, Pool>,
 
+    #[account(
+        mut,
+        seeds = [StakeRegistry::SEED, pool.key().as_ref()],
+        bump = stake_registry.bump,
+        has_one = pool
+    )]
+    pub stake_registry: Box<Account<'info, StakeRegistry>>,
+
     pub clock: Sysvar<'info, Clock>,
 
     /// CHECK:
@@ -46,8 +56,8 @@ pub struct DelegateStake<'info> {
 
 pub fn handler<'info>(ctx: Context<'_, '_, '_, 'info, DelegateStake>) -> Result<()> {
     let DelegateStake {
-        admin,
         pool,
+        stake_registry,
         stake_account,
         stake_config,
         stake_history,
@@ -57,14 +67,21 @@ pub fn handler<'info>(ctx: Context<'_, '_, '_, 'info, DelegateStake>) -> Result<
         ..
     } = ctx.accounts;
 
+    let old_entry = *stake_registry
+        .get(stake_account.key)
+        .ok_or(ErrorCode::StakeAccountNotRegistered)?;
+    if old_entry.is_transient {
+        return Err(ErrorCode::TransientStakeInFlight.into());
+    }
+
     msg!("Delegating stake");
 
     invoke_signed(
-        &delegate_stake(&stake_account.key(), admin.key, validator_vote.key),
+        &delegate_stake(&stake_account.key(), &pool.key(), validator_vote.key),
         &[
             stake_program.to_account_info(),
             stake_account.to_account_info(),
-            admin.to_account_info(),
+            pool.to_account_info(),
             validator_vote.to_account_info(),
             clock.to_account_info(),
             stake_history.to_account_info(),
@@ -73,5 +90,20 @@ pub fn handler<'info>(ctx: Context<'_, '_, '_, 'info, DelegateStake>) -> Result<
         &[&[Pool::SEED, pool.admin.as_ref(), &[pool.bump]]],
     )?;
 
+    let entry = stake_registry.get_mut(stake_account.key)?;
+    entry.vote_account = validator_vote.key();
+    entry.lamports = stake_account.lamports();
+    entry.status = StakeStatus::Activating;
+
+    emit!(StakeDelegated {
+        pool: pool.key(),
+        stake_account: stake_account.key(),
+        old_validator: old_entry.vote_account,
+        new_validator: entry.vote_account,
+        lamports: entry.lamports,
+        old_status: old_entry.status,
+        new_status: entry.status,
+    });
+
     Ok(())
 }
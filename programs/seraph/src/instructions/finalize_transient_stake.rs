@@ -0,0 +1,168 @@
+use anchor_lang::{
+    prelude::*,
+    solana_program::{
+        program::invoke_signed,
+        stake::{self, instruction::delegate_stake},
+        stake_history::StakeHistory,
+    },
+};
+use anchor_spl::stake::{withdraw, Stake as StakeProgram, Withdraw};
+
+use crate::{
+    error::ErrorCode,
+    events::{StakeDelegated, StakeWithdrawn},
+    utils::{get_pool_stake_account, get_stake_status},
+    Pool, StakeRegistry, StakeStatus,
+};
+
+#[derive(Accounts)]
+pub struct FinalizeTransientStake<'info> {
+    #[account(
+        seeds = [Pool::SEED, pool.admin.as_ref()],
+        bump
+    )]
+    pub pool: Account<'info, Pool>,
+
+    #[account(
+        mut,
+        seeds = [StakeRegistry::SEED, pool.key().as_ref()],
+        bump = stake_registry.bump,
+        has_one = pool
+    )]
+    pub stake_registry: Box<Account<'info, StakeRegistry>>,
+
+    /// CHECK: checked against the stake registry
+    #[account(mut)]
+    pub transient_stake_account: AccountInfo<'info>,
+
+    /// CHECK: checked against the transient account's target when it is redelegated
+    pub validator_vote: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [Pool::RESERVE_SEED, pool.key().as_ref()],
+        bump = pool.reserve_bump
+    )]
+    pub reserve: SystemAccount<'info>,
+
+    pub clock: Sysvar<'info, Clock>,
+
+    pub stake_history: Sysvar<'info, StakeHistory>,
+
+    /// CHECK:
+    #[account(address = stake::config::ID)]
+    pub stake_config: UncheckedAccount<'info>,
+
+    pub stake_program: Program<'info, StakeProgram>,
+
+    pub signer: Signer<'info>,
+}
+
+/// Permissionless crank completing a cooled-down transient stake account: it is delegated to the
+/// validator chosen by `redelegate_stake`, or withdrawn to the reserve after `decrease_stake`.
+/// Only withdrawals to the reserve complete while the pool is paused.
+pub fn handler(ctx: Context<FinalizeTransientStake>) -> Result<()> {
+    let FinalizeTransientStake {
+        pool,
+        stake_registry,
+        transient_stake_account,
+        validator_vote,
+        reserve,
+        clock,
+        stake_history,
+        stake_config,
+        stake_program,
+        ..
+    } = ctx.accounts;
+
+    let entry = *stake_registry
+        .get(transient_stake_account.key)
+        .ok_or(ErrorCode::StakeAccountNotRegistered)?;
+    if !entry.is_transient {
+        return Err(ErrorCode::NotTransientStake.into());
+    }
+    let stake = get_pool_stake_account(&pool.key(), transient_stake_account)?;
+    if get_stake_status(&stake, clock.epoch, stake_history) != StakeStatus::Inactive {
+        return Err(ErrorCode::StakeNotInactive.into());
+    }
+
+    if entry.transient_vote_account == Pubkey::default() {
+        let lamports = transient_stake_account.lamports();
+        let old_reserve_lamports = Pool::reserve_lamports(reserve)?;
+
+        msg!("Withdrawing {} transient lamports to the reserve", lamports);
+
+        withdraw(
+            CpiContext::new_with_signer(
+                stake_program.to_account_info(),
+                Withdraw {
+                    stake: transient_stake_account.to_account_info(),
+                    withdrawer: pool.to_account_info(),
+                    to: reserve.to_account_info(),
+                    clock: clock.to_account_info(),
+                    stake_history: stake_history.to_account_info(),
+                },
+                &[&[Pool::SEED, pool.admin.as_ref(), &[pool.bump]]],
+            ),
+            lamports,
+            None,
+        )?;
+
+        stake_registry.remove(transient_stake_account.key)?;
+
+        emit!(StakeWithdrawn {
+            pool: pool.key(),
+            stake_account: transient_stake_account.key(),
+            lamports,
+            old_reserve_lamports,
+            new_reserve_lamports: Pool::reserve_lamports(reserve)?,
+        });
+        return Ok(());
+    }
+
+    // completing a redelegation stakes again, which a paused pool does not allow
+    if pool.paused {
+        return Err(ErrorCode::PoolPaused.into());
+    }
+    if entry.transient_vote_account != validator_vote.key() {
+        return Err(ErrorCode::InvalidValidatorVote.into());
+    }
+
+    msg!("Delegating transient stake");
+
+    invoke_signed(
+        &delegate_stake(transient_stake_account.key, &pool.key(), validator_vote.key),
+        &[
+            stake_program.to_account_info(),
+            transient_stake_account.to_account_info(),
+            pool.to_account_info(),
+            validator_vote.to_account_info(),
+            clock.to_account_info(),
+            stake_history.to_account_info(),
+            stake_config.to_account_info(),
+        ],
+        &[&[Pool::SEED, pool.admin.as_ref(), &[pool.bump]]],
+    )?;
+
+    let entry = stake_registry.get_mut(transient_stake_account.key)?;
+    entry.vote_account = validator_vote.key();
+    entry.lamports = transient_stake_account.lamports();
+    entry.status = StakeStatus::Activating;
+    entry.is_transient = false;
+    entry.transient_vote_account = Pubkey::default();
+
+    emit!(StakeDelegated {
+        pool: pool.key(),
+        stake_account: transient_stake_account.key(),
+        old_validator: stake
+            .delegation()
+            .map(|delegation| delegation.voter_pubkey)
+            .unwrap_or_default(),
+        new_validator: entry.vote_account,
+        lamports: entry.lamports,
+        old_status: StakeStatus::Inactive,
+        new_status: entry.status,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,66 @@
+use anchor_lang::prelude::*;
+
+use crate::{Pool, VList, MAX_TOP_VALIDATORS, TOP_VALIDATORS_VERSION};
+
+#[derive(Accounts)]
+pub struct GetTopValidators<'info> {
+    #[account(
+        seeds = [Pool::SEED, pool.admin.as_ref()],
+        bump
+    )]
+    pub pool: Account<'info, Pool>,
+
+    #[account(
+        seeds = [VList::SEED, pool.admin.as_ref(), pool.key().as_ref()],
+        bump = v_list.bump,
+        has_one = pool
+    )]
+    pub v_list: Box<Account<'info, VList>>,
+}
+
+/// Return data of `get_top_validators`. Fields are only ever appended, and `version` is bumped
+/// when they are, so consumers can keep decoding older layouts.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, Debug)]
+pub struct TopValidators {
+    pub version: u8,
+    pub pool: Pubkey,
+    pub epoch: u64,
+    // Validators in the VList, of which the pool's percentile is eligible
+    pub list_len: u32,
+    // Highest score first
+    pub validators: Vec<TopValidator>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct TopValidator {
+    pub validator: Pubkey,
+    pub score: u32,
+    pub last_scored_epoch: u64,
+}
+
+/// Read-only view of the pool's eligible validators, the top percentile of the VList, returned
+/// through `set_return_data` so other programs can consume it by CPI. At most MAX_TOP_VALIDATORS
+/// are returned, keeping the data under the return data limit.
+pub fn handler(ctx: Context<GetTopValidators>, limit: u8) -> Result<TopValidators> {
+    let GetTopValidators { pool, v_list } = ctx.accounts;
+
+    let count = (limit as usize)
+        .min(MAX_TOP_VALIDATORS)
+        .min(pool.params.eligible_validators(v_list.idx));
+    let validators = v_list.validators[0..count]
+        .iter()
+        .map(|entry| TopValidator {
+            validator: entry.validator,
+            score: entry.score,
+            last_scored_epoch: entry.last_scored_epoch,
+        })
+        .collect();
+
+    Ok(TopValidators {
+        version: TOP_VALIDATORS_VERSION,
+        pool: pool.key(),
+        epoch: Clock::get()?.epoch,
+        list_len: v_list.idx as u32,
+        validators,
+    })
+}
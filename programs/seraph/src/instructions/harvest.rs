@@ -0,0 +1,113 @@
+use anchor_lang::{prelude::*, solana_program::stake_history::StakeHistory};
+use anchor_spl::stake::{withdraw, Stake as StakeProgram, Withdraw};
+
+use crate::{
+    error::ErrorCode, events::MevHarvested, utils::get_pool_stake_account, Pool, StakeRegistry,
+};
+
+#[derive(Accounts)]
+pub struct Harvest<'info> {
+    #[account(
+        mut,
+        seeds = [Pool::SEED, pool.admin.as_ref()],
+        bump
+    )]
+    pub pool: Account<'info, Pool>,
+
+    #[account(
+        mut,
+        seeds = [StakeRegistry::SEED, pool.key().as_ref()],
+        bump = stake_registry.bump,
+        has_one = pool
+    )]
+    pub stake_registry: Box<Account<'info, StakeRegistry>>,
+
+    /// CHECK: checked against the stake registry
+    #[account(mut)]
+    pub stake_account: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [Pool::RESERVE_SEED, pool.key().as_ref()],
+        bump = pool.reserve_bump
+    )]
+    pub reserve: SystemAccount<'info>,
+
+    pub clock: Sysvar<'info, Clock>,
+
+    pub stake_history: Sysvar<'info, StakeHistory>,
+
+    pub stake_program: Program<'info, StakeProgram>,
+}
+
+/// Sweeps lamports sitting above a delegated stake account's stake and rent-exempt reserve, such
+/// as claimed MEV tips, into the pool reserve. They are booked as MEV rewards by the next balance
+/// update. Inflation rewards are added to the delegation itself, so they are never swept.
+/// Allowed while paused, as it only withdraws to the reserve.
+pub fn handler(ctx: Context<Harvest>) -> Result<()> {
+    let Harvest {
+        pool,
+        stake_registry,
+        stake_account,
+        reserve,
+        clock,
+        stake_history,
+        stake_program,
+    } = ctx.accounts;
+
+    let entry = stake_registry
+        .get(stake_account.key)
+        .ok_or(ErrorCode::StakeAccountNotRegistered)?;
+    if entry.is_transient {
+        return Err(ErrorCode::TransientStakeInFlight.into());
+    }
+    let stake = get_pool_stake_account(&pool.key(), stake_account)?;
+    let (Some(meta), Some(delegation)) = (stake.meta(), stake.delegation()) else {
+        return Err(ErrorCode::InvalidStakeAccount.into());
+    };
+    let lamports = stake_account
+        .lamports()
+        .saturating_sub(delegation.stake)
+        .saturating_sub(meta.rent_exempt_reserve);
+    if lamports == 0 {
+        return Err(ErrorCode::NothingToHarvest.into());
+    }
+
+    let old_reserve_lamports = Pool::reserve_lamports(reserve)?;
+
+    msg!("Harvesting {} lamports into the reserve", lamports);
+
+    withdraw(
+        CpiContext::new_with_signer(
+            stake_program.to_account_info(),
+            Withdraw {
+                stake: stake_account.to_account_info(),
+                withdrawer: pool.to_account_info(),
+                to: reserve.to_account_info(),
+                clock: clock.to_account_info(),
+                stake_history: stake_history.to_account_info(),
+            },
+            &[&[Pool::SEED, pool.admin.as_ref(), &[pool.bump]]],
+        ),
+        lamports,
+        None,
+    )?;
+
+    let entry = stake_registry.get_mut(stake_account.key)?;
+    entry.lamports = stake_account.lamports();
+    pool.harvested_mev_lamports = pool
+        .harvested_mev_lamports
+        .checked_add(lamports)
+        .ok_or(ErrorCode::ArithmeticError)?;
+
+    emit!(MevHarvested {
+        pool: pool.key(),
+        stake_account: stake_account.key(),
+        validator: delegation.voter_pubkey,
+        lamports,
+        old_reserve_lamports,
+        new_reserve_lamports: Pool::reserve_lamports(reserve)?,
+    });
+
+    Ok(())
+}
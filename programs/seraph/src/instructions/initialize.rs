@@ -1,6 +1,10 @@
-use anchor_lang::prelude::*;
+use anchor_lang::{
+    prelude::*,
+    system_program::{transfer, Transfer},
+};
+use anchor_spl::token::{initialize_account3, InitializeAccount3, Token, TokenAccount};
 
-use crate::{Pool, VList};
+use crate::{utils::init_pool_mint, Pool, PoolParams, VList};
 
 #[derive(Accounts)]
 pub struct Initialize<'info> {
@@ -25,28 +29,100 @@ pub struct Initialize<'info> {
     )]
     pub v_list: Account<'info, VList>,
 
+    /// CHECK: created below as a mint whose authority is the pool, unless a closed pool left it
+    #[account(
+        mut,
+        seeds = [Pool::MINT_SEED, pool.key().as_ref()],
+        bump
+    )]
+    pub pool_mint: UncheckedAccount<'info>,
+
+    /// CHECK: initialized below as a pool token account owned by the admin
+    #[account(
+        init,
+        payer = admin,
+        space = TokenAccount::LEN,
+        owner = token_program.key(),
+        seeds = [Pool::MANAGER_FEE_SEED, pool.key().as_ref()],
+        bump
+    )]
+    pub manager_fee_account: UncheckedAccount<'info>,
+
+    // funded with its rent-exempt minimum below
+    #[account(
+        mut,
+        seeds = [Pool::RESERVE_SEED, pool.key().as_ref()],
+        bump
+    )]
+    pub reserve: SystemAccount<'info>,
+
+    pub token_program: Program<'info, Token>,
+
     pub system_program: Program<'info, System>,
 }
 
-pub fn handler(ctx: Context<Initialize>) -> Result<()> {
+pub fn handler(ctx: Context<Initialize>, params: PoolParams) -> Result<()> {
     let Initialize {
         admin, 
         pool, 
         v_list,
-        system_program: _
+        pool_mint,
+        manager_fee_account,
+        reserve,
+        token_program,
+        system_program
     } = ctx.accounts;
 
     // get meta
     let clock = Clock::get()?;
     let pool_bump = *ctx.bumps.get("pool").unwrap();
     let v_list_bump= *ctx.bumps.get("v_list").unwrap();
+    let reserve_bump = *ctx.bumps.get("reserve").unwrap();
+
+    // init pool token mint and the manager's fee account
+    init_pool_mint(
+        &admin.to_account_info(),
+        &pool.key(),
+        &pool_mint.to_account_info(),
+        *ctx.bumps.get("pool_mint").unwrap(),
+        &token_program.to_account_info(),
+        &system_program.to_account_info(),
+    )?;
+
+    initialize_account3(CpiContext::new(
+        token_program.to_account_info(),
+        InitializeAccount3 {
+            account: manager_fee_account.to_account_info(),
+            mint: pool_mint.to_account_info(),
+            authority: admin.to_account_info(),
+        },
+    ))?;
+
+    // keep the reserve rent-exempt so any amount can be withdrawn into it
+    let reserve_rent = Rent::get()?.minimum_balance(0).saturating_sub(reserve.lamports());
+    if reserve_rent > 0 {
+        transfer(
+            CpiContext::new(
+                system_program.to_account_info(),
+                Transfer {
+                    from: admin.to_account_info(),
+                    to: reserve.to_account_info(),
+                },
+            ),
+            reserve_rent,
+        )?;
+    }
 
     // init accounts
     pool.init(
         admin.key, 
         clock.slot, 
         clock.epoch, 
-        pool_bump
+        pool_bump,
+        reserve_bump,
+        pool_mint.key(),
+        manager_fee_account.key(),
+        params,
     )?;
 
     v_list.init(admin.key,pool.key(), v_list_bump)?;
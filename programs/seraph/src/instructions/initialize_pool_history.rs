@@ -0,0 +1,38 @@
+use anchor_lang::prelude::*;
+
+use crate::{error::ErrorCode, Pool, PoolHistory};
+
+#[derive(Accounts)]
+pub struct InitializePoolHistory<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [Pool::SEED, pool.admin.as_ref()],
+        bump,
+        has_one = admin,
+        constraint = !pool.paused @ ErrorCode::PoolPaused
+    )]
+    pub pool: Account<'info, Pool>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = PoolHistory::SIZE,
+        seeds = [PoolHistory::SEED, pool.key().as_ref()],
+        bump
+    )]
+    pub pool_history: AccountLoader<'info, PoolHistory>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<InitializePoolHistory>) -> Result<()> {
+    let mut pool_history = ctx.accounts.pool_history.load_init()?;
+
+    pool_history.pool = ctx.accounts.pool.key();
+    pool_history.bump = *ctx.bumps.get("pool_history").unwrap();
+    pool_history.history.init();
+
+    Ok(())
+}
@@ -0,0 +1,35 @@
+use anchor_lang::prelude::*;
+
+use crate::{error::ErrorCode, Pool, StakeRegistry};
+
+#[derive(Accounts)]
+pub struct InitializeStakeRegistry<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [Pool::SEED, pool.admin.as_ref()],
+        bump,
+        has_one = admin,
+        constraint = !pool.paused @ ErrorCode::PoolPaused
+    )]
+    pub pool: Account<'info, Pool>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = StakeRegistry::SIZE,
+        seeds = [StakeRegistry::SEED, pool.key().as_ref()],
+        bump
+    )]
+    pub stake_registry: Box<Account<'info, StakeRegistry>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<InitializeStakeRegistry>) -> Result<()> {
+    let pool = ctx.accounts.pool.key();
+    let bump = *ctx.bumps.get("stake_registry").unwrap();
+
+    ctx.accounts.stake_registry.init(pool, bump)
+}
@@ -0,0 +1,126 @@
+use anchor_lang::{
+    prelude::*,
+    system_program::{transfer, Transfer},
+};
+use anchor_spl::token::{burn, Burn, Mint, Token, TokenAccount};
+
+use crate::{error::ErrorCode, events::InstantUnstaked, Pool, BASIS_POINTS};
+
+#[derive(Accounts)]
+pub struct InstantUnstake<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [Pool::SEED, pool.admin.as_ref()],
+        bump,
+        has_one = pool_mint
+    )]
+    pub pool: Account<'info, Pool>,
+
+    #[account(mut)]
+    pub pool_mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        token::mint = pool_mint,
+        token::authority = owner
+    )]
+    pub pool_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [Pool::RESERVE_SEED, pool.key().as_ref()],
+        bump = pool.reserve_bump
+    )]
+    pub reserve: SystemAccount<'info>,
+
+    pub token_program: Program<'info, Token>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Burns `pool_tokens` and pays the lamports they redeem at the exchange rate of this epoch's
+/// balance update straight from the reserve, less a fee that rises as the unstake drains the
+/// reserve below its target. The fee stays in the pool. Lamports owed to withdrawal tickets are
+/// not available. Allowed while paused, so depositors can always leave.
+pub fn handler(ctx: Context<InstantUnstake>, pool_tokens: u64, min_lamports: u64) -> Result<()> {
+    let InstantUnstake {
+        owner,
+        pool,
+        pool_mint,
+        pool_token_account,
+        reserve,
+        token_program,
+        system_program,
+    } = ctx.accounts;
+
+    pool.check_balance_fresh(Clock::get()?.epoch)?;
+    let lamports = pool
+        .lamports_for_pool_tokens(pool_tokens, pool_mint.supply)
+        .ok_or(ErrorCode::InvalidWithdrawalAmount)?;
+    if lamports == 0 {
+        return Err(ErrorCode::InvalidWithdrawalAmount.into());
+    }
+
+    let old_reserve_lamports = Pool::reserve_lamports(reserve)?;
+    let available = old_reserve_lamports.saturating_sub(pool.pending_withdrawal_lamports);
+    if lamports > available {
+        return Err(ErrorCode::InsufficientReserve.into());
+    }
+
+    let params = pool.reserve_params;
+    let fee_bps = params.fee_bps(
+        available - lamports,
+        params.target_reserve_lamports(pool.total_lamports),
+    );
+    let fee_lamports = ((lamports as u128) * (fee_bps as u128) / (BASIS_POINTS as u128)) as u64;
+    let payout = lamports - fee_lamports;
+    if payout < min_lamports {
+        return Err(ErrorCode::InstantUnstakeBelowMinimum.into());
+    }
+
+    burn(
+        CpiContext::new(
+            token_program.to_account_info(),
+            Burn {
+                mint: pool_mint.to_account_info(),
+                from: pool_token_account.to_account_info(),
+                authority: owner.to_account_info(),
+            },
+        ),
+        pool_tokens,
+    )?;
+
+    let pool_key = pool.key();
+    transfer(
+        CpiContext::new_with_signer(
+            system_program.to_account_info(),
+            Transfer {
+                from: reserve.to_account_info(),
+                to: owner.to_account_info(),
+            },
+            &[&[Pool::RESERVE_SEED, pool_key.as_ref(), &[pool.reserve_bump]]],
+        ),
+        payout,
+    )?;
+
+    pool.total_lamports = pool
+        .total_lamports
+        .checked_sub(payout)
+        .ok_or(ErrorCode::ArithmeticError)?;
+
+    emit!(InstantUnstaked {
+        pool: pool_key,
+        owner: owner.key(),
+        pool_tokens,
+        lamports: payout,
+        fee_lamports,
+        fee_bps,
+        old_reserve_lamports,
+        new_reserve_lamports: Pool::reserve_lamports(reserve)?,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,110 @@
+use anchor_lang::{
+    prelude::*,
+    solana_program::{
+        program::invoke_signed, stake::instruction::merge, stake_history::StakeHistory,
+    },
+};
+use anchor_spl::stake::Stake as StakeProgram;
+
+use crate::{error::ErrorCode, events::StakeMerged, Pool, StakeRegistry};
+
+#[derive(Accounts)]
+pub struct MergeStake<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [Pool::SEED, pool.admin.as_ref()],
+        bump,
+        has_one = admin,
+        constraint = !pool.paused @ ErrorCode::PoolPaused
+    )]
+    pub pool: Account<'info, Pool>,
+
+    #[account(
+        mut,
+        seeds = [StakeRegistry::SEED, pool.key().as_ref()],
+        bump = stake_registry.bump,
+        has_one = pool
+    )]
+    pub stake_registry: Box<Account<'info, StakeRegistry>>,
+
+    /// CHECK: checked against the stake registry
+    #[account(mut)]
+    pub destination_stake_account: AccountInfo<'info>,
+
+    /// CHECK: checked against the stake registry, drained and closed by the merge
+    #[account(mut)]
+    pub source_stake_account: AccountInfo<'info>,
+
+    pub clock: Sysvar<'info, Clock>,
+
+    pub stake_history: Sysvar<'info, StakeHistory>,
+
+    pub stake_program: Program<'info, StakeProgram>,
+}
+
+/// Merges a registered stake account into another. The stake program rejects merges between
+/// accounts with mismatched delegations or activation states.
+pub fn handler(ctx: Context<MergeStake>) -> Result<()> {
+    let MergeStake {
+        pool,
+        stake_registry,
+        destination_stake_account,
+        source_stake_account,
+        clock,
+        stake_history,
+        stake_program,
+        ..
+    } = ctx.accounts;
+
+    if destination_stake_account.key == source_stake_account.key {
+        return Err(ErrorCode::DuplicateStakeAccount.into());
+    }
+    for stake_account in [destination_stake_account.key, source_stake_account.key] {
+        let entry = stake_registry
+            .get(stake_account)
+            .ok_or(ErrorCode::StakeAccountNotRegistered)?;
+        if entry.is_transient {
+            return Err(ErrorCode::TransientStakeInFlight.into());
+        }
+    }
+
+    let merge_ix = merge(
+        destination_stake_account.key,
+        source_stake_account.key,
+        &pool.key(),
+    )
+    .last()
+    .unwrap()
+    .clone();
+
+    msg!("Merging stake");
+
+    invoke_signed(
+        &merge_ix,
+        &[
+            stake_program.to_account_info(),
+            destination_stake_account.to_account_info(),
+            source_stake_account.to_account_info(),
+            clock.to_account_info(),
+            stake_history.to_account_info(),
+            pool.to_account_info(),
+        ],
+        &[&[Pool::SEED, pool.admin.as_ref(), &[pool.bump]]],
+    )?;
+
+    stake_registry.remove(source_stake_account.key)?;
+    let entry = stake_registry.get_mut(destination_stake_account.key)?;
+    let old_lamports = entry.lamports;
+    entry.lamports = destination_stake_account.lamports();
+
+    emit!(StakeMerged {
+        pool: pool.key(),
+        destination_stake_account: destination_stake_account.key(),
+        source_stake_account: source_stake_account.key(),
+        old_lamports,
+        new_lamports: entry.lamports,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,154 @@
+use anchor_lang::{
+    prelude::*,
+    system_program::{transfer, Transfer},
+};
+use anchor_spl::token::{initialize_account3, InitializeAccount3, Token, TokenAccount};
+
+use crate::{
+    error::ErrorCode,
+    events::AccountMigrated,
+    utils::{create_pda_account, init_pool_mint, realloc_legacy_account},
+    Pool, PoolParams, PoolV0, POOL_VERSION,
+};
+
+#[derive(Accounts)]
+pub struct MigratePool<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    /// CHECK: written before versioning, so it cannot be deserialized as a Pool until migrated
+    #[account(
+        mut,
+        seeds = [Pool::SEED, admin.key().as_ref()],
+        bump,
+        owner = crate::ID
+    )]
+    pub pool: UncheckedAccount<'info>,
+
+    /// CHECK: created below as a mint whose authority is the pool, as `initialize` does
+    #[account(
+        mut,
+        seeds = [Pool::MINT_SEED, pool.key().as_ref()],
+        bump
+    )]
+    pub pool_mint: UncheckedAccount<'info>,
+
+    /// CHECK: created below as a pool token account owned by the admin
+    #[account(
+        mut,
+        seeds = [Pool::MANAGER_FEE_SEED, pool.key().as_ref()],
+        bump
+    )]
+    pub manager_fee_account: UncheckedAccount<'info>,
+
+    // funded with its rent-exempt minimum below
+    #[account(
+        mut,
+        seeds = [Pool::RESERVE_SEED, pool.key().as_ref()],
+        bump
+    )]
+    pub reserve: SystemAccount<'info>,
+
+    pub token_program: Program<'info, Token>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Converts a pool written before versioning, whose version byte reads 0, to the current layout
+/// in place. The old pool only held its admin, start and bump, so the pool mint, manager fee
+/// account and reserve are derived and created as `initialize` does, failing if an account
+/// already there cannot serve the pool. Every other field starts at its default. Allowed while
+/// paused, as a paused pool may need upgrading.
+pub fn handler(ctx: Context<MigratePool>) -> Result<()> {
+    let MigratePool {
+        admin,
+        pool,
+        pool_mint,
+        manager_fee_account,
+        reserve,
+        token_program,
+        system_program,
+    } = ctx.accounts;
+
+    let version = pool
+        .try_borrow_data()?
+        .get(Pool::VERSION_OFFSET)
+        .copied()
+        .ok_or(anchor_lang::error::ErrorCode::AccountDidNotDeserialize)?;
+    // only pools from before versioning have an older layout so far
+    match version {
+        0 => {}
+        POOL_VERSION => return Err(ErrorCode::AccountAlreadyMigrated.into()),
+        _ => return Err(anchor_lang::error::ErrorCode::AccountDidNotDeserialize.into()),
+    }
+    realloc_legacy_account::<Pool>(pool, admin, system_program, Pool::SIZE)?;
+    let old_pool = PoolV0::deserialize(&mut &pool.try_borrow_data()?[8..])?;
+
+    init_pool_mint(
+        &admin.to_account_info(),
+        &pool.key(),
+        &pool_mint.to_account_info(),
+        *ctx.bumps.get("pool_mint").unwrap(),
+        &token_program.to_account_info(),
+        &system_program.to_account_info(),
+    )?;
+
+    let pool_key = pool.key();
+    let manager_fee_bump = *ctx.bumps.get("manager_fee_account").unwrap();
+    create_pda_account(
+        &admin.to_account_info(),
+        &manager_fee_account.to_account_info(),
+        &system_program.to_account_info(),
+        TokenAccount::LEN,
+        token_program.key,
+        &[
+            Pool::MANAGER_FEE_SEED,
+            pool_key.as_ref(),
+            &[manager_fee_bump],
+        ],
+    )?;
+    initialize_account3(CpiContext::new(
+        token_program.to_account_info(),
+        InitializeAccount3 {
+            account: manager_fee_account.to_account_info(),
+            mint: pool_mint.to_account_info(),
+            authority: admin.to_account_info(),
+        },
+    ))?;
+
+    // keep the reserve rent-exempt so any amount can be withdrawn into it
+    let reserve_rent = Rent::get()?
+        .minimum_balance(0)
+        .saturating_sub(reserve.lamports());
+    if reserve_rent > 0 {
+        transfer(
+            CpiContext::new(
+                system_program.to_account_info(),
+                Transfer {
+                    from: admin.to_account_info(),
+                    to: reserve.to_account_info(),
+                },
+            ),
+            reserve_rent,
+        )?;
+    }
+
+    let mut migrated: Account<Pool> = Account::try_from(pool)?;
+    migrated.init(
+        &old_pool.admin,
+        old_pool.start_slot,
+        old_pool.start_epoch,
+        old_pool.bump,
+        *ctx.bumps.get("reserve").unwrap(),
+        pool_mint.key(),
+        manager_fee_account.key(),
+        PoolParams::default(),
+    )?;
+    emit!(AccountMigrated {
+        pool: pool.key(),
+        account: pool.key(),
+        old_version: 0,
+        new_version: POOL_VERSION,
+    });
+    migrated.exit(&crate::ID)
+}
@@ -0,0 +1,55 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    events::AccountMigrated, utils::realloc_legacy_account, Pool, VList, VListV1, VLIST_VERSION,
+};
+
+#[derive(Accounts)]
+pub struct MigrateVList<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    /// CHECK: only its address is used, so it may be migrated before or after the VList
+    #[account(seeds = [Pool::SEED, admin.key().as_ref()], bump)]
+    pub pool: UncheckedAccount<'info>,
+
+    /// CHECK: written before versioning, so it cannot be deserialized as a VList until migrated
+    #[account(
+        mut,
+        seeds = [VList::SEED, admin.key().as_ref(), pool.key().as_ref()],
+        bump,
+        owner = crate::ID
+    )]
+    pub v_list: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Converts a VList of an older layout to the current one in place, growing it by the version
+/// byte and reserved space if written before versioning, and by the score components of each
+/// entry, which stay zeroed until the validators are scored again.
+pub fn handler(ctx: Context<MigrateVList>) -> Result<()> {
+    let MigrateVList {
+        admin,
+        pool,
+        v_list,
+        system_program,
+    } = ctx.accounts;
+
+    realloc_legacy_account::<VList>(v_list, admin, system_program, VList::SIZE)?;
+
+    let old_v_list: Box<VListV1> = {
+        let data = v_list.try_borrow_data()?;
+        Box::new(VListV1::deserialize(&mut &data[8..])?)
+    };
+    emit!(AccountMigrated {
+        pool: pool.key(),
+        account: v_list.key(),
+        old_version: old_v_list.version,
+        new_version: VLIST_VERSION,
+    });
+    let migrated = Box::new(VList::from(old_v_list.as_ref()));
+    let mut data = v_list.try_borrow_mut_data()?;
+    let mut writer: &mut [u8] = &mut data;
+    migrated.try_serialize(&mut writer)
+}
@@ -1,11 +1,83 @@
+pub mod add_stake_account;
+pub mod add_stake_pool_validator;
 pub mod calculate_score;
+pub mod claim_ticket;
+pub mod close_pool;
+pub mod deactivate_managed_stake;
 pub mod deactivate_stake;
+pub mod decrease_stake;
+pub mod decrease_stake_pool_validator_stake;
+pub mod delegate_managed_stake;
 pub mod delegate_stake;
+pub mod finalize_transient_stake;
+pub mod get_top_validators;
+pub mod harvest;
+pub mod increase_stake_pool_validator_stake;
 pub mod initialize;
+pub mod initialize_pool_history;
+pub mod initialize_stake_registry;
+pub mod instant_unstake;
+pub mod merge_stake;
+pub mod migrate_pool;
+pub mod migrate_vlist;
+pub mod pause;
+pub mod process_unstake_queue;
 pub mod redelegate_stake;
+pub mod register_managed_stake;
+pub mod remove_stake_pool_validator;
+pub mod request_withdrawal;
+pub mod revoke_managed_stake;
+pub mod set_fees;
+pub mod set_guardian;
+pub mod set_rebalance_params;
+pub mod set_reserve_params;
+pub mod set_scoring_params;
+pub mod set_stake_pool;
+pub mod split_stake;
+pub mod unpause;
+pub mod update_params;
+pub mod update_pool_balance;
+pub mod update_pool_history;
+pub mod withdraw_stake;
 
+pub use add_stake_account::*;
+pub use add_stake_pool_validator::*;
 pub use calculate_score::*;
+pub use claim_ticket::*;
+pub use close_pool::*;
+pub use deactivate_managed_stake::*;
 pub use deactivate_stake::*;
+pub use decrease_stake::*;
+pub use decrease_stake_pool_validator_stake::*;
+pub use delegate_managed_stake::*;
 pub use delegate_stake::*;
+pub use finalize_transient_stake::*;
+pub use get_top_validators::*;
+pub use harvest::*;
+pub use increase_stake_pool_validator_stake::*;
 pub use initialize::*;
+pub use initialize_pool_history::*;
+pub use initialize_stake_registry::*;
+pub use instant_unstake::*;
+pub use merge_stake::*;
+pub use migrate_pool::*;
+pub use migrate_vlist::*;
+pub use pause::*;
+pub use process_unstake_queue::*;
 pub use redelegate_stake::*;
+pub use register_managed_stake::*;
+pub use remove_stake_pool_validator::*;
+pub use request_withdrawal::*;
+pub use revoke_managed_stake::*;
+pub use set_fees::*;
+pub use set_guardian::*;
+pub use set_rebalance_params::*;
+pub use set_reserve_params::*;
+pub use set_scoring_params::*;
+pub use set_stake_pool::*;
+pub use split_stake::*;
+pub use unpause::*;
+pub use update_params::*;
+pub use update_pool_balance::*;
+pub use update_pool_history::*;
+pub use withdraw_stake::*;
@@ -0,0 +1,31 @@
+use anchor_lang::prelude::*;
+
+use crate::{events::PoolPauseChanged, Pool};
+
+#[derive(Accounts)]
+pub struct Pause<'info> {
+    pub guardian: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [Pool::SEED, pool.admin.as_ref()],
+        bump,
+        has_one = guardian
+    )]
+    pub pool: Account<'info, Pool>,
+}
+
+/// Halts the pool in an incident. Stake can still be deactivated and withdrawn to the reserve.
+pub fn handler(ctx: Context<Pause>) -> Result<()> {
+    let Pause { guardian, pool } = ctx.accounts;
+
+    emit!(PoolPauseChanged {
+        pool: pool.key(),
+        guardian: guardian.key(),
+        old_paused: pool.paused,
+        new_paused: true,
+    });
+    pool.paused = true;
+
+    Ok(())
+}
@@ -0,0 +1,219 @@
+use anchor_lang::{
+    prelude::*,
+    solana_program::stake::{self, state::StakeState, tools::get_minimum_delegation},
+};
+use anchor_spl::stake::{
+    deactivate_stake, DeactivateStake as DeactivateStakeAccount, Stake as StakeProgram,
+};
+
+use crate::{
+    error::ErrorCode,
+    events::StakeDeactivated,
+    utils::{check_split_lamports, create_pda_account, create_transient_stake, get_stake_account},
+    Pool, StakeRegistry, StakeStatus, VList,
+};
+
+#[derive(Accounts)]
+pub struct ProcessUnstakeQueue<'info> {
+    // Pays the rent of the transient stake account if one is split off, which joins the principal
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(mut, seeds = [Pool::SEED, pool.admin.as_ref()], bump)]
+    pub pool: Account<'info, Pool>,
+
+    #[account(
+        seeds = [VList::SEED, pool.admin.as_ref(), pool.key().as_ref()],
+        bump = v_list.bump,
+        has_one = pool
+    )]
+    pub v_list: Box<Account<'info, VList>>,
+
+    #[account(
+        mut,
+        seeds = [StakeRegistry::SEED, pool.key().as_ref()],
+        bump = stake_registry.bump,
+        has_one = pool
+    )]
+    pub stake_registry: Box<Account<'info, StakeRegistry>>,
+
+    #[account(
+        seeds = [Pool::RESERVE_SEED, pool.key().as_ref()],
+        bump = pool.reserve_bump
+    )]
+    pub reserve: SystemAccount<'info>,
+
+    /// CHECK: checked to be the transient stake account for `nonce` of the validator whose stake
+    /// is split, only needed when the shortfall is less than a whole stake account
+    #[account(mut)]
+    pub transient_stake_account: Option<UncheckedAccount<'info>>,
+
+    pub clock: Sysvar<'info, Clock>,
+
+    pub system_program: Program<'info, System>,
+
+    pub stake_program: Program<'info, StakeProgram>,
+}
+
+/// Deactivates stake until the reserve and the stake already on its way there cover the lamports
+/// owed to withdrawal tickets plus the reserve target kept for instant unstakes. Stake on the
+/// lowest scored validators goes first, with validators outside the VList scoring zero. Once less
+/// than a whole account is missing, only that much is split off into the transient stake account
+/// for `nonce`, which `finalize_transient_stake` withdraws to the reserve after it cools down.
+/// Once the withdrawals are covered, every ticket requested so far can be claimed from the next
+/// epoch. Every registered stake account is expected in `remaining_accounts`, in registry order.
+/// Allowed while paused, so withdrawals keep flowing.
+pub fn handler<'info>(
+    ctx: Context<'_, '_, '_, 'info, ProcessUnstakeQueue<'info>>,
+    nonce: u64,
+) -> Result<()> {
+    let ProcessUnstakeQueue {
+        payer,
+        pool,
+        v_list,
+        stake_registry,
+        reserve,
+        transient_stake_account,
+        clock,
+        system_program,
+        stake_program,
+    } = ctx.accounts;
+
+    stake_registry.check_stake_accounts(ctx.remaining_accounts)?;
+
+    let mut covered = Pool::reserve_lamports(reserve)?;
+    for (entry, account_info) in stake_registry.entries().iter().zip(ctx.remaining_accounts) {
+        let to_reserve = if entry.is_transient {
+            entry.transient_vote_account == Pubkey::default()
+        } else {
+            entry.status == StakeStatus::Deactivating
+        };
+        if to_reserve {
+            covered = covered.saturating_add(account_info.lamports());
+        }
+    }
+    let target_reserve = pool
+        .reserve_params
+        .target_reserve_lamports(pool.total_lamports);
+    let required = pool
+        .pending_withdrawal_lamports
+        .saturating_add(target_reserve);
+    let mut shortfall = required.saturating_sub(covered);
+    if shortfall == 0 {
+        msg!("Pending withdrawals and the reserve target are covered");
+        pool.mark_tickets_unstaked(clock.epoch);
+        return Ok(());
+    }
+
+    let mut candidates: Vec<usize> = stake_registry
+        .entries()
+        .iter()
+        .enumerate()
+        .filter(|(_, entry)| {
+            !entry.is_transient
+                && matches!(entry.status, StakeStatus::Active | StakeStatus::Activating)
+        })
+        .map(|(i, _)| i)
+        .collect();
+    candidates.sort_by_key(|&i| {
+        v_list
+            .get_score(&stake_registry.entries[i].vote_account)
+            .unwrap_or(0)
+    });
+
+    for i in candidates {
+        if shortfall == 0 {
+            break;
+        }
+        let stake_account = &ctx.remaining_accounts[i];
+
+        // split off the shortfall, or as much as a delegation must hold, when the account can
+        // spare it
+        let split_lamports = shortfall.max(get_minimum_delegation()?);
+        let source = get_stake_account(stake_account)?;
+        if split_lamports < stake_account.lamports()
+            && check_split_lamports(&source, stake_account.lamports(), split_lamports).is_ok()
+        {
+            let transient_stake_account = transient_stake_account
+                .as_ref()
+                .ok_or(ErrorCode::InvalidTransientStakeAccount)?;
+            let validator_vote = stake_registry.entries[i].vote_account;
+            let pool_key = pool.key();
+            let nonce_bytes = nonce.to_le_bytes();
+            let seeds = [
+                Pool::TRANSIENT_SEED,
+                pool_key.as_ref(),
+                validator_vote.as_ref(),
+                &nonce_bytes,
+            ];
+            let (address, bump) = Pubkey::find_program_address(&seeds, &crate::ID);
+            if transient_stake_account.key() != address {
+                return Err(ErrorCode::InvalidTransientStakeAccount.into());
+            }
+            create_pda_account(
+                &payer.to_account_info(),
+                &transient_stake_account.to_account_info(),
+                &system_program.to_account_info(),
+                std::mem::size_of::<StakeState>(),
+                &stake::program::ID,
+                &[
+                    Pool::TRANSIENT_SEED,
+                    pool_key.as_ref(),
+                    validator_vote.as_ref(),
+                    &nonce_bytes,
+                    &[bump],
+                ],
+            )?;
+            create_transient_stake(
+                pool,
+                stake_registry,
+                stake_account,
+                &validator_vote,
+                &transient_stake_account.to_account_info(),
+                &clock.to_account_info(),
+                &stake_program.to_account_info(),
+                split_lamports,
+                Pubkey::default(),
+            )?;
+            shortfall = 0;
+            break;
+        }
+
+        deactivate_stake(CpiContext::new_with_signer(
+            stake_program.to_account_info(),
+            DeactivateStakeAccount {
+                stake: stake_account.to_account_info(),
+                staker: pool.to_account_info(),
+                clock: clock.to_account_info(),
+            },
+            &[&[Pool::SEED, pool.admin.as_ref(), &[pool.bump]]],
+        ))?;
+
+        let entry = &mut stake_registry.entries[i];
+        let old_status = entry.status;
+        entry.status = StakeStatus::Deactivating;
+        entry.lamports = stake_account.lamports();
+        shortfall = shortfall.saturating_sub(entry.lamports);
+
+        emit!(StakeDeactivated {
+            pool: pool.key(),
+            stake_account: stake_account.key(),
+            validator: entry.vote_account,
+            lamports: entry.lamports,
+            old_status,
+            new_status: entry.status,
+        });
+    }
+
+    if shortfall <= target_reserve {
+        pool.mark_tickets_unstaked(clock.epoch);
+    }
+    if shortfall > 0 {
+        msg!(
+            "{} lamports of withdrawals and reserve remain uncovered",
+            shortfall
+        );
+    }
+
+    Ok(())
+}
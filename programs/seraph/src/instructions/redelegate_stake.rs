@@ -1,17 +1,18 @@
 use anchor_lang::{
     prelude::*,
-    solana_program::{
-        program::invoke_signed,
-        stake::state::StakeState,
-        stake::{self, instruction::redelegate},
-        sysvar::stake_history,
-    },
+    solana_program::{stake, stake::state::StakeState, vote},
 };
 use anchor_spl::stake::{Stake as StakeProgram, StakeAccount};
 
-use crate::Pool;
+use crate::{
+    error::ErrorCode,
+    events::{RebalanceRejected, StakeRedelegated},
+    utils::create_transient_stake,
+    Pool, RebalanceRejection, StakeRegistry, VList,
+};
 
 #[derive(Accounts)]
+#[instruction(lamports: u64, nonce: u64)]
 pub struct RedelegateStake<'info> {
     #[account(mut)]
     pub admin: Signer<'info>,
@@ -19,83 +20,167 @@ pub struct RedelegateStake<'info> {
     #[account(
         mut,
         seeds = [Pool::SEED, pool.admin.as_ref()],
-        bump
+        bump,
+        has_one = admin,
+        constraint = !pool.paused @ ErrorCode::PoolPaused
     )]
     pub pool: Account<'info, Pool>,
 
-    /// CHECK:
+    #[account(
+        mut,
+        seeds = [StakeRegistry::SEED, pool.key().as_ref()],
+        bump = stake_registry.bump,
+        has_one = pool
+    )]
+    pub stake_registry: Box<Account<'info, StakeRegistry>>,
+
+    #[account(
+        seeds = [VList::SEED, pool.admin.as_ref(), pool.key().as_ref()],
+        bump = v_list.bump,
+        has_one = pool
+    )]
+    pub v_list: Box<Account<'info, VList>>,
+
+    /// CHECK: checked against the stake registry
     #[account(mut)]
     pub stake_account: AccountInfo<'info>,
 
-    pub clock: Sysvar<'info, Clock>,
-
-    /// CHECK:
-    pub old_validator_vote: AccountInfo<'info>,
+    /// CHECK: checked to be the validator `stake_account` is delegated to
+    pub validator_vote: AccountInfo<'info>,
 
-    /// CHECK:
+    /// CHECK: checked to be eligible for stake in the VList, recorded as the target of the
+    /// transient account
+    #[account(owner = vote::program::ID)]
     pub new_validator_vote: AccountInfo<'info>,
 
-    /// CHECK:
-    #[account(address = stake_history::ID)]
-    pub stake_history: UncheckedAccount<'info>,
-
-    /// CHECK:
-    #[account(address = stake::config::ID)]
-    pub stake_config: UncheckedAccount<'info>,
-
-    // new stake account to make the reDelegation
+    // transient stake account cooling down before it is delegated to the new validator
     #[account(
         init,
         payer = admin,
         space = std::mem::size_of::<StakeState>(),
         owner = stake::program::ID,
+        seeds = [
+            Pool::TRANSIENT_SEED,
+            pool.key().as_ref(),
+            validator_vote.key().as_ref(),
+            &nonce.to_le_bytes()
+        ],
+        bump
     )]
-    pub redelegate_stake_account: Account<'info, StakeAccount>,
+    pub transient_stake_account: Account<'info, StakeAccount>,
+
+    pub clock: Sysvar<'info, Clock>,
 
     pub system_program: Program<'info, System>,
 
     pub stake_program: Program<'info, StakeProgram>,
 }
 
-pub fn handler<'info>(ctx: Context<'_, '_, '_, 'info, RedelegateStake>) -> Result<()> {
+/// Starts moving `lamports` of delegated stake to a new validator: the lamports are split into a
+/// transient stake account which is deactivated right away. Once it has cooled down,
+/// `finalize_transient_stake` delegates it to `new_validator_vote`.
+///
+/// The new validator must be eligible for stake and beat the incumbent's VList score by the
+/// pool's minimum improvement, and the move must fit in the cycle's redelegation budget. A move
+/// rejected on score or budget emits `RebalanceRejected` with its reason before failing.
+pub fn handler(ctx: Context<RedelegateStake>, lamports: u64, _nonce: u64) -> Result<()> {
     let RedelegateStake {
-        admin,
         pool,
+        stake_registry,
+        v_list,
         stake_account,
-        stake_config,
-        stake_history: _,
-        clock: _,
-        old_validator_vote: _,
+        validator_vote,
         new_validator_vote,
-        system_program: _,
-        stake_program: _,
-        redelegate_stake_account,
+        transient_stake_account,
+        clock,
+        stake_program,
+        ..
     } = ctx.accounts;
 
-    let redelegate_ix = redelegate(
-        stake_account.key,
-        admin.key,
-        new_validator_vote.key,
-        &redelegate_stake_account.key(),
+    // stake only moves to validators eligible for it, while an incumbent that dropped out of the
+    // list can be replaced by any of them
+    if !v_list.is_eligible(new_validator_vote.key, &pool.params) {
+        return Err(ErrorCode::ValidatorNotInList.into());
+    }
+    let from_score = v_list.get_score(validator_vote.key).unwrap_or(0);
+    let to_score = v_list
+        .get_score(new_validator_vote.key)
+        .ok_or(ErrorCode::ValidatorNotInList)?;
+    let params = pool.rebalance_params;
+    let max_stake_moved = pool.cycle_budget(clock.epoch)?;
+    let stake_moved_in_cycle = pool
+        .stake_moved_in_cycle
+        .checked_add(lamports)
+        .ok_or(ErrorCode::ArithmeticError)?;
+
+    let rejection = if !params.is_score_improvement(from_score, to_score) {
+        msg!(
+            "Score {} does not improve on {} by {} bps",
+            to_score,
+            from_score,
+            params.min_score_improvement_bps
+        );
+        Some(RebalanceRejection::InsufficientScoreImprovement)
+    } else if stake_moved_in_cycle > max_stake_moved {
+        msg!(
+            "Moving {} lamports exceeds the cycle budget: {} of {} already moved",
+            lamports,
+            pool.stake_moved_in_cycle,
+            max_stake_moved
+        );
+        Some(RebalanceRejection::BudgetExceeded)
+    } else {
+        None
+    };
+    if let Some(reason) = rejection {
+        emit!(RebalanceRejected {
+            pool: pool.key(),
+            stake_account: stake_account.key(),
+            from_validator: validator_vote.key(),
+            to_validator: new_validator_vote.key(),
+            lamports,
+            reason,
+            from_score,
+            to_score,
+            min_score_improvement_bps: params.min_score_improvement_bps,
+            stake_moved_in_cycle: pool.stake_moved_in_cycle,
+            max_stake_moved,
+        });
+        return Err(match reason {
+            RebalanceRejection::InsufficientScoreImprovement => {
+                ErrorCode::InsufficientScoreImprovement
+            }
+            RebalanceRejection::BudgetExceeded => ErrorCode::RebalanceBudgetExceeded,
+        }
+        .into());
+    }
+    pool.stake_moved_in_cycle = stake_moved_in_cycle;
+
+    msg!("Redelegating {} lamports of stake", lamports);
+
+    emit!(StakeRedelegated {
+        pool: pool.key(),
+        stake_account: stake_account.key(),
+        transient_stake_account: transient_stake_account.key(),
+        from_validator: validator_vote.key(),
+        to_validator: new_validator_vote.key(),
+        lamports,
+        from_score,
+        to_score,
+        min_score_improvement_bps: params.min_score_improvement_bps,
+        stake_moved_in_cycle: pool.stake_moved_in_cycle,
+        max_stake_moved,
+    });
+
+    create_transient_stake(
+        pool,
+        stake_registry,
+        stake_account,
+        validator_vote.key,
+        &transient_stake_account.to_account_info(),
+        &clock.to_account_info(),
+        &stake_program.to_account_info(),
+        lamports,
+        new_validator_vote.key(),
     )
-    .last()
-    .unwrap()
-    .clone();
-
-    msg!("Redelegating Stake");
-
-    // redelegate account to dest validator
-    invoke_signed(
-        &redelegate_ix,
-        &[
-            stake_account.to_account_info(),
-            new_validator_vote.to_account_info(),
-            redelegate_stake_account.to_account_info(),
-            stake_config.to_account_info(),
-            admin.to_account_info(),
-        ],
-        &[&[Pool::SEED, pool.admin.as_ref(), &[pool.bump]]],
-    )?;
-
-    Ok(())
 }
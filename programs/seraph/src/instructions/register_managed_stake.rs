@@ -0,0 +1,103 @@
+use anchor_lang::{
+    prelude::*,
+    solana_program::{
+        program::invoke,
+        stake::{instruction::authorize, state::StakeAuthorize},
+    },
+};
+use anchor_spl::stake::Stake as StakeProgram;
+
+use crate::{
+    error::ErrorCode, events::ManagedStakeRegistered, utils::get_stake_account, ManagedStake, Pool,
+};
+
+#[derive(Accounts)]
+pub struct RegisterManagedStake<'info> {
+    // Staker and withdraw authority of the stake account
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [Pool::SEED, pool.admin.as_ref()],
+        bump,
+        constraint = !pool.paused @ ErrorCode::PoolPaused
+    )]
+    pub pool: Account<'info, Pool>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = ManagedStake::SIZE,
+        seeds = [ManagedStake::SEED, pool.key().as_ref(), stake_account.key().as_ref()],
+        bump
+    )]
+    pub managed_stake: Account<'info, ManagedStake>,
+
+    /// CHECK: checked to be a stake account with the owner as staker and withdrawer
+    #[account(mut)]
+    pub stake_account: AccountInfo<'info>,
+
+    pub clock: Sysvar<'info, Clock>,
+
+    pub system_program: Program<'info, System>,
+
+    pub stake_program: Program<'info, StakeProgram>,
+}
+
+/// Hands the staker authority of the owner's stake account to the pool, which then delegates
+/// it to validators in the VList. The owner keeps withdraw authority and can revoke at any time.
+pub fn handler(ctx: Context<RegisterManagedStake>) -> Result<()> {
+    let RegisterManagedStake {
+        owner,
+        pool,
+        managed_stake,
+        stake_account,
+        clock,
+        stake_program,
+        ..
+    } = ctx.accounts;
+
+    let stake = get_stake_account(stake_account)?;
+    match stake.authorized() {
+        Some(authorized)
+            if authorized.staker == owner.key() && authorized.withdrawer == owner.key() => {}
+        _ => return Err(ErrorCode::InvalidStakeAccount.into()),
+    }
+
+    invoke(
+        &authorize(
+            stake_account.key,
+            owner.key,
+            &pool.key(),
+            StakeAuthorize::Staker,
+            None,
+        ),
+        &[
+            stake_program.to_account_info(),
+            stake_account.to_account_info(),
+            clock.to_account_info(),
+            owner.to_account_info(),
+        ],
+    )?;
+
+    let bump = *ctx.bumps.get("managed_stake").unwrap();
+    managed_stake.init(pool.key(), stake_account.key(), owner.key(), bump);
+    pool.managed_stake_count = pool
+        .managed_stake_count
+        .checked_add(1)
+        .ok_or(ErrorCode::ArithmeticError)?;
+
+    emit!(ManagedStakeRegistered {
+        pool: pool.key(),
+        stake_account: stake_account.key(),
+        owner: owner.key(),
+        validator: stake
+            .delegation()
+            .map(|delegation| delegation.voter_pubkey)
+            .unwrap_or_default(),
+        lamports: stake_account.lamports(),
+    });
+
+    Ok(())
+}
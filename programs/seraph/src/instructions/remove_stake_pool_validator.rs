@@ -0,0 +1,111 @@
+use anchor_lang::{prelude::*, solana_program::program::invoke_signed};
+use anchor_spl::stake::Stake as StakeProgram;
+
+use crate::{
+    error::ErrorCode,
+    events::StakePoolValidatorRemoved,
+    spl_stake_pool::{self, SplStakePool},
+    utils::get_stake_account,
+    Pool, VList,
+};
+
+#[derive(Accounts)]
+pub struct RemoveStakePoolValidator<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [Pool::SEED, pool.admin.as_ref()],
+        bump,
+        has_one = admin
+    )]
+    pub pool: Account<'info, Pool>,
+
+    #[account(
+        seeds = [VList::SEED, pool.admin.as_ref(), pool.key().as_ref()],
+        bump = v_list.bump,
+        has_one = pool
+    )]
+    pub v_list: Box<Account<'info, VList>>,
+
+    /// CHECK: the stake pool linked with `set_stake_pool`
+    #[account(mut, constraint = stake_pool.key() == pool.stake_pool @ ErrorCode::InvalidStakePool)]
+    pub stake_pool: UncheckedAccount<'info>,
+
+    /// CHECK: checked by the stake pool program
+    pub withdraw_authority: UncheckedAccount<'info>,
+
+    /// CHECK: checked by the stake pool program
+    #[account(mut)]
+    pub validator_list: UncheckedAccount<'info>,
+
+    /// CHECK: checked by the stake pool program to be the validator's stake account
+    #[account(mut)]
+    pub validator_stake: UncheckedAccount<'info>,
+
+    /// CHECK: checked by the stake pool program
+    #[account(mut)]
+    pub transient_stake: UncheckedAccount<'info>,
+
+    pub clock: Sysvar<'info, Clock>,
+
+    pub stake_program: Program<'info, StakeProgram>,
+
+    pub spl_stake_pool_program: Program<'info, SplStakePool>,
+}
+
+/// Removes a validator that is no longer in the top percentile of the VList from the linked
+/// stake pool, which deactivates its stake. Eligible validators are left alone.
+pub fn handler(ctx: Context<RemoveStakePoolValidator>) -> Result<()> {
+    let RemoveStakePoolValidator {
+        pool,
+        v_list,
+        stake_pool,
+        withdraw_authority,
+        validator_list,
+        validator_stake,
+        transient_stake,
+        clock,
+        stake_program,
+        spl_stake_pool_program,
+        ..
+    } = ctx.accounts;
+
+    let validator = get_stake_account(validator_stake)?
+        .delegation()
+        .map(|delegation| delegation.voter_pubkey)
+        .ok_or(ErrorCode::InvalidStakeAccount)?;
+    if v_list.is_eligible(&validator, &pool.params) {
+        return Err(ErrorCode::ValidatorStillEligible.into());
+    }
+
+    invoke_signed(
+        &spl_stake_pool::remove_validator_from_pool(
+            stake_pool.key,
+            &pool.key(),
+            withdraw_authority.key,
+            validator_list.key,
+            validator_stake.key,
+            transient_stake.key,
+        ),
+        &[
+            spl_stake_pool_program.to_account_info(),
+            stake_pool.to_account_info(),
+            pool.to_account_info(),
+            withdraw_authority.to_account_info(),
+            validator_list.to_account_info(),
+            validator_stake.to_account_info(),
+            transient_stake.to_account_info(),
+            clock.to_account_info(),
+            stake_program.to_account_info(),
+        ],
+        &[&[Pool::SEED, pool.admin.as_ref(), &[pool.bump]]],
+    )?;
+
+    emit!(StakePoolValidatorRemoved {
+        pool: pool.key(),
+        stake_pool: stake_pool.key(),
+        validator,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,110 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{burn, Burn, Mint, Token, TokenAccount};
+
+use crate::{error::ErrorCode, events::WithdrawalRequested, Pool, WithdrawalTicket};
+
+#[derive(Accounts)]
+pub struct RequestWithdrawal<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [Pool::SEED, pool.admin.as_ref()],
+        bump,
+        has_one = pool_mint
+    )]
+    pub pool: Account<'info, Pool>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = WithdrawalTicket::SIZE,
+        seeds = [
+            WithdrawalTicket::SEED,
+            pool.key().as_ref(),
+            &pool.next_ticket_id.to_le_bytes()
+        ],
+        bump
+    )]
+    pub ticket: Account<'info, WithdrawalTicket>,
+
+    #[account(mut)]
+    pub pool_mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        token::mint = pool_mint,
+        token::authority = owner
+    )]
+    pub pool_token_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Burns `pool_tokens` for a ticket on the lamports they redeem at the exchange rate of this
+/// epoch's balance update, which must already have run. The lamports leave `total_lamports` and
+/// wait in the unstake queue until `process_unstake_queue` has deactivated enough stake and it
+/// has cooled down. Allowed while paused, so depositors can always leave.
+pub fn handler(ctx: Context<RequestWithdrawal>, pool_tokens: u64) -> Result<()> {
+    let RequestWithdrawal {
+        owner,
+        pool,
+        ticket,
+        pool_mint,
+        pool_token_account,
+        token_program,
+        ..
+    } = ctx.accounts;
+
+    let clock = Clock::get()?;
+    pool.check_balance_fresh(clock.epoch)?;
+    let lamports = pool
+        .lamports_for_pool_tokens(pool_tokens, pool_mint.supply)
+        .ok_or(ErrorCode::InvalidWithdrawalAmount)?;
+    if lamports == 0 {
+        return Err(ErrorCode::InvalidWithdrawalAmount.into());
+    }
+
+    burn(
+        CpiContext::new(
+            token_program.to_account_info(),
+            Burn {
+                mint: pool_mint.to_account_info(),
+                from: pool_token_account.to_account_info(),
+                authority: owner.to_account_info(),
+            },
+        ),
+        pool_tokens,
+    )?;
+
+    ticket.pool = pool.key();
+    ticket.owner = owner.key();
+    ticket.id = pool.next_ticket_id;
+    ticket.lamports = lamports;
+    ticket.requested_epoch = clock.epoch;
+    ticket.bump = *ctx.bumps.get("ticket").unwrap();
+
+    pool.next_ticket_id += 1;
+    pool.total_lamports = pool
+        .total_lamports
+        .checked_sub(lamports)
+        .ok_or(ErrorCode::ArithmeticError)?;
+    pool.pending_withdrawal_lamports = pool
+        .pending_withdrawal_lamports
+        .checked_add(lamports)
+        .ok_or(ErrorCode::ArithmeticError)?;
+
+    emit!(WithdrawalRequested {
+        pool: pool.key(),
+        ticket: ticket.key(),
+        owner: owner.key(),
+        pool_tokens,
+        lamports,
+        requested_epoch: ticket.requested_epoch,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,82 @@
+use anchor_lang::{
+    prelude::*,
+    solana_program::{
+        program::invoke_signed,
+        stake::{instruction::authorize, state::StakeAuthorize},
+    },
+};
+use anchor_spl::stake::Stake as StakeProgram;
+
+use crate::{events::ManagedStakeRevoked, utils::get_stake_account, ManagedStake, Pool};
+
+#[derive(Accounts)]
+pub struct RevokeManagedStake<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(mut, seeds = [Pool::SEED, pool.admin.as_ref()], bump)]
+    pub pool: Account<'info, Pool>,
+
+    #[account(
+        mut,
+        close = owner,
+        seeds = [ManagedStake::SEED, pool.key().as_ref(), stake_account.key().as_ref()],
+        bump = managed_stake.bump,
+        has_one = pool,
+        has_one = stake_account,
+        has_one = owner
+    )]
+    pub managed_stake: Account<'info, ManagedStake>,
+
+    /// CHECK: checked to be a stake account
+    #[account(mut)]
+    pub stake_account: AccountInfo<'info>,
+
+    pub clock: Sysvar<'info, Clock>,
+
+    pub stake_program: Program<'info, StakeProgram>,
+}
+
+/// Returns the staker authority of a managed stake account to its owner and stops managing it.
+/// Allowed while paused. If the owner already took the staker authority back with their withdraw
+/// authority, the account is only released.
+pub fn handler(ctx: Context<RevokeManagedStake>) -> Result<()> {
+    let RevokeManagedStake {
+        owner,
+        pool,
+        stake_account,
+        clock,
+        stake_program,
+        ..
+    } = ctx.accounts;
+
+    let stake = get_stake_account(stake_account)?;
+    if matches!(stake.authorized(), Some(authorized) if authorized.staker == pool.key()) {
+        invoke_signed(
+            &authorize(
+                stake_account.key,
+                &pool.key(),
+                owner.key,
+                StakeAuthorize::Staker,
+                None,
+            ),
+            &[
+                stake_program.to_account_info(),
+                stake_account.to_account_info(),
+                clock.to_account_info(),
+                pool.to_account_info(),
+            ],
+            &[&[Pool::SEED, pool.admin.as_ref(), &[pool.bump]]],
+        )?;
+    }
+
+    pool.managed_stake_count = pool.managed_stake_count.saturating_sub(1);
+
+    emit!(ManagedStakeRevoked {
+        pool: pool.key(),
+        stake_account: stake_account.key(),
+        owner: owner.key(),
+    });
+
+    Ok(())
+}
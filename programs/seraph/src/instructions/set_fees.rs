@@ -0,0 +1,44 @@
+use anchor_lang::prelude::*;
+
+use crate::{error::ErrorCode, events::FeesStaged, Fees, Pool, FEE_CHANGE_DELAY_EPOCHS};
+
+#[derive(Accounts)]
+pub struct SetFees<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [Pool::SEED, pool.admin.as_ref()],
+        bump,
+        has_one = admin,
+        constraint = !pool.paused @ ErrorCode::PoolPaused
+    )]
+    pub pool: Account<'info, Pool>,
+}
+
+pub fn handler(
+    ctx: Context<SetFees>,
+    management_fee_bps: u16,
+    performance_fee_bps: u16,
+) -> Result<()> {
+    let pool = &mut ctx.accounts.pool;
+
+    let fees = Fees {
+        management_fee_bps,
+        performance_fee_bps,
+    };
+    fees.validate()?;
+
+    // Staged fees only take effect after the delay, giving depositors time to exit
+    let effective_epoch = Clock::get()?.epoch + FEE_CHANGE_DELAY_EPOCHS;
+    emit!(FeesStaged {
+        pool: pool.key(),
+        old_next_fees: pool.next_fees,
+        new_next_fees: fees,
+        effective_epoch,
+    });
+    pool.next_fees = Some(fees);
+    pool.next_fees_epoch = effective_epoch;
+
+    Ok(())
+}
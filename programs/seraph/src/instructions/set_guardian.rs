@@ -0,0 +1,31 @@
+use anchor_lang::prelude::*;
+
+use crate::{events::GuardianChanged, Pool};
+
+#[derive(Accounts)]
+pub struct SetGuardian<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [Pool::SEED, pool.admin.as_ref()],
+        bump,
+        has_one = admin
+    )]
+    pub pool: Account<'info, Pool>,
+}
+
+/// Hands the pause switch to `guardian`. Allowed while paused so a lost guardian key can be
+/// replaced.
+pub fn handler(ctx: Context<SetGuardian>, guardian: Pubkey) -> Result<()> {
+    let pool = &mut ctx.accounts.pool;
+
+    emit!(GuardianChanged {
+        pool: pool.key(),
+        old_guardian: pool.guardian,
+        new_guardian: guardian,
+    });
+    pool.guardian = guardian;
+
+    Ok(())
+}
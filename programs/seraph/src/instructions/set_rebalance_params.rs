@@ -0,0 +1,40 @@
+use anchor_lang::prelude::*;
+
+use crate::{error::ErrorCode, events::RebalanceParamsChanged, Pool, RebalanceParams};
+
+#[derive(Accounts)]
+pub struct SetRebalanceParams<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [Pool::SEED, pool.admin.as_ref()],
+        bump,
+        has_one = admin,
+        constraint = !pool.paused @ ErrorCode::PoolPaused
+    )]
+    pub pool: Account<'info, Pool>,
+}
+
+pub fn handler(
+    ctx: Context<SetRebalanceParams>,
+    min_score_improvement_bps: u16,
+    max_stake_moved_bps: u16,
+) -> Result<()> {
+    let pool = &mut ctx.accounts.pool;
+
+    let params = RebalanceParams {
+        min_score_improvement_bps,
+        max_stake_moved_bps,
+    };
+    params.validate()?;
+
+    emit!(RebalanceParamsChanged {
+        pool: pool.key(),
+        old_params: pool.rebalance_params,
+        new_params: params,
+    });
+    pool.rebalance_params = params;
+
+    Ok(())
+}
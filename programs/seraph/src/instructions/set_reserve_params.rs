@@ -0,0 +1,32 @@
+use anchor_lang::prelude::*;
+
+use crate::{error::ErrorCode, events::ReserveParamsChanged, Pool, ReserveParams};
+
+#[derive(Accounts)]
+pub struct SetReserveParams<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [Pool::SEED, pool.admin.as_ref()],
+        bump,
+        has_one = admin,
+        constraint = !pool.paused @ ErrorCode::PoolPaused
+    )]
+    pub pool: Account<'info, Pool>,
+}
+
+pub fn handler(ctx: Context<SetReserveParams>, params: ReserveParams) -> Result<()> {
+    let pool = &mut ctx.accounts.pool;
+
+    params.validate()?;
+
+    emit!(ReserveParamsChanged {
+        pool: pool.key(),
+        old_params: pool.reserve_params,
+        new_params: params,
+    });
+    pool.reserve_params = params;
+
+    Ok(())
+}
@@ -0,0 +1,34 @@
+use anchor_lang::prelude::*;
+
+use crate::{error::ErrorCode, events::ScoringParamsChanged, Pool, ScoringParams};
+
+#[derive(Accounts)]
+pub struct SetScoringParams<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [Pool::SEED, pool.admin.as_ref()],
+        bump,
+        has_one = admin,
+        constraint = !pool.paused @ ErrorCode::PoolPaused
+    )]
+    pub pool: Account<'info, Pool>,
+}
+
+/// Changes how validators are scored. Scores already in the VList are kept until their
+/// validators are scored again.
+pub fn handler(ctx: Context<SetScoringParams>, params: ScoringParams) -> Result<()> {
+    let pool = &mut ctx.accounts.pool;
+
+    params.validate()?;
+
+    emit!(ScoringParamsChanged {
+        pool: pool.key(),
+        old_params: pool.scoring_params,
+        new_params: params,
+    });
+    pool.scoring_params = params;
+
+    Ok(())
+}
@@ -0,0 +1,42 @@
+use anchor_lang::prelude::*;
+
+use crate::{error::ErrorCode, events::StakePoolChanged, spl_stake_pool::StakePoolHeader, Pool};
+
+#[derive(Accounts)]
+pub struct SetStakePool<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [Pool::SEED, pool.admin.as_ref()],
+        bump,
+        has_one = admin,
+        constraint = !pool.paused @ ErrorCode::PoolPaused
+    )]
+    pub pool: Account<'info, Pool>,
+
+    /// CHECK: checked to be an SPL stake pool with the pool as staker
+    pub stake_pool: UncheckedAccount<'info>,
+}
+
+/// Links the SPL stake pool the pool drives. The stake pool's manager must first hand the
+/// `staker` role to the pool PDA.
+pub fn handler(ctx: Context<SetStakePool>) -> Result<()> {
+    let SetStakePool {
+        pool, stake_pool, ..
+    } = ctx.accounts;
+
+    let header = StakePoolHeader::load(stake_pool)?;
+    if header.staker != pool.key() {
+        return Err(ErrorCode::InvalidStakePool.into());
+    }
+
+    emit!(StakePoolChanged {
+        pool: pool.key(),
+        old_stake_pool: pool.stake_pool,
+        new_stake_pool: stake_pool.key(),
+    });
+    pool.stake_pool = stake_pool.key();
+
+    Ok(())
+}
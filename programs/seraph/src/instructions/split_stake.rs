@@ -0,0 +1,130 @@
+use anchor_lang::{
+    prelude::*,
+    solana_program::{
+        program::invoke_signed,
+        stake::state::StakeState,
+        stake::{self, instruction::split},
+    },
+};
+use anchor_spl::stake::{Stake as StakeProgram, StakeAccount};
+
+use crate::{
+    error::ErrorCode,
+    events::StakeSplit,
+    utils::{check_split_lamports, get_pool_stake_account},
+    Pool, StakeRegistry, StakeRegistryEntry,
+};
+
+#[derive(Accounts)]
+pub struct SplitStake<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [Pool::SEED, pool.admin.as_ref()],
+        bump,
+        has_one = admin,
+        constraint = !pool.paused @ ErrorCode::PoolPaused
+    )]
+    pub pool: Account<'info, Pool>,
+
+    #[account(
+        mut,
+        seeds = [StakeRegistry::SEED, pool.key().as_ref()],
+        bump = stake_registry.bump,
+        has_one = pool
+    )]
+    pub stake_registry: Box<Account<'info, StakeRegistry>>,
+
+    /// CHECK: checked against the stake registry
+    #[account(mut)]
+    pub stake_account: AccountInfo<'info>,
+
+    // new stake account receiving the split lamports
+    #[account(
+        init,
+        payer = admin,
+        space = std::mem::size_of::<StakeState>(),
+        owner = stake::program::ID,
+    )]
+    pub split_stake_account: Account<'info, StakeAccount>,
+
+    pub system_program: Program<'info, System>,
+
+    pub stake_program: Program<'info, StakeProgram>,
+}
+
+/// Moves `lamports` from a registered stake account into a new one with the same delegation.
+/// Transient accounts cannot be split while in flight. Both accounts must keep their rent-exempt
+/// reserve and, when delegated, the minimum delegation.
+pub fn handler(ctx: Context<SplitStake>, lamports: u64) -> Result<()> {
+    let SplitStake {
+        pool,
+        stake_registry,
+        stake_account,
+        split_stake_account,
+        stake_program,
+        ..
+    } = ctx.accounts;
+
+    let source_entry = *stake_registry
+        .get(stake_account.key)
+        .ok_or(ErrorCode::StakeAccountNotRegistered)?;
+    if source_entry.is_transient {
+        return Err(ErrorCode::TransientStakeInFlight.into());
+    }
+    let source = get_pool_stake_account(&pool.key(), stake_account)?;
+    let old_lamports = stake_account.lamports();
+    check_split_lamports(&source, old_lamports, lamports)?;
+
+    // rent paid by the admin for the new account joins the pool's principal
+    let split_rent = split_stake_account.to_account_info().lamports();
+    pool.total_lamports = pool
+        .total_lamports
+        .checked_add(split_rent)
+        .ok_or(ErrorCode::ArithmeticError)?;
+
+    let split_ix = split(
+        stake_account.key,
+        &pool.key(),
+        lamports,
+        &split_stake_account.key(),
+    )
+    .last()
+    .unwrap()
+    .clone();
+
+    msg!("Splitting {} lamports of stake", lamports);
+
+    invoke_signed(
+        &split_ix,
+        &[
+            stake_program.to_account_info(),
+            stake_account.to_account_info(),
+            split_stake_account.to_account_info(),
+            pool.to_account_info(),
+        ],
+        &[&[Pool::SEED, pool.admin.as_ref(), &[pool.bump]]],
+    )?;
+
+    stake_registry.get_mut(stake_account.key)?.lamports = stake_account.lamports();
+
+    emit!(StakeSplit {
+        pool: pool.key(),
+        stake_account: stake_account.key(),
+        split_stake_account: split_stake_account.key(),
+        lamports,
+        old_lamports,
+        new_lamports: stake_account.lamports(),
+    });
+    stake_registry.add(StakeRegistryEntry {
+        stake_account: split_stake_account.key(),
+        vote_account: source_entry.vote_account,
+        lamports: split_stake_account.to_account_info().lamports(),
+        status: source_entry.status,
+        ..StakeRegistryEntry::default()
+    })?;
+
+    Ok(())
+}
@@ -0,0 +1,30 @@
+use anchor_lang::prelude::*;
+
+use crate::{events::PoolPauseChanged, Pool};
+
+#[derive(Accounts)]
+pub struct Unpause<'info> {
+    pub guardian: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [Pool::SEED, pool.admin.as_ref()],
+        bump,
+        has_one = guardian
+    )]
+    pub pool: Account<'info, Pool>,
+}
+
+pub fn handler(ctx: Context<Unpause>) -> Result<()> {
+    let Unpause { guardian, pool } = ctx.accounts;
+
+    emit!(PoolPauseChanged {
+        pool: pool.key(),
+        guardian: guardian.key(),
+        old_paused: pool.paused,
+        new_paused: false,
+    });
+    pool.paused = false;
+
+    Ok(())
+}
@@ -0,0 +1,63 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    error::ErrorCode,
+    events::{PoolParamsChanged, ValidatorRemoved},
+    utils::emit_selection_changes,
+    Pool, PoolParams, RemovalReason, VList,
+};
+
+#[derive(Accounts)]
+pub struct UpdateParams<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [Pool::SEED, pool.admin.as_ref()],
+        bump,
+        has_one = admin,
+        constraint = !pool.paused @ ErrorCode::PoolPaused
+    )]
+    pub pool: Account<'info, Pool>,
+
+    #[account(
+        mut,
+        seeds = [VList::SEED, pool.admin.as_ref(), pool.key().as_ref()],
+        bump = v_list.bump,
+        has_one = pool
+    )]
+    pub v_list: Box<Account<'info, VList>>,
+}
+
+/// Replaces the pool's scoring and cycle parameters. Shrinking the list size drops the
+/// lowest-scored validators from the VList right away, and a new list size or percentile may
+/// change which validators are eligible for stake.
+pub fn handler(ctx: Context<UpdateParams>, params: PoolParams) -> Result<()> {
+    let UpdateParams { pool, v_list, .. } = ctx.accounts;
+
+    params.validate()?;
+
+    let old_ranking = v_list.ranking();
+    let removed = v_list.truncate(params.max_validators as usize);
+    for (i, entry) in removed.iter().enumerate() {
+        emit!(ValidatorRemoved {
+            pool: pool.key(),
+            validator: entry.validator,
+            reason: RemovalReason::ListTruncated,
+            score: entry.score,
+            rank: (params.max_validators as usize + i) as u32,
+            components: entry.components,
+        });
+    }
+    v_list.refresh_eligibility(&params);
+    emit_selection_changes(pool.key(), &old_ranking, &pool.params, v_list, &params);
+
+    emit!(PoolParamsChanged {
+        pool: pool.key(),
+        old_params: pool.params,
+        new_params: params,
+    });
+    pool.params = params;
+
+    Ok(())
+}
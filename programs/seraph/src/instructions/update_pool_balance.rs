@@ -0,0 +1,157 @@
+use anchor_lang::{prelude::*, solana_program::stake_history::StakeHistory};
+use anchor_spl::token::{mint_to, Mint, MintTo, Token, TokenAccount};
+
+use crate::{
+    error::ErrorCode,
+    events::{FeesChanged, PoolBalanceUpdated},
+    utils::refresh_stake_registry,
+    Pool, StakeRegistry,
+};
+
+#[derive(Accounts)]
+pub struct UpdatePoolBalance<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [Pool::SEED, pool.admin.as_ref()],
+        bump,
+        has_one = admin,
+        has_one = pool_mint,
+        has_one = manager_fee_account
+    )]
+    pub pool: Account<'info, Pool>,
+
+    #[account(
+        mut,
+        seeds = [StakeRegistry::SEED, pool.key().as_ref()],
+        bump = stake_registry.bump,
+        has_one = pool
+    )]
+    pub stake_registry: Box<Account<'info, StakeRegistry>>,
+
+    #[account(mut)]
+    pub pool_mint: Account<'info, Mint>,
+
+    #[account(mut)]
+    pub manager_fee_account: Account<'info, TokenAccount>,
+
+    #[account(
+        seeds = [Pool::RESERVE_SEED, pool.key().as_ref()],
+        bump = pool.reserve_bump
+    )]
+    pub reserve: SystemAccount<'info>,
+
+    pub stake_history: Sysvar<'info, StakeHistory>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+/// Measures growth of the pool's stake and reserve since the last update and mints the
+/// manager's fees as pool tokens. Every registered stake account is expected in
+/// `remaining_accounts`, in registry order. Allowed while paused, as withdrawals are priced from
+/// the balance it books.
+pub fn handler<'info>(ctx: Context<'_, '_, '_, 'info, UpdatePoolBalance<'info>>) -> Result<()> {
+    let UpdatePoolBalance {
+        pool,
+        stake_registry,
+        pool_mint,
+        manager_fee_account,
+        reserve,
+        stake_history,
+        token_program,
+        ..
+    } = ctx.accounts;
+
+    let clock = Clock::get()?;
+    if clock.epoch <= pool.last_update_epoch {
+        return Err(ErrorCode::PoolAlreadyUpdated.into());
+    }
+
+    if let Some(next_fees) = pool.next_fees {
+        if clock.epoch >= pool.next_fees_epoch {
+            emit!(FeesChanged {
+                pool: pool.key(),
+                epoch: clock.epoch,
+                old_fees: pool.fees,
+                new_fees: next_fees,
+            });
+            pool.fees = next_fees;
+            pool.next_fees = None;
+        }
+    }
+
+    let stake_lamports = refresh_stake_registry(
+        &pool.key(),
+        stake_registry,
+        ctx.remaining_accounts,
+        clock.epoch,
+        stake_history,
+    )?;
+    // Lamports owed to withdrawal tickets no longer back pool tokens
+    let total_lamports = stake_lamports
+        .checked_add(Pool::reserve_lamports(reserve)?)
+        .and_then(|lamports| lamports.checked_sub(pool.pending_withdrawal_lamports))
+        .ok_or(ErrorCode::ArithmeticError)?;
+
+    // Registered principal is already in `pool.total_lamports`, so any growth is rewards
+    let rewards = total_lamports.saturating_sub(pool.total_lamports);
+    let mev_rewards = pool.harvested_mev_lamports.min(rewards);
+
+    let supply = pool_mint.supply;
+    let excess_rewards = pool
+        .excess_rewards(total_lamports, rewards, supply)
+        .ok_or(ErrorCode::ArithmeticError)?;
+    let fee_lamports = pool
+        .fees
+        .fee_lamports(rewards, excess_rewards)
+        .ok_or(ErrorCode::ArithmeticError)?;
+    let fee_pool_tokens = Pool::pool_tokens_for_fee(fee_lamports, total_lamports, supply)
+        .ok_or(ErrorCode::ArithmeticError)?;
+
+    if fee_pool_tokens > 0 {
+        msg!("Minting {} pool tokens in fees", fee_pool_tokens);
+
+        mint_to(
+            CpiContext::new_with_signer(
+                token_program.to_account_info(),
+                MintTo {
+                    mint: pool_mint.to_account_info(),
+                    to: manager_fee_account.to_account_info(),
+                    authority: pool.to_account_info(),
+                },
+                &[&[Pool::SEED, pool.admin.as_ref(), &[pool.bump]]],
+            ),
+            fee_pool_tokens,
+        )?;
+    }
+
+    let supply = supply
+        .checked_add(fee_pool_tokens)
+        .ok_or(ErrorCode::ArithmeticError)?;
+    let exchange_rate =
+        Pool::exchange_rate(total_lamports, supply).ok_or(ErrorCode::ArithmeticError)?;
+
+    emit!(PoolBalanceUpdated {
+        pool: pool.key(),
+        epoch: clock.epoch,
+        old_total_lamports: pool.total_lamports,
+        new_total_lamports: total_lamports,
+        rewards,
+        mev_rewards,
+        fee_lamports,
+        fee_pool_tokens,
+        old_high_water_mark: pool.high_water_mark,
+        new_high_water_mark: pool.high_water_mark.max(exchange_rate),
+    });
+
+    pool.high_water_mark = pool.high_water_mark.max(exchange_rate);
+    pool.total_lamports = total_lamports;
+    pool.last_update_epoch = clock.epoch;
+    pool.last_epoch_rewards = rewards;
+    pool.last_epoch_mev_rewards = mev_rewards;
+    pool.harvested_mev_lamports = 0;
+    pool.last_epoch_fees = fee_lamports;
+
+    Ok(())
+}
@@ -0,0 +1,84 @@
+use anchor_lang::{prelude::*, solana_program::stake_history::StakeHistory};
+use anchor_spl::token::Mint;
+
+use crate::{
+    error::ErrorCode, utils::get_pool_stake_activation, Pool, PoolHistory, PoolHistoryEntry,
+    StakeRegistry,
+};
+
+#[derive(Accounts)]
+pub struct UpdatePoolHistory<'info> {
+    #[account(
+        seeds = [Pool::SEED, pool.admin.as_ref()],
+        bump,
+        has_one = pool_mint,
+        constraint = !pool.paused @ ErrorCode::PoolPaused
+    )]
+    pub pool: Account<'info, Pool>,
+
+    #[account(
+        mut,
+        seeds = [PoolHistory::SEED, pool.key().as_ref()],
+        bump,
+        has_one = pool
+    )]
+    pub pool_history: AccountLoader<'info, PoolHistory>,
+
+    #[account(
+        seeds = [StakeRegistry::SEED, pool.key().as_ref()],
+        bump = stake_registry.bump,
+        has_one = pool
+    )]
+    pub stake_registry: Box<Account<'info, StakeRegistry>>,
+
+    pub pool_mint: Account<'info, Mint>,
+
+    pub stake_history: Sysvar<'info, StakeHistory>,
+
+    #[account(mut)]
+    pub signer: Signer<'info>,
+}
+
+/// Permissionless crank recording the pool's state for the current epoch. Runs after
+/// `update_pool_balance`, with every registered stake account in `remaining_accounts`.
+pub fn handler<'info>(ctx: Context<'_, '_, '_, 'info, UpdatePoolHistory<'info>>) -> Result<()> {
+    let UpdatePoolHistory {
+        pool,
+        pool_history,
+        stake_registry,
+        pool_mint,
+        stake_history,
+        ..
+    } = ctx.accounts;
+
+    let clock = Clock::get()?;
+    pool.check_balance_fresh(clock.epoch)?;
+
+    let (active_stake_lamports, activating_stake_lamports, transient_stake_lamports) =
+        get_pool_stake_activation(
+            &pool.key(),
+            stake_registry,
+            ctx.remaining_accounts,
+            clock.epoch,
+            stake_history,
+        )?;
+
+    let exchange_rate = Pool::exchange_rate(pool.total_lamports, pool_mint.supply)
+        .ok_or(ErrorCode::ArithmeticError)?;
+
+    let mut pool_history = pool_history.load_mut()?;
+    pool_history.record(PoolHistoryEntry {
+        epoch: clock.epoch,
+        total_lamports: pool.total_lamports,
+        active_stake_lamports,
+        activating_stake_lamports,
+        transient_stake_lamports,
+        rewards_lamports: pool.last_epoch_rewards,
+        fee_lamports: pool.last_epoch_fees,
+        pool_token_supply: pool_mint.supply,
+        exchange_rate,
+        ..PoolHistoryEntry::default()
+    });
+
+    Ok(())
+}
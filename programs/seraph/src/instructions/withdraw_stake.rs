@@ -0,0 +1,106 @@
+use anchor_lang::{prelude::*, solana_program::stake_history::StakeHistory};
+use anchor_spl::stake::{withdraw, Stake as StakeProgram, Withdraw};
+
+use crate::{
+    error::ErrorCode,
+    events::StakeWithdrawn,
+    utils::{get_pool_stake_account, get_stake_status},
+    Pool, StakeRegistry, StakeStatus,
+};
+
+#[derive(Accounts)]
+pub struct WithdrawStake<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [Pool::SEED, pool.admin.as_ref()],
+        bump,
+        has_one = admin
+    )]
+    pub pool: Account<'info, Pool>,
+
+    #[account(
+        mut,
+        seeds = [StakeRegistry::SEED, pool.key().as_ref()],
+        bump = stake_registry.bump,
+        has_one = pool
+    )]
+    pub stake_registry: Box<Account<'info, StakeRegistry>>,
+
+    /// CHECK: checked against the stake registry, closed once emptied
+    #[account(mut)]
+    pub stake_account: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [Pool::RESERVE_SEED, pool.key().as_ref()],
+        bump = pool.reserve_bump
+    )]
+    pub reserve: SystemAccount<'info>,
+
+    pub clock: Sysvar<'info, Clock>,
+
+    pub stake_history: Sysvar<'info, StakeHistory>,
+
+    pub stake_program: Program<'info, StakeProgram>,
+}
+
+/// Empties a fully inactive stake account into the pool reserve and drops it from the registry.
+/// Lamports only move between the pool's own accounts, so `pool.total_lamports` is unchanged.
+pub fn handler(ctx: Context<WithdrawStake>) -> Result<()> {
+    let WithdrawStake {
+        pool,
+        stake_registry,
+        stake_account,
+        reserve,
+        clock,
+        stake_history,
+        stake_program,
+        ..
+    } = ctx.accounts;
+
+    let entry = stake_registry
+        .get(stake_account.key)
+        .ok_or(ErrorCode::StakeAccountNotRegistered)?;
+    // transient accounts are completed by `finalize_transient_stake`
+    if entry.is_transient {
+        return Err(ErrorCode::TransientStakeInFlight.into());
+    }
+    let stake = get_pool_stake_account(&pool.key(), stake_account)?;
+    if get_stake_status(&stake, clock.epoch, stake_history) != StakeStatus::Inactive {
+        return Err(ErrorCode::StakeNotInactive.into());
+    }
+
+    let lamports = stake_account.lamports();
+    let old_reserve_lamports = Pool::reserve_lamports(reserve)?;
+
+    msg!("Withdrawing {} lamports to the reserve", lamports);
+
+    withdraw(
+        CpiContext::new_with_signer(
+            stake_program.to_account_info(),
+            Withdraw {
+                stake: stake_account.to_account_info(),
+                withdrawer: pool.to_account_info(),
+                to: reserve.to_account_info(),
+                clock: clock.to_account_info(),
+                stake_history: stake_history.to_account_info(),
+            },
+            &[&[Pool::SEED, pool.admin.as_ref(), &[pool.bump]]],
+        ),
+        lamports,
+        None,
+    )?;
+
+    stake_registry.remove(stake_account.key)?;
+
+    emit!(StakeWithdrawn {
+        pool: pool.key(),
+        stake_account: stake_account.key(),
+        lamports,
+        old_reserve_lamports,
+        new_reserve_lamports: Pool::reserve_lamports(reserve)?,
+    });
+
+    Ok(())
+}
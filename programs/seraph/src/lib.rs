@@ -2,8 +2,11 @@ use anchor_lang::prelude::*;
 
 pub mod constants;
 pub mod error;
+pub mod events;
 pub mod instructions;
+pub mod spl_stake_pool;
 pub mod state;
+pub mod utils;
 
 pub use constants::*;
 pub use instructions::*;
@@ -15,8 +18,8 @@ declare_id!("9EK4NR8LwFBzV6jCNYoshQ9yyuM6yDxB8zsqrhTsK5z");
 pub mod seraph {
     use super::*;
 
-    pub fn initialize(ctx: Context<Initialize>) -> Result<()> {
-        initialize::handler(ctx)
+    pub fn initialize(ctx: Context<Initialize>, params: PoolParams) -> Result<()> {
+        initialize::handler(ctx, params)
     }
 
     pub fn calculate_score(ctx: Context<CalculateScore>) -> Result<()> {
@@ -27,11 +30,186 @@ pub mod seraph {
         delegate_stake::handler(ctx)
     }
 
-    pub fn redelegate_stake<'info>(ctx: Context<'_, '_, '_, 'info, RedelegateStake>) -> Result<()> {
-        redelegate_stake::handler(ctx)
+    pub fn redelegate_stake(
+        ctx: Context<RedelegateStake>,
+        lamports: u64,
+        nonce: u64,
+    ) -> Result<()> {
+        redelegate_stake::handler(ctx, lamports, nonce)
     }
 
     pub fn deactivate_stake<'info>(ctx: Context<'_, '_, '_, 'info, DeactivateStake>) -> Result<()> {
         deactivate_stake::handler(ctx)
     }
+
+    pub fn set_fees(
+        ctx: Context<SetFees>,
+        management_fee_bps: u16,
+        performance_fee_bps: u16,
+    ) -> Result<()> {
+        set_fees::handler(ctx, management_fee_bps, performance_fee_bps)
+    }
+
+    pub fn update_pool_balance<'info>(
+        ctx: Context<'_, '_, '_, 'info, UpdatePoolBalance<'info>>,
+    ) -> Result<()> {
+        update_pool_balance::handler(ctx)
+    }
+
+    pub fn initialize_pool_history(ctx: Context<InitializePoolHistory>) -> Result<()> {
+        initialize_pool_history::handler(ctx)
+    }
+
+    pub fn update_pool_history<'info>(
+        ctx: Context<'_, '_, '_, 'info, UpdatePoolHistory<'info>>,
+    ) -> Result<()> {
+        update_pool_history::handler(ctx)
+    }
+
+    pub fn initialize_stake_registry(ctx: Context<InitializeStakeRegistry>) -> Result<()> {
+        initialize_stake_registry::handler(ctx)
+    }
+
+    pub fn add_stake_account(ctx: Context<AddStakeAccount>) -> Result<()> {
+        add_stake_account::handler(ctx)
+    }
+
+    pub fn split_stake(ctx: Context<SplitStake>, lamports: u64) -> Result<()> {
+        split_stake::handler(ctx, lamports)
+    }
+
+    pub fn merge_stake(ctx: Context<MergeStake>) -> Result<()> {
+        merge_stake::handler(ctx)
+    }
+
+    pub fn withdraw_stake(ctx: Context<WithdrawStake>) -> Result<()> {
+        withdraw_stake::handler(ctx)
+    }
+
+    pub fn decrease_stake(ctx: Context<DecreaseStake>, lamports: u64, nonce: u64) -> Result<()> {
+        decrease_stake::handler(ctx, lamports, nonce)
+    }
+
+    pub fn finalize_transient_stake(ctx: Context<FinalizeTransientStake>) -> Result<()> {
+        finalize_transient_stake::handler(ctx)
+    }
+
+    pub fn set_rebalance_params(
+        ctx: Context<SetRebalanceParams>,
+        min_score_improvement_bps: u16,
+        max_stake_moved_bps: u16,
+    ) -> Result<()> {
+        set_rebalance_params::handler(ctx, min_score_improvement_bps, max_stake_moved_bps)
+    }
+
+    pub fn get_top_validators(ctx: Context<GetTopValidators>, limit: u8) -> Result<TopValidators> {
+        get_top_validators::handler(ctx, limit)
+    }
+
+    pub fn set_guardian(ctx: Context<SetGuardian>, guardian: Pubkey) -> Result<()> {
+        set_guardian::handler(ctx, guardian)
+    }
+
+    pub fn pause(ctx: Context<Pause>) -> Result<()> {
+        pause::handler(ctx)
+    }
+
+    pub fn unpause(ctx: Context<Unpause>) -> Result<()> {
+        unpause::handler(ctx)
+    }
+
+    pub fn close_pool(ctx: Context<ClosePool>) -> Result<()> {
+        close_pool::handler(ctx)
+    }
+
+    pub fn update_params(ctx: Context<UpdateParams>, params: PoolParams) -> Result<()> {
+        update_params::handler(ctx, params)
+    }
+
+    pub fn migrate_pool(ctx: Context<MigratePool>) -> Result<()> {
+        migrate_pool::handler(ctx)
+    }
+
+    pub fn migrate_vlist(ctx: Context<MigrateVList>) -> Result<()> {
+        migrate_vlist::handler(ctx)
+    }
+
+    pub fn register_managed_stake(ctx: Context<RegisterManagedStake>) -> Result<()> {
+        register_managed_stake::handler(ctx)
+    }
+
+    pub fn delegate_managed_stake(ctx: Context<DelegateManagedStake>) -> Result<()> {
+        delegate_managed_stake::handler(ctx)
+    }
+
+    pub fn deactivate_managed_stake(ctx: Context<DeactivateManagedStake>) -> Result<()> {
+        deactivate_managed_stake::handler(ctx)
+    }
+
+    pub fn revoke_managed_stake(ctx: Context<RevokeManagedStake>) -> Result<()> {
+        revoke_managed_stake::handler(ctx)
+    }
+
+    pub fn request_withdrawal(ctx: Context<RequestWithdrawal>, pool_tokens: u64) -> Result<()> {
+        request_withdrawal::handler(ctx, pool_tokens)
+    }
+
+    pub fn process_unstake_queue<'info>(
+        ctx: Context<'_, '_, '_, 'info, ProcessUnstakeQueue<'info>>,
+        nonce: u64,
+    ) -> Result<()> {
+        process_unstake_queue::handler(ctx, nonce)
+    }
+
+    pub fn claim_ticket(ctx: Context<ClaimTicket>) -> Result<()> {
+        claim_ticket::handler(ctx)
+    }
+
+    pub fn set_reserve_params(ctx: Context<SetReserveParams>, params: ReserveParams) -> Result<()> {
+        set_reserve_params::handler(ctx, params)
+    }
+
+    pub fn instant_unstake(
+        ctx: Context<InstantUnstake>,
+        pool_tokens: u64,
+        min_lamports: u64,
+    ) -> Result<()> {
+        instant_unstake::handler(ctx, pool_tokens, min_lamports)
+    }
+
+    pub fn harvest(ctx: Context<Harvest>) -> Result<()> {
+        harvest::handler(ctx)
+    }
+
+    pub fn set_stake_pool(ctx: Context<SetStakePool>) -> Result<()> {
+        set_stake_pool::handler(ctx)
+    }
+
+    pub fn add_stake_pool_validator(ctx: Context<AddStakePoolValidator>) -> Result<()> {
+        add_stake_pool_validator::handler(ctx)
+    }
+
+    pub fn remove_stake_pool_validator(ctx: Context<RemoveStakePoolValidator>) -> Result<()> {
+        remove_stake_pool_validator::handler(ctx)
+    }
+
+    pub fn increase_stake_pool_validator_stake(
+        ctx: Context<IncreaseStakePoolValidatorStake>,
+        lamports: u64,
+        transient_stake_seed: u64,
+    ) -> Result<()> {
+        increase_stake_pool_validator_stake::handler(ctx, lamports, transient_stake_seed)
+    }
+
+    pub fn decrease_stake_pool_validator_stake(
+        ctx: Context<DecreaseStakePoolValidatorStake>,
+        lamports: u64,
+        transient_stake_seed: u64,
+    ) -> Result<()> {
+        decrease_stake_pool_validator_stake::handler(ctx, lamports, transient_stake_seed)
+    }
+
+    pub fn set_scoring_params(ctx: Context<SetScoringParams>, params: ScoringParams) -> Result<()> {
+        set_scoring_params::handler(ctx, params)
+    }
 }
@@ -0,0 +1,221 @@
+//! Client side of the SPL stake pool program, for driving a stake pool whose `staker` is a seraph
+//! pool. The `spl-stake-pool` crate does not build against this tree's solana version, so the few
+//! staker instructions seraph sends are built here, following the program's 1.0 interface.
+use anchor_lang::{
+    prelude::*,
+    solana_program::{
+        instruction::{AccountMeta, Instruction},
+        stake, system_program, sysvar,
+    },
+};
+
+use crate::error::ErrorCode;
+
+declare_id!("SPoo1Ku8WFXoNDMHPsrGSTSG1Y47rzgn41SLUNakuHy");
+
+// `AccountType::StakePool` of the program's state
+const STAKE_POOL_ACCOUNT_TYPE: u8 = 1;
+
+const AUTHORITY_WITHDRAW: &[u8] = b"withdraw";
+const TRANSIENT_STAKE_SEED_PREFIX: &[u8] = b"transient";
+
+// Variant indices of the program's `StakePoolInstruction`
+const ADD_VALIDATOR_TO_POOL: u8 = 1;
+const REMOVE_VALIDATOR_FROM_POOL: u8 = 2;
+const INCREASE_VALIDATOR_STAKE: u8 = 4;
+const DECREASE_VALIDATOR_STAKE_WITH_RESERVE: u8 = 21;
+
+#[derive(Clone)]
+pub struct SplStakePool;
+
+impl anchor_lang::Id for SplStakePool {
+    fn id() -> Pubkey {
+        ID
+    }
+}
+
+/// Leading fields of a stake pool account, up to the ones seraph checks
+#[derive(AnchorDeserialize)]
+pub struct StakePoolHeader {
+    pub account_type: u8,
+    pub manager: Pubkey,
+    pub staker: Pubkey,
+    pub stake_deposit_authority: Pubkey,
+    pub stake_withdraw_bump_seed: u8,
+    pub validator_list: Pubkey,
+    pub reserve_stake: Pubkey,
+    pub pool_mint: Pubkey,
+}
+
+impl StakePoolHeader {
+    pub fn load(account_info: &AccountInfo) -> Result<Self> {
+        if account_info.owner != &ID {
+            return Err(ErrorCode::InvalidStakePool.into());
+        }
+        let data = account_info.try_borrow_data()?;
+        let header = Self::deserialize(&mut &data[..]).map_err(|_| ErrorCode::InvalidStakePool)?;
+        if header.account_type != STAKE_POOL_ACCOUNT_TYPE {
+            return Err(ErrorCode::InvalidStakePool.into());
+        }
+        Ok(header)
+    }
+}
+
+pub fn withdraw_authority_pubkey(stake_pool: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[stake_pool.as_ref(), AUTHORITY_WITHDRAW], &ID).0
+}
+
+/// Validator stake account created by `add_validator_to_pool`, without a seed suffix
+pub fn validator_stake_pubkey(validator: &Pubkey, stake_pool: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[validator.as_ref(), stake_pool.as_ref()], &ID).0
+}
+
+pub fn transient_stake_pubkey(validator: &Pubkey, stake_pool: &Pubkey, seed: u64) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            TRANSIENT_STAKE_SEED_PREFIX,
+            validator.as_ref(),
+            stake_pool.as_ref(),
+            &seed.to_le_bytes(),
+        ],
+        &ID,
+    )
+    .0
+}
+
+fn lamports_and_seed_data(tag: u8, lamports: u64, transient_stake_seed: u64) -> Vec<u8> {
+    let mut data = vec![tag];
+    data.extend_from_slice(&lamports.to_le_bytes());
+    data.extend_from_slice(&transient_stake_seed.to_le_bytes());
+    data
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn add_validator_to_pool(
+    stake_pool: &Pubkey,
+    staker: &Pubkey,
+    reserve_stake: &Pubkey,
+    withdraw_authority: &Pubkey,
+    validator_list: &Pubkey,
+    validator_stake: &Pubkey,
+    validator: &Pubkey,
+) -> Instruction {
+    let mut data = vec![ADD_VALIDATOR_TO_POOL];
+    // no seed suffix for the validator stake account
+    data.extend_from_slice(&0u32.to_le_bytes());
+    Instruction {
+        program_id: ID,
+        accounts: vec![
+            AccountMeta::new(*stake_pool, false),
+            AccountMeta::new_readonly(*staker, true),
+            AccountMeta::new(*reserve_stake, false),
+            AccountMeta::new_readonly(*withdraw_authority, false),
+            AccountMeta::new(*validator_list, false),
+            AccountMeta::new(*validator_stake, false),
+            AccountMeta::new_readonly(*validator, false),
+            AccountMeta::new_readonly(sysvar::rent::ID, false),
+            AccountMeta::new_readonly(sysvar::clock::ID, false),
+            AccountMeta::new_readonly(sysvar::stake_history::ID, false),
+            AccountMeta::new_readonly(stake::config::ID, false),
+            AccountMeta::new_readonly(system_program::ID, false),
+            AccountMeta::new_readonly(stake::program::ID, false),
+        ],
+        data,
+    }
+}
+
+pub fn remove_validator_from_pool(
+    stake_pool: &Pubkey,
+    staker: &Pubkey,
+    withdraw_authority: &Pubkey,
+    validator_list: &Pubkey,
+    validator_stake: &Pubkey,
+    transient_stake: &Pubkey,
+) -> Instruction {
+    Instruction {
+        program_id: ID,
+        accounts: vec![
+            AccountMeta::new(*stake_pool, false),
+            AccountMeta::new_readonly(*staker, true),
+            AccountMeta::new_readonly(*withdraw_authority, false),
+            AccountMeta::new(*validator_list, false),
+            AccountMeta::new(*validator_stake, false),
+            AccountMeta::new(*transient_stake, false),
+            AccountMeta::new_readonly(sysvar::clock::ID, false),
+            AccountMeta::new_readonly(stake::program::ID, false),
+        ],
+        data: vec![REMOVE_VALIDATOR_FROM_POOL],
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn increase_validator_stake(
+    stake_pool: &Pubkey,
+    staker: &Pubkey,
+    withdraw_authority: &Pubkey,
+    validator_list: &Pubkey,
+    reserve_stake: &Pubkey,
+    transient_stake: &Pubkey,
+    validator_stake: &Pubkey,
+    validator: &Pubkey,
+    lamports: u64,
+    transient_stake_seed: u64,
+) -> Instruction {
+    Instruction {
+        program_id: ID,
+        accounts: vec![
+            AccountMeta::new_readonly(*stake_pool, false),
+            AccountMeta::new_readonly(*staker, true),
+            AccountMeta::new_readonly(*withdraw_authority, false),
+            AccountMeta::new(*validator_list, false),
+            AccountMeta::new(*reserve_stake, false),
+            AccountMeta::new(*transient_stake, false),
+            AccountMeta::new_readonly(*validator_stake, false),
+            AccountMeta::new_readonly(*validator, false),
+            AccountMeta::new_readonly(sysvar::clock::ID, false),
+            AccountMeta::new_readonly(sysvar::rent::ID, false),
+            AccountMeta::new_readonly(sysvar::stake_history::ID, false),
+            AccountMeta::new_readonly(stake::config::ID, false),
+            AccountMeta::new_readonly(system_program::ID, false),
+            AccountMeta::new_readonly(stake::program::ID, false),
+        ],
+        data: lamports_and_seed_data(INCREASE_VALIDATOR_STAKE, lamports, transient_stake_seed),
+    }
+}
+
+/// `DecreaseValidatorStakeWithReserve`, which replaced the deprecated `DecreaseValidatorStake`
+/// and funds the transient account's rent from the reserve
+#[allow(clippy::too_many_arguments)]
+pub fn decrease_validator_stake(
+    stake_pool: &Pubkey,
+    staker: &Pubkey,
+    withdraw_authority: &Pubkey,
+    validator_list: &Pubkey,
+    reserve_stake: &Pubkey,
+    validator_stake: &Pubkey,
+    transient_stake: &Pubkey,
+    lamports: u64,
+    transient_stake_seed: u64,
+) -> Instruction {
+    Instruction {
+        program_id: ID,
+        accounts: vec![
+            AccountMeta::new_readonly(*stake_pool, false),
+            AccountMeta::new_readonly(*staker, true),
+            AccountMeta::new_readonly(*withdraw_authority, false),
+            AccountMeta::new(*validator_list, false),
+            AccountMeta::new(*reserve_stake, false),
+            AccountMeta::new(*validator_stake, false),
+            AccountMeta::new(*transient_stake, false),
+            AccountMeta::new_readonly(sysvar::clock::ID, false),
+            AccountMeta::new_readonly(sysvar::stake_history::ID, false),
+            AccountMeta::new_readonly(system_program::ID, false),
+            AccountMeta::new_readonly(stake::program::ID, false),
+        ],
+        data: lamports_and_seed_data(
+            DECREASE_VALIDATOR_STAKE_WITH_RESERVE,
+            lamports,
+            transient_stake_seed,
+        ),
+    }
+}
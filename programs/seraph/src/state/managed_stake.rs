@@ -0,0 +1,37 @@
+use std::mem::size_of;
+
+use anchor_lang::prelude::*;
+
+/// A stake account whose owner keeps withdraw authority and hands only the staker authority to
+/// the pool, which delegates it to validators in the VList. It is not part of the pool's stake.
+#[account]
+pub struct ManagedStake {
+    pub pool: Pubkey,
+    pub stake_account: Pubkey,
+    // Withdraw authority of the stake account, who may revoke the pool's staker authority
+    pub owner: Pubkey,
+    pub bump: u8,
+    padding: [u8; 7],
+}
+
+static_assertions::const_assert_eq!(size_of::<ManagedStake>(), 104);
+
+impl ManagedStake {
+    pub const SEED: &'static [u8] = b"managed_stake";
+    pub const SIZE: usize = 8 + size_of::<Self>();
+
+    pub fn pubkey(pool: Pubkey, stake_account: Pubkey) -> Pubkey {
+        Pubkey::find_program_address(
+            &[Self::SEED, pool.as_ref(), stake_account.as_ref()],
+            &crate::ID,
+        )
+        .0
+    }
+
+    pub fn init(&mut self, pool: Pubkey, stake_account: Pubkey, owner: Pubkey, bump: u8) {
+        self.pool = pool;
+        self.stake_account = stake_account;
+        self.owner = owner;
+        self.bump = bump;
+    }
+}
@@ -1,42 +1,653 @@
+pub mod managed_stake;
+pub mod pool_history;
+pub mod stake_registry;
+pub mod withdrawal_ticket;
+
+pub use managed_stake::*;
+pub use pool_history::*;
+pub use stake_registry::*;
+pub use withdrawal_ticket::*;
+
 use std::mem::size_of;
 
 use anchor_lang::prelude::*;
 
-use crate::MAX_VALIDATORS_IN_LIST;
+use crate::{
+    error::ErrorCode, BASIS_POINTS, DEFAULT_EPOCHS_PER_CYCLE, DEFAULT_LOOKBACK_EPOCHS,
+    DEFAULT_MAX_INSTANT_UNSTAKE_FEE_BPS, DEFAULT_MAX_STAKE_MOVED_BPS, DEFAULT_MIN_EPOCHS,
+    DEFAULT_MIN_INSTANT_UNSTAKE_FEE_BPS, DEFAULT_MIN_SCORE_IMPROVEMENT_BPS, DEFAULT_PERCENTILE,
+    DEFAULT_TARGET_RESERVE_BPS, EXCHANGE_RATE_PRECISION, MAX_EPOCHS_PER_CYCLE, MAX_LOOKBACK_EPOCHS,
+    MAX_VALIDATORS_IN_LIST, NORMALIZED_CREDITS, POOL_VERSION, VLIST_VERSION,
+};
 
 #[account]
-#[derive(Default)]
 pub struct Pool {
     pub admin: Pubkey,
     pub start_slot: u64,
     pub start_epoch: u64,
     pub bump: u8,
+    // Layout version, POOL_VERSION once initialized or migrated. Kept ahead of any variable-length
+    // field so it is read at VERSION_OFFSET in every layout, where pools written before
+    // versioning hold a zero padding byte
+    pub version: u8,
+    // Bump of the system-owned reserve holding lamports withdrawn from stake
+    pub reserve_bump: u8,
+    // Pool token minted to the manager as fees
+    pub pool_mint: Pubkey,
+    // Token account receiving the manager's fees
+    pub manager_fee_account: Pubkey,
+    // Lamports held across the pool's stake accounts and reserve as of `last_update_epoch`
+    pub total_lamports: u64,
+    pub last_update_epoch: u64,
+    pub fees: Fees,
+    // Fees staged by the manager, applied once `next_fees_epoch` is reached
+    pub next_fees: Option<Fees>,
+    pub next_fees_epoch: u64,
+    // Highest lamports per pool token seen, scaled by EXCHANGE_RATE_PRECISION
+    pub high_water_mark: u64,
+    // Rewards and fees measured by the last balance update
+    pub last_epoch_rewards: u64,
+    pub last_epoch_fees: u64,
+    pub rebalance_params: RebalanceParams,
+    // First epoch of the current rebalancing cycle and the stake redelegated since
+    pub cycle_start_epoch: u64,
+    pub stake_moved_in_cycle: u64,
+    // Authority allowed to pause the pool in an incident, the admin by default
+    pub guardian: Pubkey,
+    // While set, only deactivation and withdrawals to the reserve are allowed
+    pub paused: bool,
+    pub params: PoolParams,
+    // Seed of the next withdrawal ticket
+    pub next_ticket_id: u64,
+    // Lamports owed to unclaimed withdrawal tickets, excluded from `total_lamports`
+    pub pending_withdrawal_lamports: u64,
+    pub reserve_params: ReserveParams,
+    // MEV tips harvested into the reserve since the last balance update
+    pub harvested_mev_lamports: u64,
+    // Share of the last balance update's rewards that came from harvested MEV tips
+    pub last_epoch_mev_rewards: u64,
+    // SPL stake pool this pool is the staker of, default while none is linked
+    pub stake_pool: Pubkey,
+    pub scoring_params: ScoringParams,
+    // ManagedStake accounts registered and not yet revoked
+    pub managed_stake_count: u64,
+    // Tickets below this id are covered by stake the unstake queue deactivated in `unstaked_epoch`
+    pub unstaked_ticket_id: u64,
+    pub unstaked_epoch: u64,
+    // Tickets below this id are covered by stake that has finished cooling down
+    pub claimable_ticket_id: u64,
+    // Space for future fields, zeroed
+    reserved: [u8; 2],
+}
+
+#[derive(AnchorDeserialize, AnchorSerialize, Default, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Fees {
+    // Share of each epoch's rewards, in basis points
+    pub management_fee_bps: u16,
+    // Share of rewards earned above the high-water mark, in basis points
+    pub performance_fee_bps: u16,
+}
+
+#[derive(AnchorDeserialize, AnchorSerialize, Default, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct RebalanceParams {
+    // Score gain over the incumbent validator required to move stake, in basis points
+    pub min_score_improvement_bps: u16,
+    // Share of the pool's lamports that may be redelegated per cycle, in basis points
+    pub max_stake_moved_bps: u16,
+}
+
+#[derive(AnchorDeserialize, AnchorSerialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum RebalanceRejection {
+    // The new validator does not beat the incumbent's score by the minimum improvement
+    InsufficientScoreImprovement,
+    // The move does not fit in what is left of the cycle's redelegation budget
+    BudgetExceeded,
+}
+
+#[derive(AnchorDeserialize, AnchorSerialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum RemovalReason {
+    // An epoch of the lookback was missing, under MissingEpochPolicy::Ineligible
+    MissingEpochs,
+    // The list was shrunk below the validator's rank
+    ListTruncated,
+}
+
+#[derive(AnchorDeserialize, AnchorSerialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct ReserveParams {
+    // Share of the pool's lamports kept liquid in the reserve, in basis points
+    pub target_reserve_bps: u16,
+    // Instant unstake fee while the reserve stays at or above its target, in basis points
+    pub min_fee_bps: u16,
+    // Instant unstake fee for draining the reserve completely, in basis points
+    pub max_fee_bps: u16,
+}
+
+// Zeroed, the default, for pools migrated from before scoring options existed
+#[derive(AnchorDeserialize, AnchorSerialize, Default, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct ScoringParams {
+    // Largest decentralization bonus, in basis points of the score, 0 disables the bonus
+    pub max_decentralization_bonus_bps: u16,
+    // Validators with less activated stake than this earn the stake bonus, 0 to disable
+    pub stake_threshold_lamports: u64,
+    // Validators ranked outside this many largest by stake earn the rank bonus, 0 to disable
+    pub top_n_rank: u32,
+    pub bonus_curve: BonusCurve,
+    // Weight of each epoch relative to the one after it, in percent, for an exponentially decayed
+    // average over every epoch in the lookback with missing epochs scoring 0. 0 keeps the flat
+    // average over the epochs with history
+    pub decay_percent: u8,
+    pub missing_epoch_policy: MissingEpochPolicy,
+    // Scores each epoch's credits relative to the cluster's highest, out of NORMALIZED_CREDITS.
+    // Completed epochs without a recorded cluster reference count as missing. The current epoch
+    // never has one yet, so it is left out of the lookback
+    pub normalize_credits: bool,
+    // Weight of the leader slot skip rate against each epoch's score, in basis points: at 10_000 a
+    // validator skipping half its leader slots keeps half the score. 0 ignores block production
+    pub skip_rate_weight_bps: u16,
+    // Space for future scoring options, zeroed, which keeps the struct free of alignment padding
+    pub padding: [u8; 4],
+}
+
+#[derive(AnchorDeserialize, AnchorSerialize, Default, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum MissingEpochPolicy {
+    // Average over the epochs with history only, counting entries never recorded by the oracle
+    #[default]
+    Skip,
+    // Epochs without history, or with credits or commission never recorded, score 0
+    Zero,
+    // A validator missing any epoch of the lookback is dropped from the VList
+    Ineligible,
+}
+
+#[derive(AnchorDeserialize, AnchorSerialize, Default, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum BonusCurve {
+    // The full bonus past the threshold
+    #[default]
+    Step,
+    // The stake bonus grows as stake falls to zero, the rank bonus grows until twice the top N
+    Linear,
+}
+
+#[derive(AnchorDeserialize, AnchorSerialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct PoolParams {
+    // Length of a rebalancing cycle, over which the redelegation budget applies
+    pub epochs_per_cycle: u16,
+    // Epochs of validator history averaged into a score
+    pub lookback_epochs: u16,
+    // Validators kept in the VList, at most MAX_VALIDATORS_IN_LIST
+    pub max_validators: u16,
+    // Share of the VList eligible for stake, in percent
+    pub percentile: u8,
+    // Epochs of history within the lookback a validator needs to be scored
+    pub min_epochs: u16,
+}
+
+static_assertions::const_assert_eq!(size_of::<Pool>(), 368);
+
+/// Pool as written before versioning, before it held a mint, a reserve or any parameters. Read
+/// by `migrate_pool` to rebuild the pool in the current layout.
+#[derive(AnchorDeserialize)]
+pub struct PoolV0 {
+    pub admin: Pubkey,
+    pub start_slot: u64,
+    pub start_epoch: u64,
+    pub bump: u8,
+}
+
+impl PoolV0 {
+    // Account size, padded as the struct was
+    pub const SIZE: usize = 8 + 56;
+}
+
+impl Fees {
+    pub fn validate(&self) -> Result<()> {
+        let total_bps = self.management_fee_bps as u64 + self.performance_fee_bps as u64;
+        if total_bps > BASIS_POINTS {
+            return Err(ErrorCode::InvalidFee.into());
+        }
+        Ok(())
+    }
+
+    /// Lamports owed to the manager for `rewards`, of which `excess_rewards` were earned above
+    /// the high-water mark
+    pub fn fee_lamports(&self, rewards: u64, excess_rewards: u64) -> Option<u64> {
+        let management = (rewards as u128)
+            .checked_mul(self.management_fee_bps as u128)?
+            .checked_div(BASIS_POINTS as u128)?;
+        let performance = (excess_rewards as u128)
+            .checked_mul(self.performance_fee_bps as u128)?
+            .checked_div(BASIS_POINTS as u128)?;
+        u64::try_from(management.checked_add(performance)?).ok()
+    }
+}
+
+impl Default for PoolParams {
+    fn default() -> Self {
+        Self {
+            epochs_per_cycle: DEFAULT_EPOCHS_PER_CYCLE,
+            lookback_epochs: DEFAULT_LOOKBACK_EPOCHS,
+            max_validators: MAX_VALIDATORS_IN_LIST as u16,
+            percentile: DEFAULT_PERCENTILE,
+            min_epochs: DEFAULT_MIN_EPOCHS,
+        }
+    }
+}
+
+impl PoolParams {
+    pub fn validate(&self) -> Result<()> {
+        if !(1..=MAX_EPOCHS_PER_CYCLE).contains(&self.epochs_per_cycle)
+            || !(1..=MAX_LOOKBACK_EPOCHS).contains(&self.lookback_epochs)
+            || !(1..=MAX_VALIDATORS_IN_LIST as u16).contains(&self.max_validators)
+            || !(1..=100).contains(&self.percentile)
+            || !(1..=self.lookback_epochs).contains(&self.min_epochs)
+        {
+            return Err(ErrorCode::InvalidPoolParams.into());
+        }
+        Ok(())
+    }
+
+    /// Number of the `list_len` top validators eligible for stake, at least one of a non-empty list
+    pub fn eligible_validators(&self, list_len: usize) -> usize {
+        (list_len * self.percentile as usize / 100).max(list_len.min(1))
+    }
+}
+
+impl Default for ReserveParams {
+    fn default() -> Self {
+        Self {
+            target_reserve_bps: DEFAULT_TARGET_RESERVE_BPS,
+            min_fee_bps: DEFAULT_MIN_INSTANT_UNSTAKE_FEE_BPS,
+            max_fee_bps: DEFAULT_MAX_INSTANT_UNSTAKE_FEE_BPS,
+        }
+    }
+}
+
+impl ReserveParams {
+    pub fn validate(&self) -> Result<()> {
+        if self.target_reserve_bps as u64 > BASIS_POINTS
+            || self.max_fee_bps as u64 > BASIS_POINTS
+            || self.min_fee_bps > self.max_fee_bps
+        {
+            return Err(ErrorCode::InvalidReserveParams.into());
+        }
+        Ok(())
+    }
+
+    /// Lamports the reserve should hold for a pool of `total_lamports`
+    pub fn target_reserve_lamports(&self, total_lamports: u64) -> u64 {
+        ((total_lamports as u128) * (self.target_reserve_bps as u128) / (BASIS_POINTS as u128))
+            as u64
+    }
+
+    /// Instant unstake fee, in basis points, for leaving `reserve_after` lamports in a reserve
+    /// targeting `target_reserve`. Rises linearly from the minimum fee at the target to the
+    /// maximum fee for an empty reserve.
+    pub fn fee_bps(&self, reserve_after: u64, target_reserve: u64) -> u16 {
+        if reserve_after >= target_reserve {
+            return self.min_fee_bps;
+        }
+        let drained = (target_reserve - reserve_after) as u128;
+        let fee_range = (self.max_fee_bps - self.min_fee_bps) as u128;
+        self.min_fee_bps + (fee_range * drained / target_reserve as u128) as u16
+    }
 }
 
-static_assertions::const_assert_eq!(size_of::<Pool>(), 56);
+impl ScoringParams {
+    pub fn validate(&self) -> Result<()> {
+        if self.max_decentralization_bonus_bps as u64 > BASIS_POINTS
+            || self.decay_percent > 100
+            || self.skip_rate_weight_bps as u64 > BASIS_POINTS
+        {
+            return Err(ErrorCode::InvalidScoringParams.into());
+        }
+        Ok(())
+    }
+
+    /// Average of `epoch_scores`, oldest first, decayed or flat and with missing epochs skipped or
+    /// scoring 0, as configured
+    pub fn average(&self, epoch_scores: &[Option<u32>]) -> u32 {
+        if self.decay_percent != 0 {
+            return self.decayed_average(epoch_scores);
+        }
+        let total_score: u64 = epoch_scores
+            .iter()
+            .flatten()
+            .map(|&score| score as u64)
+            .sum();
+        let epochs = match self.missing_epoch_policy {
+            MissingEpochPolicy::Zero => epoch_scores.len(),
+            MissingEpochPolicy::Skip | MissingEpochPolicy::Ineligible => {
+                epoch_scores.iter().flatten().count()
+            }
+        };
+        if epochs == 0 {
+            return 0;
+        }
+        (total_score / epochs as u64) as u32
+    }
+
+    /// Average of `epoch_scores`, oldest first, with each epoch weighing `decay_percent` of the
+    /// one after it. Missing epochs score 0 rather than being skipped.
+    pub fn decayed_average(&self, epoch_scores: &[Option<u32>]) -> u32 {
+        // Weight of the latest epoch, scaled so that 50 epochs of decay keep precision
+        const LATEST_WEIGHT: u128 = 1_000_000_000_000;
+
+        let mut weight = LATEST_WEIGHT;
+        let mut weighted_scores: u128 = 0;
+        let mut total_weight: u128 = 0;
+        for epoch_score in epoch_scores.iter().rev() {
+            weighted_scores += weight * epoch_score.unwrap_or(0) as u128;
+            total_weight += weight;
+            weight = weight * self.decay_percent as u128 / 100;
+        }
+        if total_weight == 0 {
+            return 0;
+        }
+        (weighted_scores / total_weight) as u32
+    }
+
+    /// Score bonus, in basis points, for a validator with `activated_stake_lamports` ranked
+    /// `rank` by stake, counting from 0. The larger of the stake and rank bonuses applies.
+    pub fn decentralization_bonus_bps(&self, activated_stake_lamports: u64, rank: u32) -> u16 {
+        let max_bonus = self.max_decentralization_bonus_bps as u64;
+        let threshold = self.stake_threshold_lamports;
+        let stake_bonus = if threshold > 0 && activated_stake_lamports < threshold {
+            match self.bonus_curve {
+                BonusCurve::Step => max_bonus,
+                BonusCurve::Linear => {
+                    ((max_bonus as u128) * ((threshold - activated_stake_lamports) as u128)
+                        / threshold as u128) as u64
+                }
+            }
+        } else {
+            0
+        };
+        let top_n = self.top_n_rank as u64;
+        let rank_bonus = if top_n > 0 && rank as u64 >= top_n {
+            match self.bonus_curve {
+                BonusCurve::Step => max_bonus,
+                BonusCurve::Linear => max_bonus * (rank as u64 - top_n).min(top_n) / top_n,
+            }
+        } else {
+            0
+        };
+        stake_bonus.max(rank_bonus) as u16
+    }
+
+    /// `score` raised by `bonus_bps`
+    pub fn apply_bonus(score: u32, bonus_bps: u16) -> u32 {
+        ((score as u64) * (BASIS_POINTS + bonus_bps as u64) / BASIS_POINTS).min(u32::MAX as u64)
+            as u32
+    }
+
+    /// Reduces an epoch's score by the share of its leader slots the validator skipped, weighed by
+    /// `skip_rate_weight_bps`. Epochs without leader slots are not penalized.
+    pub fn apply_skip_rate(&self, score: u32, leader_slots: u32, blocks_produced: u32) -> u32 {
+        if leader_slots == 0 {
+            return score;
+        }
+        let skipped_slots = leader_slots.saturating_sub(blocks_produced) as u64;
+        let penalty = self.skip_rate_weight_bps as u64 * skipped_slots;
+        let kept = BASIS_POINTS * leader_slots as u64 - penalty;
+        (score as u64 * kept / (BASIS_POINTS * leader_slots as u64)) as u32
+    }
+
+    /// `epoch_credits` out of NORMALIZED_CREDITS for the cluster's highest credits in the epoch,
+    /// None without a reference to compare against
+    pub fn normalized_credits(epoch_credits: u32, max_epoch_credits: u32) -> Option<u32> {
+        if max_epoch_credits == 0 {
+            return None;
+        }
+        Some(
+            (epoch_credits as u64 * NORMALIZED_CREDITS / max_epoch_credits as u64)
+                .min(NORMALIZED_CREDITS) as u32,
+        )
+    }
+}
+
+impl RebalanceParams {
+    pub fn validate(&self) -> Result<()> {
+        if self.min_score_improvement_bps as u64 > BASIS_POINTS
+            || self.max_stake_moved_bps as u64 > BASIS_POINTS
+        {
+            return Err(ErrorCode::InvalidRebalanceParams.into());
+        }
+        Ok(())
+    }
+
+    /// Whether `new_score` beats `incumbent_score` by at least the minimum improvement
+    pub fn is_score_improvement(&self, incumbent_score: u32, new_score: u32) -> bool {
+        (new_score as u128) * (BASIS_POINTS as u128)
+            >= (incumbent_score as u128)
+                * (BASIS_POINTS as u128 + self.min_score_improvement_bps as u128)
+    }
+
+    /// Lamports that may be redelegated per cycle out of `total_lamports`
+    pub fn max_stake_moved(&self, total_lamports: u64) -> Option<u64> {
+        u64::try_from(
+            (total_lamports as u128)
+                .checked_mul(self.max_stake_moved_bps as u128)?
+                .checked_div(BASIS_POINTS as u128)?,
+        )
+        .ok()
+    }
+}
 
 impl Pool {
     pub const SEED: &'static [u8] = b"pool";
+    pub const MINT_SEED: &'static [u8] = b"pool_mint";
+    pub const MANAGER_FEE_SEED: &'static [u8] = b"manager_fee";
+    pub const RESERVE_SEED: &'static [u8] = b"reserve";
+    pub const TRANSIENT_SEED: &'static [u8] = b"transient";
     pub const SIZE: usize = 8 + size_of::<Self>();
+    // Offset of `version` in the account data, after the discriminator, admin, start slot and
+    // epoch, and bump
+    pub const VERSION_OFFSET: usize = 8 + 32 + 8 + 8 + 1;
 
     pub fn pubkey(admin: Pubkey) -> Pubkey {
         Pubkey::find_program_address(&[Self::SEED, admin.as_ref()], &crate::ID).0
     }
 
+    pub fn mint_pubkey(pool: Pubkey) -> Pubkey {
+        Pubkey::find_program_address(&[Self::MINT_SEED, pool.as_ref()], &crate::ID).0
+    }
+
+    pub fn manager_fee_pubkey(pool: Pubkey) -> Pubkey {
+        Pubkey::find_program_address(&[Self::MANAGER_FEE_SEED, pool.as_ref()], &crate::ID).0
+    }
+
+    pub fn reserve_pubkey(pool: Pubkey) -> Pubkey {
+        Pubkey::find_program_address(&[Self::RESERVE_SEED, pool.as_ref()], &crate::ID).0
+    }
+
+    /// Transient stake account deactivating stake away from `validator_vote`
+    pub fn transient_stake_pubkey(pool: Pubkey, validator_vote: Pubkey, nonce: u64) -> Pubkey {
+        Pubkey::find_program_address(
+            &[
+                Self::TRANSIENT_SEED,
+                pool.as_ref(),
+                validator_vote.as_ref(),
+                &nonce.to_le_bytes(),
+            ],
+            &crate::ID,
+        )
+        .0
+    }
+
+    /// Reserve lamports available to the pool, excluding the reserve's rent-exempt minimum
+    pub fn reserve_lamports(reserve: &AccountInfo) -> Result<u64> {
+        Ok(reserve
+            .lamports()
+            .saturating_sub(Rent::get()?.minimum_balance(0)))
+    }
+
+    #[allow(clippy::too_many_arguments)]
     pub fn init(
         &mut self,
         admin: &Pubkey,
         start_slot: u64,
         start_epoch: u64,
         bump: u8,
+        reserve_bump: u8,
+        pool_mint: Pubkey,
+        manager_fee_account: Pubkey,
+        params: PoolParams,
     ) -> Result<()> {
+        params.validate()?;
+
         self.admin = *admin;
         self.start_slot = start_slot;
         self.start_epoch = start_epoch;
         self.bump = bump;
+        self.reserve_bump = reserve_bump;
+        self.pool_mint = pool_mint;
+        self.manager_fee_account = manager_fee_account;
+        self.total_lamports = 0;
+        self.last_update_epoch = start_epoch;
+        self.fees = Fees::default();
+        self.next_fees = None;
+        self.next_fees_epoch = 0;
+        self.high_water_mark = 0;
+        self.last_epoch_rewards = 0;
+        self.last_epoch_fees = 0;
+        self.rebalance_params = RebalanceParams {
+            min_score_improvement_bps: DEFAULT_MIN_SCORE_IMPROVEMENT_BPS,
+            max_stake_moved_bps: DEFAULT_MAX_STAKE_MOVED_BPS,
+        };
+        self.cycle_start_epoch = start_epoch;
+        self.stake_moved_in_cycle = 0;
+        self.guardian = *admin;
+        self.paused = false;
+        self.params = params;
+        self.version = POOL_VERSION;
+        self.reserve_params = ReserveParams::default();
+        self.scoring_params = ScoringParams::default();
+
+        Ok(())
+    }
 
+    /// Starts a new rebalancing cycle every `epochs_per_cycle` epochs. Returns the lamports that
+    /// may be redelegated over the current cycle.
+    pub fn cycle_budget(&mut self, epoch: u64) -> Result<u64> {
+        let epochs_per_cycle = self.params.epochs_per_cycle as u64;
+        if epoch >= self.cycle_start_epoch + epochs_per_cycle {
+            self.cycle_start_epoch = epoch - epoch % epochs_per_cycle;
+            self.stake_moved_in_cycle = 0;
+        }
+
+        Ok(self
+            .rebalance_params
+            .max_stake_moved(self.total_lamports)
+            .ok_or(ErrorCode::ArithmeticError)?)
+    }
+
+    /// Lamports per pool token, scaled by EXCHANGE_RATE_PRECISION. 1:1 while no tokens exist.
+    pub fn exchange_rate(total_lamports: u64, pool_token_supply: u64) -> Option<u64> {
+        if pool_token_supply == 0 {
+            return Some(EXCHANGE_RATE_PRECISION);
+        }
+        u64::try_from(
+            (total_lamports as u128)
+                .checked_mul(EXCHANGE_RATE_PRECISION as u128)?
+                .checked_div(pool_token_supply as u128)?,
+        )
+        .ok()
+    }
+
+    /// Fails unless the balance was updated in `epoch`, so pool tokens are never priced before
+    /// the epoch's rewards and fees are booked
+    pub fn check_balance_fresh(&self, epoch: u64) -> Result<()> {
+        if self.last_update_epoch != epoch {
+            return Err(ErrorCode::PoolBalanceStale.into());
+        }
         Ok(())
     }
+
+    /// Marks every ticket requested so far as covered by stake deactivated in `epoch`. Tickets
+    /// covered in an earlier epoch have cooled down by then.
+    pub fn mark_tickets_unstaked(&mut self, epoch: u64) {
+        if epoch > self.unstaked_epoch {
+            self.claimable_ticket_id = self.unstaked_ticket_id;
+        }
+        self.unstaked_ticket_id = self.next_ticket_id;
+        self.unstaked_epoch = epoch;
+    }
+
+    /// Whether the stake deactivated to cover ticket `id` is inactive in `epoch`
+    pub fn is_ticket_claimable(&self, id: u64, epoch: u64) -> bool {
+        id < self.claimable_ticket_id
+            || (id < self.unstaked_ticket_id && epoch > self.unstaked_epoch)
+    }
+
+    /// Lamports redeemed by `pool_tokens` at the pool's current exchange rate
+    pub fn lamports_for_pool_tokens(
+        &self,
+        pool_tokens: u64,
+        pool_token_supply: u64,
+    ) -> Option<u64> {
+        if pool_token_supply == 0 {
+            return None;
+        }
+        u64::try_from(
+            (pool_tokens as u128)
+                .checked_mul(self.total_lamports as u128)?
+                .checked_div(pool_token_supply as u128)?,
+        )
+        .ok()
+    }
+
+    /// Portion of `rewards` that lifts the pool above its high-water mark
+    pub fn excess_rewards(
+        &self,
+        total_lamports: u64,
+        rewards: u64,
+        pool_token_supply: u64,
+    ) -> Option<u64> {
+        if pool_token_supply == 0 {
+            return Some(rewards);
+        }
+        let high_water_lamports = (self.high_water_mark as u128)
+            .checked_mul(pool_token_supply as u128)?
+            .checked_div(EXCHANGE_RATE_PRECISION as u128)?;
+        let excess = (total_lamports as u128).saturating_sub(high_water_lamports);
+        Some(u64::try_from(excess).ok()?.min(rewards))
+    }
+
+    /// Pool tokens minted for a deposit of `lamports` into a pool of `total_lamports`, 1:1 while no
+    /// tokens exist
+    pub fn pool_tokens_for_deposit(
+        lamports: u64,
+        total_lamports: u64,
+        pool_token_supply: u64,
+    ) -> Option<u64> {
+        if pool_token_supply == 0 {
+            return Some(lamports);
+        }
+        u64::try_from(
+            (lamports as u128)
+                .checked_mul(pool_token_supply as u128)?
+                .checked_div(total_lamports as u128)?,
+        )
+        .ok()
+    }
+
+    /// Pool tokens to mint so that the manager owns `fee_lamports` of `total_lamports`. Nothing is
+    /// minted while no tokens exist, since the first tokens would claim the whole pool.
+    pub fn pool_tokens_for_fee(
+        fee_lamports: u64,
+        total_lamports: u64,
+        pool_token_supply: u64,
+    ) -> Option<u64> {
+        if pool_token_supply == 0 || fee_lamports == 0 || fee_lamports >= total_lamports {
+            return Some(0);
+        }
+        u64::try_from(
+            (fee_lamports as u128)
+                .checked_mul(pool_token_supply as u128)?
+                .checked_div((total_lamports - fee_lamports) as u128)?,
+        )
+        .ok()
+    }
 }
 
 #[account]
@@ -47,6 +658,10 @@ pub struct VList {
     pub pool: Pubkey,
     pub bump: u8,
     padding: [u8; 7],
+    // Layout version, VLIST_VERSION once initialized or migrated
+    pub version: u8,
+    // Space for future fields, zeroed
+    reserved: [[u8; 32]; 4],
 }
 
 #[derive(AnchorDeserialize, AnchorSerialize, Default, Clone, Copy)]
@@ -54,9 +669,87 @@ pub struct VListEntry {
     pub validator: Pubkey,
     pub last_scored_epoch: u64,
     pub score: u32,
+    // How `score` was reached, zeroed for entries migrated from version 1 until scored again. The
+    // ELIGIBLE flag is refreshed whenever the list or its parameters change.
+    pub components: ScoreComponents,
+}
+
+/// Breakdown of a validator's score: `credits - commission_penalty - skip_rate_penalty +
+/// decentralization_bonus`, each term averaged over the epochs scored the way the score is
+#[derive(AnchorDeserialize, AnchorSerialize, Default, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct ScoreComponents {
+    // Epoch credits, relative to the cluster's highest when the pool normalizes credits
+    pub credits: u32,
+    // Credits paid out as commission
+    pub commission_penalty: u32,
+    // Credits lost for skipped leader slots
+    pub skip_rate_penalty: u32,
+    pub decentralization_bonus: u32,
+    // Lookback the validator was scored over, inclusive, and how many of its epochs were not
+    // missing
+    pub start_epoch: u16,
+    pub end_epoch: u16,
+    pub epochs_scored: u8,
+    // ScoreComponents::* flags
+    pub flags: u8,
+    pub padding: [u8; 2],
+}
+
+impl ScoreComponents {
+    // Among the pool's percentile eligible for stake
+    pub const ELIGIBLE: u8 = 1 << 0;
+    // Some epochs of the lookback had no history, or no cluster reference when normalizing
+    pub const MISSING_EPOCHS: u8 = 1 << 1;
+    // Credits were normalized against the cluster's highest
+    pub const NORMALIZED: u8 = 1 << 2;
+}
+
+/// VList layout of version 1, which accounts written before versioning share once grown, with
+/// their version reading as 0. Only read to migrate them.
+#[derive(AnchorDeserialize)]
+pub struct VListV1 {
+    pub validators: [VListEntryV1; MAX_VALIDATORS_IN_LIST],
+    pub idx: usize,
+    pub admin: Pubkey,
+    pub pool: Pubkey,
+    pub bump: u8,
+    _padding: [u8; 7],
+    pub version: u8,
+    _reserved: [[u8; 32]; 4],
 }
 
-static_assertions::const_assert_eq!(size_of::<VList>(), 4880);
+#[derive(AnchorDeserialize, Default, Clone, Copy)]
+pub struct VListEntryV1 {
+    pub validator: Pubkey,
+    pub last_scored_epoch: u64,
+    pub score: u32,
+}
+
+impl From<&VListV1> for VList {
+    fn from(v_list: &VListV1) -> Self {
+        let mut validators = [VListEntry::default(); MAX_VALIDATORS_IN_LIST];
+        for (entry, old_entry) in validators.iter_mut().zip(v_list.validators.iter()) {
+            *entry = VListEntry {
+                validator: old_entry.validator,
+                last_scored_epoch: old_entry.last_scored_epoch,
+                score: old_entry.score,
+                components: ScoreComponents::default(),
+            };
+        }
+        Self {
+            validators,
+            idx: v_list.idx,
+            admin: v_list.admin,
+            pool: v_list.pool,
+            bump: v_list.bump,
+            padding: [0; 7],
+            version: VLIST_VERSION,
+            reserved: [[0; 32]; 4],
+        }
+    }
+}
+
+static_assertions::const_assert_eq!(size_of::<VList>(), 7416);
 
 impl VList {
     pub const SEED: &'static [u8] = b"v_list";
@@ -72,40 +765,124 @@ impl VList {
         self.validators = [VListEntry::default(); MAX_VALIDATORS_IN_LIST];
         self.idx = 0;
         self.pool = pool;
+        self.version = VLIST_VERSION;
 
         Ok(())
     }
 
+    /// Whether `validator_pubkey` is in the top percentile of the list eligible for stake
+    pub fn is_eligible(&self, validator_pubkey: &Pubkey, params: &PoolParams) -> bool {
+        self.validators[0..params.eligible_validators(self.idx)]
+            .iter()
+            .any(|entry| entry.validator == *validator_pubkey)
+    }
+
+    /// Sets the ELIGIBLE flag of the listed validators in the top percentile and clears it on the
+    /// rest
+    pub fn refresh_eligibility(&mut self, params: &PoolParams) {
+        let eligible_count = params.eligible_validators(self.idx);
+        for (rank, entry) in self.validators[0..self.idx].iter_mut().enumerate() {
+            if rank < eligible_count {
+                entry.components.flags |= ScoreComponents::ELIGIBLE;
+            } else {
+                entry.components.flags &= !ScoreComponents::ELIGIBLE;
+            }
+        }
+    }
+
+    /// Listed validators, highest score first
+    pub fn ranking(&self) -> Vec<Pubkey> {
+        self.validators[0..self.idx]
+            .iter()
+            .map(|entry| entry.validator)
+            .collect()
+    }
+
+    pub fn get_score(&self, validator_pubkey: &Pubkey) -> Option<u32> {
+        self.validators[0..self.idx]
+            .iter()
+            .find(|entry| entry.validator == *validator_pubkey)
+            .map(|entry| entry.score)
+    }
+
+    /// Scores a validator into the list, adding it while the list is shorter than
+    /// `max_validators`, and keeps the list sorted by descending score. Returns the validator's
+    /// score and rank before and after.
     pub fn insert_or_update(
         &mut self,
         validator_pubkey: Pubkey,
         new_score: u32,
+        components: ScoreComponents,
         current_epoch: u64,
-    ) {
+        max_validators: usize,
+    ) -> VListUpdate {
+        let mut update = VListUpdate::default();
+
         // Search for the validator in the list
-        let mut found = false;
         for i in 0..self.idx {
             if self.validators[i].validator == validator_pubkey {
+                update.old_score = Some(self.validators[i].score);
+                update.old_rank = Some(i);
                 self.validators[i].score = new_score;
+                self.validators[i].components = components;
                 self.validators[i].last_scored_epoch = current_epoch;
-                found = true;
                 break;
             }
         }
 
         // If not found, add a new validator entry
-        if !found && self.idx < MAX_VALIDATORS_IN_LIST {
+        if update.old_rank.is_none() && self.idx < max_validators.min(MAX_VALIDATORS_IN_LIST) {
             self.validators[self.idx] = VListEntry {
                 validator: validator_pubkey,
                 last_scored_epoch: current_epoch,
                 score: new_score,
+                components,
             };
 
             self.idx += 1;
         }
 
         self.validators[0..self.idx].sort_by(|a, b| b.score.cmp(&a.score));
+
+        update.new_rank = self.validators[0..self.idx]
+            .iter()
+            .position(|entry| entry.validator == validator_pubkey);
+        update
+    }
+
+    /// Takes a validator out of the list, keeping it sorted. Returns its rank and entry, None when
+    /// it was not listed.
+    pub fn remove(&mut self, validator_pubkey: &Pubkey) -> Option<(usize, VListEntry)> {
+        let rank = self.validators[0..self.idx]
+            .iter()
+            .position(|entry| entry.validator == *validator_pubkey)?;
+        let entry = self.validators[rank];
+        self.validators.copy_within(rank + 1..self.idx, rank);
+        self.idx -= 1;
+        self.validators[self.idx] = VListEntry::default();
+        Some((rank, entry))
     }
+
+    /// Drops the lowest-scored validators beyond `max_validators`, returning their entries, which
+    /// ranked from `max_validators` on
+    pub fn truncate(&mut self, max_validators: usize) -> Vec<VListEntry> {
+        let mut removed = vec![];
+        for entry in self.validators[max_validators.min(self.idx)..self.idx].iter_mut() {
+            removed.push(*entry);
+            *entry = VListEntry::default();
+        }
+        self.idx = self.idx.min(max_validators);
+        removed
+    }
+}
+
+#[derive(Default, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct VListUpdate {
+    // None while the validator was not in the list
+    pub old_score: Option<u32>,
+    pub old_rank: Option<usize>,
+    // None when the list is full and the validator could not be added
+    pub new_rank: Option<usize>,
 }
 
 impl TryFrom<Vec<u8>> for VList {
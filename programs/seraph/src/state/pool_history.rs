@@ -0,0 +1,116 @@
+use std::mem::size_of;
+
+use anchor_lang::prelude::*;
+
+const MAX_ITEMS: usize = 100;
+
+static_assertions::const_assert_eq!(size_of::<PoolHistoryEntry>(), 96);
+
+#[zero_copy]
+pub struct PoolHistoryEntry {
+    pub epoch: u64,
+    // Lamports across the pool's stake accounts and reserve as of the epoch's balance update
+    pub total_lamports: u64,
+    pub active_stake_lamports: u64,
+    pub activating_stake_lamports: u64,
+    // Lamports in transient stake accounts moving between validators or back to the reserve
+    pub transient_stake_lamports: u64,
+    // Stake growth measured since the previous balance update
+    pub rewards_lamports: u64,
+    // Portion of the rewards taken by the manager
+    pub fee_lamports: u64,
+    pub pool_token_supply: u64,
+    // Lamports per pool token, scaled by EXCHANGE_RATE_PRECISION
+    pub exchange_rate: u64,
+    pub padding: [u8; 24],
+}
+
+impl Default for PoolHistoryEntry {
+    fn default() -> Self {
+        Self {
+            epoch: u64::MAX,
+            total_lamports: u64::MAX,
+            active_stake_lamports: u64::MAX,
+            activating_stake_lamports: u64::MAX,
+            transient_stake_lamports: u64::MAX,
+            rewards_lamports: u64::MAX,
+            fee_lamports: u64::MAX,
+            pool_token_supply: u64::MAX,
+            exchange_rate: u64::MAX,
+            padding: [u8::MAX; 24],
+        }
+    }
+}
+
+#[zero_copy]
+pub struct CircBufPool {
+    pub idx: u64,
+    pub is_empty: u8,
+    pub padding: [u8; 7],
+    pub arr: [PoolHistoryEntry; MAX_ITEMS],
+}
+
+impl CircBufPool {
+    pub fn init(&mut self) {
+        self.idx = (self.arr.len() - 1) as u64;
+        self.arr = [PoolHistoryEntry::default(); MAX_ITEMS];
+        self.is_empty = 1;
+    }
+
+    pub fn push(&mut self, item: PoolHistoryEntry) {
+        self.idx = (self.idx + 1) % self.arr.len() as u64;
+        self.arr[self.idx as usize] = item;
+        self.is_empty = 0;
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.is_empty == 1
+    }
+
+    pub fn last(&self) -> Option<&PoolHistoryEntry> {
+        if self.is_empty() {
+            None
+        } else {
+            Some(&self.arr[self.idx as usize])
+        }
+    }
+
+    pub fn last_mut(&mut self) -> Option<&mut PoolHistoryEntry> {
+        if self.is_empty() {
+            None
+        } else {
+            Some(&mut self.arr[self.idx as usize])
+        }
+    }
+}
+
+static_assertions::const_assert_eq!(size_of::<PoolHistory>(), 9656);
+
+#[account(zero_copy)]
+pub struct PoolHistory {
+    pub pool: Pubkey,
+    pub bump: u8,
+    pub _padding0: [u8; 7],
+    pub history: CircBufPool,
+}
+
+impl PoolHistory {
+    pub const SEED: &'static [u8] = b"pool_history";
+    pub const SIZE: usize = 8 + size_of::<Self>();
+    pub const MAX_ITEMS: usize = MAX_ITEMS;
+
+    pub fn pubkey(pool: Pubkey) -> Pubkey {
+        Pubkey::find_program_address(&[Self::SEED, pool.as_ref()], &crate::ID).0
+    }
+
+    /// Records `entry` for its epoch, overwriting an earlier record from the same epoch
+    pub fn record(&mut self, entry: PoolHistoryEntry) {
+        if let Some(last) = self.history.last_mut() {
+            if last.epoch == entry.epoch {
+                *last = entry;
+                return;
+            }
+        }
+        self.history.push(entry);
+    }
+}
@@ -0,0 +1,121 @@
+use std::mem::size_of;
+
+use anchor_lang::prelude::*;
+
+use crate::{error::ErrorCode, MAX_STAKE_ACCOUNTS};
+
+#[derive(AnchorDeserialize, AnchorSerialize, Default, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum StakeStatus {
+    #[default]
+    Inactive,
+    Activating,
+    Active,
+    Deactivating,
+}
+
+#[derive(AnchorDeserialize, AnchorSerialize, Default, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct StakeRegistryEntry {
+    pub stake_account: Pubkey,
+    // Default pubkey while the account has never been delegated
+    pub vote_account: Pubkey,
+    pub lamports: u64,
+    pub status: StakeStatus,
+    // Set while the account is a transient account cooling down
+    pub is_transient: bool,
+    // Validator a transient account is delegated to once inactive, default when it returns to
+    // the reserve
+    pub transient_vote_account: Pubkey,
+}
+
+#[account]
+pub struct StakeRegistry {
+    pub entries: [StakeRegistryEntry; MAX_STAKE_ACCOUNTS], // every stake account managed by the pool
+    pub idx: usize,
+    pub pool: Pubkey,
+    pub bump: u8,
+    padding: [u8; 7],
+}
+
+static_assertions::const_assert_eq!(size_of::<StakeRegistry>(), 7216);
+
+impl StakeRegistry {
+    pub const SEED: &'static [u8] = b"stake_registry";
+    pub const SIZE: usize = 8 + size_of::<Self>();
+
+    pub fn pubkey(pool: Pubkey) -> Pubkey {
+        Pubkey::find_program_address(&[Self::SEED, pool.as_ref()], &crate::ID).0
+    }
+
+    pub fn init(&mut self, pool: Pubkey, bump: u8) -> Result<()> {
+        self.entries = [StakeRegistryEntry::default(); MAX_STAKE_ACCOUNTS];
+        self.idx = 0;
+        self.pool = pool;
+        self.bump = bump;
+
+        Ok(())
+    }
+
+    pub fn entries(&self) -> &[StakeRegistryEntry] {
+        &self.entries[0..self.idx]
+    }
+
+    pub fn entries_mut(&mut self) -> &mut [StakeRegistryEntry] {
+        &mut self.entries[0..self.idx]
+    }
+
+    pub fn get(&self, stake_account: &Pubkey) -> Option<&StakeRegistryEntry> {
+        self.entries()
+            .iter()
+            .find(|entry| entry.stake_account == *stake_account)
+    }
+
+    pub fn get_mut(&mut self, stake_account: &Pubkey) -> Result<&mut StakeRegistryEntry> {
+        self.entries_mut()
+            .iter_mut()
+            .find(|entry| entry.stake_account == *stake_account)
+            .ok_or(ErrorCode::StakeAccountNotRegistered.into())
+    }
+
+    pub fn add(&mut self, entry: StakeRegistryEntry) -> Result<()> {
+        if self.get(&entry.stake_account).is_some() {
+            return Err(ErrorCode::DuplicateStakeAccount.into());
+        }
+        if self.idx >= MAX_STAKE_ACCOUNTS {
+            return Err(ErrorCode::StakeRegistryFull.into());
+        }
+        self.entries[self.idx] = entry;
+        self.idx += 1;
+
+        Ok(())
+    }
+
+    pub fn remove(&mut self, stake_account: &Pubkey) -> Result<StakeRegistryEntry> {
+        let position = self
+            .entries()
+            .iter()
+            .position(|entry| entry.stake_account == *stake_account)
+            .ok_or(ErrorCode::StakeAccountNotRegistered)?;
+        let entry = self.entries[position];
+
+        // Keep entries contiguous so `remaining_accounts` can be matched in order
+        self.entries.copy_within(position + 1..self.idx, position);
+        self.idx -= 1;
+        self.entries[self.idx] = StakeRegistryEntry::default();
+
+        Ok(entry)
+    }
+
+    /// Checks that `stake_accounts` lists every registered stake account, in registry order
+    pub fn check_stake_accounts(&self, stake_accounts: &[AccountInfo]) -> Result<()> {
+        if stake_accounts.len() != self.idx
+            || self
+                .entries()
+                .iter()
+                .zip(stake_accounts)
+                .any(|(entry, account_info)| entry.stake_account != *account_info.key)
+        {
+            return Err(ErrorCode::StakeRegistryMismatch.into());
+        }
+        Ok(())
+    }
+}
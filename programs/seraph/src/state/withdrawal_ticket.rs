@@ -0,0 +1,28 @@
+use std::mem::size_of;
+
+use anchor_lang::prelude::*;
+
+/// Lamports owed to `owner` for burned pool tokens, paid from the reserve once the stake
+/// deactivated to cover them has cooled down
+#[account]
+pub struct WithdrawalTicket {
+    pub pool: Pubkey,
+    pub owner: Pubkey,
+    pub id: u64,
+    pub lamports: u64,
+    // Epoch the ticket was requested in
+    pub requested_epoch: u64,
+    pub bump: u8,
+    padding: [u8; 7],
+}
+
+static_assertions::const_assert_eq!(size_of::<WithdrawalTicket>(), 96);
+
+impl WithdrawalTicket {
+    pub const SEED: &'static [u8] = b"withdrawal_ticket";
+    pub const SIZE: usize = 8 + size_of::<Self>();
+
+    pub fn pubkey(pool: Pubkey, id: u64) -> Pubkey {
+        Pubkey::find_program_address(&[Self::SEED, pool.as_ref(), &id.to_le_bytes()], &crate::ID).0
+    }
+}
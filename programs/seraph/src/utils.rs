@@ -0,0 +1,440 @@
+use anchor_lang::{
+    prelude::*,
+    solana_program::{
+        program::invoke_signed,
+        program_option::COption,
+        stake::{self, instruction::split, tools::get_minimum_delegation},
+        stake_history::StakeHistory,
+    },
+    system_program::{
+        allocate, assign, create_account, transfer, Allocate, Assign, CreateAccount, Transfer,
+    },
+};
+use anchor_spl::{
+    stake::{deactivate_stake, DeactivateStake, StakeAccount},
+    token::{initialize_mint2, InitializeMint2, Mint},
+};
+
+use crate::{
+    error::ErrorCode,
+    events::{StakeDeactivated, ValidatorSelectionChanged},
+    Pool, PoolParams, StakeRegistry, StakeRegistryEntry, StakeStatus, VList, POOL_MINT_DECIMALS,
+};
+
+/// Deserializes an initialized stake account owned by the stake program
+pub fn get_stake_account(account_info: &AccountInfo) -> Result<StakeAccount> {
+    if *account_info.owner != stake::program::ID {
+        return Err(ErrorCode::InvalidStakeAccount.into());
+    }
+    let data = account_info.try_borrow_data()?;
+    let stake_account = StakeAccount::try_deserialize(&mut data.as_ref())?;
+    if stake_account.authorized().is_none() {
+        return Err(ErrorCode::InvalidStakeAccount.into());
+    }
+    Ok(stake_account)
+}
+
+/// Deserializes a stake account, checking that its withdraw authority is `pool`
+pub fn get_pool_stake_account(pool: &Pubkey, account_info: &AccountInfo) -> Result<StakeAccount> {
+    let stake_account = get_stake_account(account_info)?;
+    match stake_account.authorized() {
+        Some(authorized) if authorized.withdrawer == *pool => Ok(stake_account),
+        _ => Err(ErrorCode::InvalidStakeAccount.into()),
+    }
+}
+
+/// Checks that splitting `lamports` off a stake account holding `source_lamports` leaves both
+/// sides with the minimum delegation, when delegated, and the source with its rent-exempt
+/// reserve. The split account is expected to be funded with its own rent-exempt reserve.
+pub fn check_split_lamports(
+    source: &StakeAccount,
+    source_lamports: u64,
+    lamports: u64,
+) -> Result<()> {
+    let rent_exempt_reserve = source
+        .meta()
+        .ok_or(ErrorCode::InvalidStakeAccount)?
+        .rent_exempt_reserve;
+    let minimum_stake = if source.delegation().is_some() {
+        get_minimum_delegation()?
+    } else {
+        0
+    };
+    let remaining_lamports = source_lamports
+        .checked_sub(lamports)
+        .ok_or(ErrorCode::InvalidStakeAmount)?;
+    if lamports == 0
+        || lamports < minimum_stake
+        || remaining_lamports < rent_exempt_reserve.saturating_add(minimum_stake)
+    {
+        return Err(ErrorCode::InvalidStakeAmount.into());
+    }
+    Ok(())
+}
+
+/// Splits `lamports` off a registered, delegated stake account into a freshly allocated transient
+/// stake account and deactivates it. Once inactive, the transient account is delegated to
+/// `transient_vote_account`, or withdrawn to the reserve when that is the default pubkey.
+#[allow(clippy::too_many_arguments)]
+pub fn create_transient_stake<'info>(
+    pool: &mut Account<'info, Pool>,
+    stake_registry: &mut StakeRegistry,
+    stake_account: &AccountInfo<'info>,
+    validator_vote: &Pubkey,
+    transient_stake_account: &AccountInfo<'info>,
+    clock: &AccountInfo<'info>,
+    stake_program: &AccountInfo<'info>,
+    lamports: u64,
+    transient_vote_account: Pubkey,
+) -> Result<()> {
+    let source_entry = *stake_registry
+        .get(stake_account.key)
+        .ok_or(ErrorCode::StakeAccountNotRegistered)?;
+    if source_entry.is_transient {
+        return Err(ErrorCode::TransientStakeInFlight.into());
+    }
+    // transient accounts are derived from the validator the stake leaves
+    if source_entry.vote_account != *validator_vote {
+        return Err(ErrorCode::InvalidValidatorVote.into());
+    }
+    let source = get_pool_stake_account(&pool.key(), stake_account)?;
+    if source.delegation().is_none() {
+        return Err(ErrorCode::InvalidStakeAccount.into());
+    }
+    check_split_lamports(&source, stake_account.lamports(), lamports)?;
+
+    // rent paid by the admin for the transient account joins the pool's principal
+    pool.total_lamports = pool
+        .total_lamports
+        .checked_add(transient_stake_account.lamports())
+        .ok_or(ErrorCode::ArithmeticError)?;
+
+    let split_ix = split(
+        stake_account.key,
+        &pool.key(),
+        lamports,
+        transient_stake_account.key,
+    )
+    .last()
+    .unwrap()
+    .clone();
+
+    invoke_signed(
+        &split_ix,
+        &[
+            stake_program.clone(),
+            stake_account.clone(),
+            transient_stake_account.clone(),
+            pool.to_account_info(),
+        ],
+        &[&[Pool::SEED, pool.admin.as_ref(), &[pool.bump]]],
+    )?;
+
+    deactivate_stake(CpiContext::new_with_signer(
+        stake_program.clone(),
+        DeactivateStake {
+            stake: transient_stake_account.clone(),
+            staker: pool.to_account_info(),
+            clock: clock.clone(),
+        },
+        &[&[Pool::SEED, pool.admin.as_ref(), &[pool.bump]]],
+    ))?;
+
+    stake_registry.get_mut(stake_account.key)?.lamports = stake_account.lamports();
+    let entry = StakeRegistryEntry {
+        stake_account: transient_stake_account.key(),
+        vote_account: source_entry.vote_account,
+        lamports: transient_stake_account.lamports(),
+        status: StakeStatus::Deactivating,
+        is_transient: true,
+        transient_vote_account,
+    };
+    stake_registry.add(entry)?;
+
+    emit!(StakeDeactivated {
+        pool: pool.key(),
+        stake_account: entry.stake_account,
+        validator: entry.vote_account,
+        lamports: entry.lamports,
+        old_status: source_entry.status,
+        new_status: entry.status,
+    });
+
+    Ok(())
+}
+
+/// Derives the activation state of a stake account at `epoch`
+pub fn get_stake_status(
+    stake_account: &StakeAccount,
+    epoch: u64,
+    stake_history: &StakeHistory,
+) -> StakeStatus {
+    let Some(delegation) = stake_account.delegation() else {
+        return StakeStatus::Inactive;
+    };
+    let status = delegation.stake_activating_and_deactivating(epoch, Some(stake_history), None);
+    if delegation.deactivation_epoch != u64::MAX {
+        if status.effective > 0 {
+            StakeStatus::Deactivating
+        } else {
+            StakeStatus::Inactive
+        }
+    } else if status.activating > 0 {
+        StakeStatus::Activating
+    } else {
+        StakeStatus::Active
+    }
+}
+
+/// Refreshes every registry entry from its stake account and returns the pool's total stake
+/// lamports. `stake_accounts` must list the registered accounts in registry order.
+pub fn refresh_stake_registry(
+    pool: &Pubkey,
+    stake_registry: &mut StakeRegistry,
+    stake_accounts: &[AccountInfo],
+    epoch: u64,
+    stake_history: &StakeHistory,
+) -> Result<u64> {
+    stake_registry.check_stake_accounts(stake_accounts)?;
+
+    let mut total_lamports: u64 = 0;
+    for (entry, account_info) in stake_registry.entries_mut().iter_mut().zip(stake_accounts) {
+        let stake_account = get_pool_stake_account(pool, account_info)?;
+        entry.lamports = account_info.lamports();
+        entry.status = get_stake_status(&stake_account, epoch, stake_history);
+        if let Some(delegation) = stake_account.delegation() {
+            entry.vote_account = delegation.voter_pubkey;
+        }
+        total_lamports = total_lamports
+            .checked_add(entry.lamports)
+            .ok_or(ErrorCode::ArithmeticError)?;
+    }
+    Ok(total_lamports)
+}
+
+/// Sums (active, activating, transient) stake across the pool's registered stake accounts at
+/// `epoch`. Transient accounts are counted by their full lamports.
+pub fn get_pool_stake_activation(
+    pool: &Pubkey,
+    stake_registry: &StakeRegistry,
+    stake_accounts: &[AccountInfo],
+    epoch: u64,
+    stake_history: &StakeHistory,
+) -> Result<(u64, u64, u64)> {
+    stake_registry.check_stake_accounts(stake_accounts)?;
+
+    let mut active: u64 = 0;
+    let mut activating: u64 = 0;
+    let mut transient: u64 = 0;
+    for (entry, account_info) in stake_registry.entries().iter().zip(stake_accounts) {
+        let stake_account = get_pool_stake_account(pool, account_info)?;
+        if entry.is_transient {
+            transient = transient
+                .checked_add(account_info.lamports())
+                .ok_or(ErrorCode::ArithmeticError)?;
+            continue;
+        }
+        if let Some(delegation) = stake_account.delegation() {
+            let status =
+                delegation.stake_activating_and_deactivating(epoch, Some(stake_history), None);
+            active = active
+                .checked_add(status.effective)
+                .ok_or(ErrorCode::ArithmeticError)?;
+            activating = activating
+                .checked_add(status.activating)
+                .ok_or(ErrorCode::ArithmeticError)?;
+        }
+    }
+    Ok((active, activating, transient))
+}
+
+/// Creates the PDA `account` with `space` bytes owned by `owner`, paying its rent from `payer`.
+/// Like Anchor's `init`, lamports someone already sent to the address do not block its creation.
+pub fn create_pda_account<'info>(
+    payer: &AccountInfo<'info>,
+    account: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    space: usize,
+    owner: &Pubkey,
+    signer_seeds: &[&[u8]],
+) -> Result<()> {
+    let rent = Rent::get()?.minimum_balance(space);
+    if account.lamports() == 0 {
+        return create_account(
+            CpiContext::new_with_signer(
+                system_program.clone(),
+                CreateAccount {
+                    from: payer.clone(),
+                    to: account.clone(),
+                },
+                &[signer_seeds],
+            ),
+            rent,
+            space as u64,
+            owner,
+        );
+    }
+
+    let rent_top_up = rent.saturating_sub(account.lamports());
+    if rent_top_up > 0 {
+        transfer(
+            CpiContext::new(
+                system_program.clone(),
+                Transfer {
+                    from: payer.clone(),
+                    to: account.clone(),
+                },
+            ),
+            rent_top_up,
+        )?;
+    }
+    allocate(
+        CpiContext::new_with_signer(
+            system_program.clone(),
+            Allocate {
+                account_to_allocate: account.clone(),
+            },
+            &[signer_seeds],
+        ),
+        space as u64,
+    )?;
+    assign(
+        CpiContext::new_with_signer(
+            system_program.clone(),
+            Assign {
+                account_to_assign: account.clone(),
+            },
+            &[signer_seeds],
+        ),
+        owner,
+    )
+}
+
+/// Creates the pool's mint PDA with the pool as its mint authority. The token program cannot
+/// close a mint, so one left behind by a closed pool is reused once all of its tokens are burned.
+pub fn init_pool_mint<'info>(
+    payer: &AccountInfo<'info>,
+    pool: &Pubkey,
+    pool_mint: &AccountInfo<'info>,
+    pool_mint_bump: u8,
+    token_program: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+) -> Result<()> {
+    if pool_mint.owner == &System::id() {
+        create_pda_account(
+            payer,
+            pool_mint,
+            system_program,
+            Mint::LEN,
+            token_program.key,
+            &[Pool::MINT_SEED, pool.as_ref(), &[pool_mint_bump]],
+        )?;
+        return initialize_mint2(
+            CpiContext::new(
+                token_program.clone(),
+                InitializeMint2 {
+                    mint: pool_mint.clone(),
+                },
+            ),
+            POOL_MINT_DECIMALS,
+            pool,
+            None,
+        );
+    }
+
+    if pool_mint.owner != token_program.key {
+        return Err(ErrorCode::InvalidPoolMint.into());
+    }
+    let mint = Mint::try_deserialize(&mut pool_mint.try_borrow_data()?.as_ref())?;
+    if mint.mint_authority != COption::Some(*pool)
+        || mint.freeze_authority.is_some()
+        || mint.decimals != POOL_MINT_DECIMALS
+        || mint.supply > 0
+    {
+        return Err(ErrorCode::InvalidPoolMint.into());
+    }
+    Ok(())
+}
+
+/// Grows an account of type `T` written in an older, smaller layout to `size`, topping its rent
+/// up from `payer`. The new bytes are zeroed, so the old fields keep their offsets and anything
+/// appended reads as zero until the caller sets it.
+pub fn realloc_legacy_account<'info, T: anchor_lang::Discriminator>(
+    account: &AccountInfo<'info>,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    size: usize,
+) -> Result<()> {
+    if account.data_len() >= size {
+        return Err(ErrorCode::AccountAlreadyMigrated.into());
+    }
+    if account.try_borrow_data()?.get(..8) != Some(T::DISCRIMINATOR.as_slice()) {
+        return Err(anchor_lang::error::ErrorCode::AccountDiscriminatorMismatch.into());
+    }
+
+    let rent_top_up = Rent::get()?
+        .minimum_balance(size)
+        .saturating_sub(account.lamports());
+    if rent_top_up > 0 {
+        transfer(
+            CpiContext::new(
+                system_program.clone(),
+                Transfer {
+                    from: payer.clone(),
+                    to: account.clone(),
+                },
+            ),
+            rent_top_up,
+        )?;
+    }
+    account.realloc(size, true)?;
+
+    Ok(())
+}
+
+/// Emits a selection change for every validator that entered or left the top percentile of the
+/// VList eligible for stake, given the list's ranking and parameters before the change
+pub fn emit_selection_changes(
+    pool: Pubkey,
+    old_ranking: &[Pubkey],
+    old_params: &PoolParams,
+    v_list: &VList,
+    params: &PoolParams,
+) {
+    let new_ranking = v_list.ranking();
+    let old_selected_count = old_params.eligible_validators(old_ranking.len());
+    let new_selected_count = params.eligible_validators(new_ranking.len());
+    let rank = |ranking: &[Pubkey], validator: &Pubkey| {
+        ranking
+            .iter()
+            .position(|ranked| ranked == validator)
+            .map(|rank| rank as u32)
+    };
+
+    for (selected, validators, selected_count) in [
+        (
+            false,
+            &old_ranking[..old_selected_count],
+            new_selected_count,
+        ),
+        (true, &new_ranking[..new_selected_count], old_selected_count),
+    ] {
+        for validator in validators {
+            let old_rank = rank(old_ranking, validator);
+            let new_rank = rank(&new_ranking, validator);
+            let other_rank = if selected { old_rank } else { new_rank };
+            if matches!(other_rank, Some(rank) if (rank as usize) < selected_count) {
+                continue;
+            }
+            emit!(ValidatorSelectionChanged {
+                pool,
+                validator: *validator,
+                selected,
+                old_rank,
+                new_rank,
+                old_selected_count: old_selected_count as u32,
+                new_selected_count: new_selected_count as u32,
+            });
+        }
+    }
+}
@@ -26,4 +26,6 @@ pub enum ValidatorHistoryError {
     ArithmeticError,
     #[msg("Slot history sysvar is not containing expected slots")]
     SlotHistoryOutOfDate,
+    #[msg("More blocks produced than leader slots")]
+    InvalidBlockProduction,
 }
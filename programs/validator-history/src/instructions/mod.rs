@@ -11,6 +11,8 @@ pub mod realloc_validator_history_account;
 pub mod set_new_admin;
 pub mod set_new_oracle_authority;
 pub mod set_new_tip_distribution_program;
+pub mod update_block_production;
+pub mod update_max_epoch_credits;
 pub mod update_mev_commission;
 pub mod update_stake_history;
 
@@ -26,5 +28,7 @@ pub use realloc_validator_history_account::*;
 pub use set_new_admin::*;
 pub use set_new_oracle_authority::*;
 pub use set_new_tip_distribution_program::*;
+pub use update_block_production::*;
+pub use update_max_epoch_credits::*;
 pub use update_mev_commission::*;
 pub use update_stake_history::*;
@@ -0,0 +1,41 @@
+use anchor_lang::prelude::*;
+
+use crate::{errors::ValidatorHistoryError, utils::cast_epoch, ClusterHistory, Config};
+
+#[derive(Accounts)]
+pub struct UpdateMaxEpochCredits<'info> {
+    #[account(
+        mut,
+        seeds = [ClusterHistory::SEED],
+        bump,
+    )]
+    pub cluster_history_account: AccountLoader<'info, ClusterHistory>,
+
+    #[account(
+        seeds = [Config::SEED],
+        bump = config.bump,
+        has_one = oracle_authority
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(mut)]
+    pub oracle_authority: Signer<'info>,
+}
+
+pub fn handler(
+    ctx: Context<UpdateMaxEpochCredits>,
+    epoch: u64,
+    max_epoch_credits: u32,
+) -> Result<()> {
+    let mut cluster_history_account = ctx.accounts.cluster_history_account.load_mut()?;
+
+    // Credits are only final once the epoch is over
+    if epoch >= Clock::get()?.epoch {
+        return Err(ValidatorHistoryError::EpochOutOfRange.into());
+    }
+    let epoch = cast_epoch(epoch);
+
+    cluster_history_account.set_max_epoch_credits(epoch, max_epoch_credits)?;
+
+    Ok(())
+}
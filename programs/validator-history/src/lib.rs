@@ -116,4 +116,21 @@ pub mod validator_history {
     ) -> Result<()> {
         instructions::backfill_total_blocks::handler(ctx, epoch, blocks_in_epoch)
     }
+
+    pub fn update_block_production(
+        ctx: Context<UpdateBlockProduction>,
+        epoch: u64,
+        leader_slots: u32,
+        blocks_produced: u32,
+    ) -> Result<()> {
+        instructions::update_block_production::handler(ctx, epoch, leader_slots, blocks_produced)
+    }
+
+    pub fn update_max_epoch_credits(
+        ctx: Context<UpdateMaxEpochCredits>,
+        epoch: u64,
+        max_epoch_credits: u32,
+    ) -> Result<()> {
+        instructions::update_max_epoch_credits::handler(ctx, epoch, max_epoch_credits)
+    }
 }
@@ -60,7 +60,10 @@ pub struct ValidatorHistoryEntry {
     pub rank: u32,
     // Most recent updated slot for epoch credits and commission
     pub vote_account_last_update_slot: u64,
-    pub padding1: [u8; 88],
+    // Leader slots assigned to the validator in the epoch, and how many it produced a block in
+    pub leader_slots: u32,
+    pub blocks_produced: u32,
+    pub padding1: [u8; 80],
 }
 
 impl Default for ValidatorHistoryEntry {
@@ -82,7 +85,9 @@ impl Default for ValidatorHistoryEntry {
             is_superminority: u8::MAX,
             rank: u32::MAX,
             vote_account_last_update_slot: u64::MAX,
-            padding1: [u8::MAX; 88],
+            leader_slots: u32::MAX,
+            blocks_produced: u32::MAX,
+            padding1: [u8::MAX; 80],
         }
     }
 }
@@ -241,6 +246,14 @@ impl CircBuf {
     pub fn vote_account_last_update_slot_latest(&self) -> Option<u64> {
         field_latest!(self, vote_account_last_update_slot)
     }
+
+    pub fn leader_slots_range(&self, start_epoch: u16, end_epoch: u16) -> Vec<Option<u32>> {
+        field_range!(self, start_epoch, end_epoch, leader_slots, u32)
+    }
+
+    pub fn blocks_produced_range(&self, start_epoch: u16, end_epoch: u16) -> Vec<Option<u32>> {
+        field_range!(self, start_epoch, end_epoch, blocks_produced, u32)
+    }
 }
 
 pub enum ValidatorHistoryVersion {
@@ -296,6 +309,43 @@ impl ValidatorHistory {
         Ok(())
     }
 
+    pub fn set_block_production(
+        &mut self,
+        epoch: u16,
+        leader_slots: u32,
+        blocks_produced: u32,
+    ) -> Result<()> {
+        // Only one authority for upload here, so any epoch can be updated in case of missed upload
+        if let Some(entry) = self.history.last_mut() {
+            match entry.epoch.cmp(&epoch) {
+                Ordering::Equal => {
+                    entry.leader_slots = leader_slots;
+                    entry.blocks_produced = blocks_produced;
+                    return Ok(());
+                }
+                Ordering::Greater => {
+                    for entry in self.history.arr_mut().iter_mut() {
+                        if entry.epoch == epoch {
+                            entry.leader_slots = leader_slots;
+                            entry.blocks_produced = blocks_produced;
+                            return Ok(());
+                        }
+                    }
+                    return Err(ValidatorHistoryError::EpochOutOfRange.into());
+                }
+                Ordering::Less => {}
+            }
+        }
+        let entry = ValidatorHistoryEntry {
+            epoch,
+            leader_slots,
+            blocks_produced,
+            ..ValidatorHistoryEntry::default()
+        };
+        self.history.push(entry);
+        Ok(())
+    }
+
     pub fn set_stake(
         &mut self,
         epoch: u16,
@@ -548,11 +598,16 @@ pub struct ClusterHistory {
     pub history: CircBufCluster,
 }
 
+static_assertions::const_assert_eq!(size_of::<ClusterHistoryEntry>(), 256);
+
 #[zero_copy]
 pub struct ClusterHistoryEntry {
     pub total_blocks: u32,
     pub epoch: u16,
-    pub padding: [u8; 250],
+    pub padding0: [u8; 2],
+    // Highest epoch credits earned by a validator in the epoch, uploaded by the oracle authority
+    pub max_epoch_credits: u32,
+    pub padding: [u8; 244],
 }
 
 impl Default for ClusterHistoryEntry {
@@ -560,7 +615,9 @@ impl Default for ClusterHistoryEntry {
         Self {
             total_blocks: u32::MAX,
             epoch: u16::MAX,
-            padding: [u8::MAX; 250],
+            padding0: [u8::MAX; 2],
+            max_epoch_credits: u32::MAX,
+            padding: [u8::MAX; 244],
         }
     }
 }
@@ -666,6 +723,19 @@ impl CircBufCluster {
             })
             .collect::<Vec<Option<u32>>>()
     }
+
+    pub fn max_epoch_credits_range(&self, start_epoch: u16, end_epoch: u16) -> Vec<Option<u32>> {
+        let epoch_range = self.epoch_range(start_epoch, end_epoch);
+        epoch_range
+            .iter()
+            .map(|maybe_entry| {
+                maybe_entry
+                    .as_ref()
+                    .map(|entry| entry.max_epoch_credits)
+                    .filter(|&field| field != ClusterHistoryEntry::default().max_epoch_credits)
+            })
+            .collect::<Vec<Option<u32>>>()
+    }
 }
 
 impl ClusterHistory {
@@ -690,6 +760,36 @@ impl ClusterHistory {
 
         Ok(())
     }
+
+    // Sets the cluster's highest epoch credits for the target epoch
+    pub fn set_max_epoch_credits(&mut self, epoch: u16, max_epoch_credits: u32) -> Result<()> {
+        // Only one authority for upload here, so any epoch can be updated in case of missed upload
+        if let Some(entry) = self.history.last_mut() {
+            match entry.epoch.cmp(&epoch) {
+                Ordering::Equal => {
+                    entry.max_epoch_credits = max_epoch_credits;
+                    return Ok(());
+                }
+                Ordering::Greater => {
+                    for entry in self.history.arr_mut().iter_mut() {
+                        if entry.epoch == epoch {
+                            entry.max_epoch_credits = max_epoch_credits;
+                            return Ok(());
+                        }
+                    }
+                    return Err(ValidatorHistoryError::EpochOutOfRange.into());
+                }
+                Ordering::Less => {}
+            }
+        }
+        let entry = ClusterHistoryEntry {
+            epoch,
+            max_epoch_credits,
+            ..ClusterHistoryEntry::default()
+        };
+        self.history.push(entry);
+        Ok(())
+    }
 }
 
 #[cfg(test)]
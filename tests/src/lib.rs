@@ -1,2 +1,3 @@
 pub mod fixtures;
 pub mod seraph_fixtures;
+pub mod top_validators_consumer;
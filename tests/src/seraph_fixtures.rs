@@ -3,24 +3,33 @@ use anchor_lang::{
     prelude::Rent,
     solana_program::{
         clock::Clock,
+        program_option::COption,
+        program_pack::Pack,
         pubkey::Pubkey,
         vote::state::{VoteInit, VoteState, VoteStateVersions},
     },
     AccountSerialize, InstructionData, ToAccountMetas,
 };
+use anchor_spl::token::spl_token;
 use rand::Rng;
-use seraph::{Pool, VList};
+use seraph::{
+    spl_stake_pool, ManagedStake, Pool, PoolHistory, PoolParams, ReserveParams, ScoringParams,
+    StakeRegistry, VList, WithdrawalTicket,
+};
 use solana_program_test::*;
 use solana_sdk::{
     account::Account,
     epoch_schedule::EpochSchedule,
-    instruction::Instruction,
+    instruction::{AccountMeta, Instruction},
     signature::Keypair,
     signer::Signer,
     stake::{
+        self,
         instruction::{authorize, initialize},
-        state::{Authorized, Lockup, StakeAuthorize, StakeState},
+        state::{Authorized, Lockup, Meta, StakeAuthorize, StakeState},
     },
+    system_instruction,
+    sysvar::{clock, stake_history},
     transaction::Transaction,
 };
 use std::{cell::RefCell, rc::Rc};
@@ -28,12 +37,27 @@ use std::{cell::RefCell, rc::Rc};
 use jito_tip_distribution::{
     sdk::derive_tip_distribution_account_address, state::TipDistributionAccount,
 };
-use validator_history::{self, constants::MAX_ALLOC_BYTES, ClusterHistory, ValidatorHistory};
+use validator_history::{
+    self, constants::MAX_ALLOC_BYTES, ClusterHistory, ValidatorHistory, ValidatorHistoryEntry,
+};
+
+use crate::top_validators_consumer;
 
 pub const TOTAL_VALIDATORS: usize = 20;
 const AIRDROP_LAMPORTS: u64 = 10_000_000_000_000_000;
 const TOTAL_EPOCHS: usize = 50;
 
+// Validators an SPL stake pool set up by the fixture can hold
+const SPL_STAKE_POOL_MAX_VALIDATORS: u32 = TOTAL_VALIDATORS as u32;
+
+/// Accounts of an SPL stake pool with the seraph pool as staker
+pub struct SplStakePoolAccounts {
+    pub stake_pool: Pubkey,
+    pub validator_list: Pubkey,
+    pub reserve_stake: Pubkey,
+    pub withdraw_authority: Pubkey,
+}
+
 pub struct STestFixture {
     pub ctx: Rc<RefCell<ProgramTestContext>>,
     pub vote_accounts: Vec<Keypair>,
@@ -49,12 +73,27 @@ pub struct STestFixture {
     pub admin: Keypair,
     pub pool: Pubkey,
     pub v_list: Pubkey,
-    pub redelegate_stake_accounts: Vec<Keypair>,
+    pub pool_mint: Pubkey,
+    pub manager_fee_account: Pubkey,
+    pub reserve: Pubkey,
+    pub pool_history: Pubkey,
+    pub stake_registry: Pubkey,
+    // Pool token account owned by `keypair`, receiving the pool tokens for deposited stake
+    pub depositor_token_account: Keypair,
     pub keypair: Keypair,
 }
 
 impl STestFixture {
     pub async fn new() -> Self {
+        Self::new_with_programs(false).await
+    }
+
+    /// Also loads the SPL stake pool program
+    pub async fn new_with_spl_stake_pool() -> Self {
+        Self::new_with_programs(true).await
+    }
+
+    async fn new_with_programs(with_spl_stake_pool: bool) -> Self {
         /*
            Initializes test context with ValidatorHistory and TipDistribution programs loaded, as well as
            a vote account and a system account for signing transactions.
@@ -72,12 +111,23 @@ impl STestFixture {
             processor!(jito_tip_distribution::entry),
         );
         program.add_program("seraph", seraph::id(), processor!(seraph::entry));
+        program.add_program(
+            "top_validators_consumer",
+            top_validators_consumer::id(),
+            processor!(top_validators_consumer::process_instruction),
+        );
+        if with_spl_stake_pool {
+            program.add_program(
+                "spl_stake_pool",
+                spl_stake_pool::ID,
+                processor!(spl_stake_pool_program::processor::Processor::process),
+            );
+        }
 
         let epoch = 0;
         let vote_accounts: Vec<Keypair> = (0..TOTAL_VALIDATORS).map(|_| Keypair::new()).collect();
         let stakers: Vec<Keypair> = (0..TOTAL_VALIDATORS).map(|_| Keypair::new()).collect();
         let stake_accounts: Vec<Keypair> = (0..TOTAL_VALIDATORS).map(|_| Keypair::new()).collect();
-        let redelegate_stake_accounts = (0..2).map(|_| Keypair::new()).collect();
         let identity_keypairs: Vec<Keypair> =
             (0..TOTAL_VALIDATORS).map(|_| Keypair::new()).collect();
         let epoch_credits: Vec<Vec<(u64, u64, u64)>> = (0..TOTAL_VALIDATORS)
@@ -128,11 +178,17 @@ impl STestFixture {
         )
         .0;
         let keypair = Keypair::new();
+        let depositor_token_account = Keypair::new();
 
         // Seraph Accounts
         let admin = Keypair::new();
         let pool = Pool::pubkey(admin.pubkey());
         let v_list = VList::pubkey(admin.pubkey(), pool);
+        let pool_mint = Pool::mint_pubkey(pool);
+        let manager_fee_account = Pool::manager_fee_pubkey(pool);
+        let reserve = Pool::reserve_pubkey(pool);
+        let pool_history = PoolHistory::pubkey(pool);
+        let stake_registry = StakeRegistry::pubkey(pool);
         let rent = Rent::default();
         let stake_account_lamports = rent
             .minimum_balance(std::mem::size_of::<solana_sdk::stake::state::StakeState>())
@@ -185,7 +241,12 @@ impl STestFixture {
             admin,
             pool,
             v_list,
-            redelegate_stake_accounts,
+            pool_mint,
+            manager_fee_account,
+            reserve,
+            pool_history,
+            stake_registry,
+            depositor_token_account,
             keypair,
         }
     }
@@ -206,22 +267,51 @@ impl STestFixture {
         T::try_deserialize(&mut ai.data.as_slice()).unwrap()
     }
 
-    pub async fn initialize_seraph(&self) {
-        let initialize_seraph = Instruction {
+    pub fn initialize_seraph_ix(&self) -> Instruction {
+        Instruction {
             program_id: seraph::id(),
             accounts: seraph::accounts::Initialize {
                 admin: self.admin.pubkey(),
                 pool: self.pool,
                 v_list: self.v_list,
+                pool_mint: self.pool_mint,
+                manager_fee_account: self.manager_fee_account,
+                reserve: self.reserve,
+                token_program: anchor_spl::token::ID,
                 system_program: anchor_lang::solana_program::system_program::id(),
             }
             .to_account_metas(None),
-            data: seraph::instruction::Initialize {}.data(),
-        };
+            data: seraph::instruction::Initialize {
+                params: PoolParams::default(),
+            }
+            .data(),
+        }
+    }
+
+    pub async fn initialize_seraph(&self) {
+        let rent = self.ctx.borrow_mut().banks_client.get_rent().await.unwrap();
+        let create_depositor_token_account = system_instruction::create_account(
+            &self.keypair.pubkey(),
+            &self.depositor_token_account.pubkey(),
+            rent.minimum_balance(spl_token::state::Account::LEN),
+            spl_token::state::Account::LEN as u64,
+            &spl_token::id(),
+        );
+        let initialize_depositor_token_account = spl_token::instruction::initialize_account(
+            &spl_token::id(),
+            &self.depositor_token_account.pubkey(),
+            &self.pool_mint,
+            &self.keypair.pubkey(),
+        )
+        .unwrap();
         let transaction = Transaction::new_signed_with_payer(
-            &[initialize_seraph],
+            &[
+                self.initialize_seraph_ix(),
+                create_depositor_token_account,
+                initialize_depositor_token_account,
+            ],
             Some(&self.keypair.pubkey()),
-            &[&self.keypair, &self.admin],
+            &[&self.keypair, &self.admin, &self.depositor_token_account],
             self.ctx
                 .borrow_mut()
                 .get_new_latest_blockhash()
@@ -239,7 +329,97 @@ impl STestFixture {
         }
     }
 
-    pub async fn init_and_auth_stake_accounts_to_admin(&self) {
+    pub async fn initialize_pool_history(&self) {
+        let instruction = Instruction {
+            program_id: seraph::id(),
+            accounts: seraph::accounts::InitializePoolHistory {
+                admin: self.admin.pubkey(),
+                pool: self.pool,
+                pool_history: self.pool_history,
+                system_program: anchor_lang::solana_program::system_program::id(),
+            }
+            .to_account_metas(None),
+            data: seraph::instruction::InitializePoolHistory {}.data(),
+        };
+        let transaction = Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&self.admin.pubkey()),
+            &[&self.admin],
+            self.ctx
+                .borrow_mut()
+                .get_new_latest_blockhash()
+                .await
+                .unwrap(),
+        );
+        self.submit_transaction_assert_success(transaction).await;
+    }
+
+    pub async fn initialize_stake_registry(&self) {
+        let instruction = Instruction {
+            program_id: seraph::id(),
+            accounts: seraph::accounts::InitializeStakeRegistry {
+                admin: self.admin.pubkey(),
+                pool: self.pool,
+                stake_registry: self.stake_registry,
+                system_program: anchor_lang::solana_program::system_program::id(),
+            }
+            .to_account_metas(None),
+            data: seraph::instruction::InitializeStakeRegistry {}.data(),
+        };
+        let transaction = Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&self.admin.pubkey()),
+            &[&self.admin],
+            self.ctx
+                .borrow_mut()
+                .get_new_latest_blockhash()
+                .await
+                .unwrap(),
+        );
+        self.submit_transaction_assert_success(transaction).await;
+    }
+
+    pub fn add_stake_account_ix(&self, stake_account: Pubkey) -> Instruction {
+        Instruction {
+            program_id: seraph::id(),
+            data: seraph::instruction::AddStakeAccount {}.data(),
+            accounts: seraph::accounts::AddStakeAccount {
+                admin: self.admin.pubkey(),
+                pool: self.pool,
+                stake_registry: self.stake_registry,
+                stake_account,
+                pool_mint: self.pool_mint,
+                depositor_token_account: self.depositor_token_account.pubkey(),
+                stake_history: stake_history::id(),
+                token_program: anchor_spl::token::ID,
+            }
+            .to_account_metas(None),
+        }
+    }
+
+    /// Initializes the stake registry and registers `stake_accounts` with the pool, minting their
+    /// pool tokens to `depositor_token_account`
+    pub async fn register_stake_accounts(&self, stake_accounts: &[Pubkey]) {
+        self.initialize_stake_registry().await;
+
+        let instructions: Vec<Instruction> = stake_accounts
+            .iter()
+            .map(|stake_account| self.add_stake_account_ix(*stake_account))
+            .collect();
+        let transaction = Transaction::new_signed_with_payer(
+            &instructions,
+            Some(&self.admin.pubkey()),
+            &[&self.admin],
+            self.ctx
+                .borrow_mut()
+                .get_new_latest_blockhash()
+                .await
+                .unwrap(),
+        );
+        self.submit_transaction_assert_success(transaction).await;
+    }
+
+    pub async fn init_and_auth_stake_accounts_to_pool(&self) {
         for i in 0..TOTAL_VALIDATORS {
             let init_ix = initialize(
                 &self.stake_accounts[i].pubkey(),
@@ -253,7 +433,7 @@ impl STestFixture {
             let auth_s_ix = authorize(
                 &self.stake_accounts[i].pubkey(),
                 &self.stakers[i].pubkey(),
-                &self.admin.pubkey(),
+                &self.pool,
                 StakeAuthorize::Staker,
                 None,
             );
@@ -261,7 +441,7 @@ impl STestFixture {
             let auth_w_ix = authorize(
                 &self.stake_accounts[i].pubkey(),
                 &self.stakers[i].pubkey(),
-                &self.admin.pubkey(),
+                &self.pool,
                 StakeAuthorize::Withdrawer,
                 None,
             );
@@ -289,32 +469,18 @@ impl STestFixture {
         }
     }
 
-    pub async fn initialize_config(&self) {
-        let instruction = Instruction {
-            program_id: validator_history::id(),
-            accounts: validator_history::accounts::InitializeConfig {
-                config: self.validator_history_config,
-                system_program: anchor_lang::solana_program::system_program::id(),
-                signer: self.keypair.pubkey(),
-            }
-            .to_account_metas(None),
-            data: validator_history::instruction::InitializeConfig {
-                authority: self.keypair.pubkey(),
-            }
-            .data(),
-        };
-        let set_tip_distribution_instruction = Instruction {
-            program_id: validator_history::id(),
-            accounts: validator_history::accounts::SetNewTipDistributionProgram {
-                config: self.validator_history_config,
-                new_tip_distribution_program: jito_tip_distribution::id(),
-                admin: self.keypair.pubkey(),
-            }
-            .to_account_metas(None),
-            data: validator_history::instruction::SetNewTipDistributionProgram {}.data(),
-        };
+    /// Initializes stake account `i` with its staker as both staker and withdrawer
+    pub async fn init_stake_account_to_staker(&self, i: usize) {
+        let init_ix = initialize(
+            &self.stake_accounts[i].pubkey(),
+            &Authorized {
+                staker: self.stakers[i].pubkey(),
+                withdrawer: self.stakers[i].pubkey(),
+            },
+            &Lockup::default(),
+        );
         let transaction = Transaction::new_signed_with_payer(
-            &[instruction, set_tip_distribution_instruction],
+            &[init_ix],
             Some(&self.keypair.pubkey()),
             &[&self.keypair],
             self.ctx
@@ -323,136 +489,1128 @@ impl STestFixture {
                 .await
                 .unwrap(),
         );
-        if let Err(e) = self
-            .ctx
-            .borrow_mut()
-            .banks_client
-            .process_transaction_with_preflight(transaction)
-            .await
-        {
-            panic!("Error: {}", e);
-        }
+        self.submit_transaction_assert_success(transaction).await;
     }
 
-    pub async fn initialize_validator_history_accounts(&self) {
-        for i in 0..TOTAL_VALIDATORS {
-            let instruction = Instruction {
-                program_id: validator_history::id(),
-                accounts: validator_history::accounts::InitializeValidatorHistoryAccount {
-                    validator_history_account: self.validator_history_accounts[i],
-                    vote_account: self.vote_accounts[i].pubkey(),
-                    system_program: anchor_lang::solana_program::system_program::id(),
-                    signer: self.keypair.pubkey(),
-                }
-                .to_account_metas(None),
-                data: validator_history::instruction::InitializeValidatorHistoryAccount {}.data(),
-            };
-
-            let mut ixs = vec![instruction];
+    pub fn managed_stake(&self, stake_account: Pubkey) -> Pubkey {
+        ManagedStake::pubkey(self.pool, stake_account)
+    }
 
-            // Realloc validator history account
-            let num_reallocs = (ValidatorHistory::SIZE - MAX_ALLOC_BYTES) / MAX_ALLOC_BYTES + 1;
-            ixs.extend(vec![
-                Instruction {
-                    program_id: validator_history::id(),
-                    accounts: validator_history::accounts::ReallocValidatorHistoryAccount {
-                        validator_history_account: self.validator_history_accounts[i],
-                        vote_account: self.vote_accounts[i].pubkey(),
-                        config: self.validator_history_config,
-                        system_program: anchor_lang::solana_program::system_program::id(),
-                        signer: self.keypair.pubkey(),
-                    }
-                    .to_account_metas(None),
-                    data: validator_history::instruction::ReallocValidatorHistoryAccount {}.data(),
-                };
-                num_reallocs
-            ]);
+    pub fn register_managed_stake_ix(&self, owner: Pubkey, stake_account: Pubkey) -> Instruction {
+        Instruction {
+            program_id: seraph::id(),
+            data: seraph::instruction::RegisterManagedStake {}.data(),
+            accounts: seraph::accounts::RegisterManagedStake {
+                owner,
+                pool: self.pool,
+                managed_stake: self.managed_stake(stake_account),
+                stake_account,
+                clock: clock::id(),
+                system_program: anchor_lang::solana_program::system_program::id(),
+                stake_program: stake::program::ID,
+            }
+            .to_account_metas(None),
+        }
+    }
 
-            let transaction = Transaction::new_signed_with_payer(
-                &ixs,
-                Some(&self.keypair.pubkey()),
-                &[&self.keypair],
-                self.ctx
-                    .borrow_mut()
-                    .get_new_latest_blockhash()
-                    .await
-                    .unwrap(),
-            );
-            self.submit_transaction_assert_success(transaction).await;
+    pub fn delegate_managed_stake_ix(
+        &self,
+        stake_account: Pubkey,
+        validator_vote: Pubkey,
+    ) -> Instruction {
+        Instruction {
+            program_id: seraph::id(),
+            data: seraph::instruction::DelegateManagedStake {}.data(),
+            accounts: seraph::accounts::DelegateManagedStake {
+                admin: self.admin.pubkey(),
+                pool: self.pool,
+                v_list: self.v_list,
+                managed_stake: self.managed_stake(stake_account),
+                stake_account,
+                validator_vote,
+                clock: clock::id(),
+                stake_history: stake_history::id(),
+                stake_config: stake::config::ID,
+                stake_program: stake::program::ID,
+            }
+            .to_account_metas(None),
         }
     }
 
-    pub async fn copy_vote_accounts(&self, total_epochs: usize) {
-        for i in 0..TOTAL_VALIDATORS {
-            self.ctx.borrow_mut().set_account(
-                &self.vote_accounts[i].pubkey(),
-                &new_vote_account(
-                    self.vote_accounts[i].pubkey(),
-                    self.vote_accounts[i].pubkey(),
-                    self.commisions[i][total_epochs],
-                    Some(self.epoch_credits[i][0..total_epochs].to_vec()),
-                )
-                .into(),
-            );
-            let instruction = Instruction {
-                program_id: validator_history::id(),
-                data: validator_history::instruction::CopyVoteAccount {}.data(),
-                accounts: validator_history::accounts::CopyVoteAccount {
-                    validator_history_account: self.validator_history_accounts[i],
-                    vote_account: self.vote_accounts[i].pubkey(),
-                    signer: self.keypair.pubkey(),
-                }
-                .to_account_metas(None),
-            };
+    pub fn deactivate_managed_stake_ix(&self, stake_account: Pubkey) -> Instruction {
+        Instruction {
+            program_id: seraph::id(),
+            data: seraph::instruction::DeactivateManagedStake {}.data(),
+            accounts: seraph::accounts::DeactivateManagedStake {
+                admin: self.admin.pubkey(),
+                pool: self.pool,
+                v_list: self.v_list,
+                managed_stake: self.managed_stake(stake_account),
+                stake_account,
+                clock: clock::id(),
+                stake_program: stake::program::ID,
+            }
+            .to_account_metas(None),
+        }
+    }
 
-            let transaction = Transaction::new_signed_with_payer(
-                &[instruction],
-                Some(&self.keypair.pubkey()),
-                &[&self.keypair],
-                self.ctx
-                    .borrow_mut()
-                    .get_new_latest_blockhash()
-                    .await
-                    .unwrap(),
-            );
-            if let Err(e) = self
-                .ctx
-                .borrow_mut()
-                .banks_client
-                .process_transaction_with_preflight(transaction)
-                .await
-            {
-                panic!("Error: {}", e);
+    pub fn revoke_managed_stake_ix(&self, owner: Pubkey, stake_account: Pubkey) -> Instruction {
+        Instruction {
+            program_id: seraph::id(),
+            data: seraph::instruction::RevokeManagedStake {}.data(),
+            accounts: seraph::accounts::RevokeManagedStake {
+                owner,
+                pool: self.pool,
+                managed_stake: self.managed_stake(stake_account),
+                stake_account,
+                clock: clock::id(),
+                stake_program: stake::program::ID,
             }
+            .to_account_metas(None),
         }
     }
 
-    pub async fn initialize_cluster_history_account(&self) {
-        let instruction = Instruction {
-            program_id: validator_history::id(),
-            accounts: validator_history::accounts::InitializeClusterHistoryAccount {
-                cluster_history_account: self.cluster_history_account,
+    pub fn withdrawal_ticket(&self, id: u64) -> Pubkey {
+        WithdrawalTicket::pubkey(self.pool, id)
+    }
+
+    pub fn request_withdrawal_ix(
+        &self,
+        owner: Pubkey,
+        pool_token_account: Pubkey,
+        ticket_id: u64,
+        pool_tokens: u64,
+    ) -> Instruction {
+        Instruction {
+            program_id: seraph::id(),
+            data: seraph::instruction::RequestWithdrawal { pool_tokens }.data(),
+            accounts: seraph::accounts::RequestWithdrawal {
+                owner,
+                pool: self.pool,
+                ticket: self.withdrawal_ticket(ticket_id),
+                pool_mint: self.pool_mint,
+                pool_token_account,
+                token_program: anchor_spl::token::ID,
                 system_program: anchor_lang::solana_program::system_program::id(),
-                signer: self.keypair.pubkey(),
             }
             .to_account_metas(None),
-            data: validator_history::instruction::InitializeClusterHistoryAccount {}.data(),
-        };
+        }
+    }
 
-        let mut ixs = vec![instruction];
+    /// Cranks the unstake queue, splitting any partial shortfall off into the transient stake
+    /// account for `nonce` of `split_validator`
+    pub fn process_unstake_queue_ix(
+        &self,
+        stake_accounts: &[Pubkey],
+        split_validator: Option<Pubkey>,
+        nonce: u64,
+    ) -> Instruction {
+        let mut accounts = seraph::accounts::ProcessUnstakeQueue {
+            payer: self.keypair.pubkey(),
+            pool: self.pool,
+            v_list: self.v_list,
+            stake_registry: self.stake_registry,
+            reserve: self.reserve,
+            transient_stake_account: split_validator.map(|validator_vote| {
+                Pool::transient_stake_pubkey(self.pool, validator_vote, nonce)
+            }),
+            clock: clock::id(),
+            system_program: anchor_lang::solana_program::system_program::id(),
+            stake_program: stake::program::ID,
+        }
+        .to_account_metas(None);
+        accounts.extend(
+            stake_accounts
+                .iter()
+                .map(|stake_account| AccountMeta::new(*stake_account, false)),
+        );
 
-        // Realloc cluster history account
-        let num_reallocs = (ClusterHistory::SIZE - MAX_ALLOC_BYTES) / MAX_ALLOC_BYTES + 1;
-        ixs.extend(vec![
-            Instruction {
-                program_id: validator_history::id(),
-                accounts: validator_history::accounts::ReallocClusterHistoryAccount {
-                    cluster_history_account: self.cluster_history_account,
-                    system_program: anchor_lang::solana_program::system_program::id(),
-                    signer: self.keypair.pubkey(),
-                }
-                .to_account_metas(None),
-                data: validator_history::instruction::ReallocClusterHistoryAccount {}.data(),
+        Instruction {
+            program_id: seraph::id(),
+            data: seraph::instruction::ProcessUnstakeQueue { nonce }.data(),
+            accounts,
+        }
+    }
+
+    pub fn claim_ticket_ix(&self, owner: Pubkey, ticket_id: u64) -> Instruction {
+        Instruction {
+            program_id: seraph::id(),
+            data: seraph::instruction::ClaimTicket {}.data(),
+            accounts: seraph::accounts::ClaimTicket {
+                owner,
+                pool: self.pool,
+                ticket: self.withdrawal_ticket(ticket_id),
+                reserve: self.reserve,
+                system_program: anchor_lang::solana_program::system_program::id(),
+            }
+            .to_account_metas(None),
+        }
+    }
+
+    pub fn set_reserve_params_ix(&self, params: ReserveParams) -> Instruction {
+        Instruction {
+            program_id: seraph::id(),
+            data: seraph::instruction::SetReserveParams { params }.data(),
+            accounts: seraph::accounts::SetReserveParams {
+                admin: self.admin.pubkey(),
+                pool: self.pool,
+            }
+            .to_account_metas(None),
+        }
+    }
+
+    pub fn instant_unstake_ix(
+        &self,
+        owner: Pubkey,
+        pool_token_account: Pubkey,
+        pool_tokens: u64,
+        min_lamports: u64,
+    ) -> Instruction {
+        Instruction {
+            program_id: seraph::id(),
+            data: seraph::instruction::InstantUnstake {
+                pool_tokens,
+                min_lamports,
+            }
+            .data(),
+            accounts: seraph::accounts::InstantUnstake {
+                owner,
+                pool: self.pool,
+                pool_mint: self.pool_mint,
+                pool_token_account,
+                reserve: self.reserve,
+                token_program: anchor_spl::token::ID,
+                system_program: anchor_lang::solana_program::system_program::id(),
+            }
+            .to_account_metas(None),
+        }
+    }
+
+    pub fn update_pool_balance_ix(&self, stake_accounts: &[Pubkey]) -> Instruction {
+        let mut accounts = seraph::accounts::UpdatePoolBalance {
+            admin: self.admin.pubkey(),
+            pool: self.pool,
+            stake_registry: self.stake_registry,
+            pool_mint: self.pool_mint,
+            manager_fee_account: self.manager_fee_account,
+            reserve: self.reserve,
+            stake_history: stake_history::id(),
+            token_program: anchor_spl::token::ID,
+        }
+        .to_account_metas(None);
+        accounts.extend(
+            stake_accounts
+                .iter()
+                .map(|stake_account| AccountMeta::new_readonly(*stake_account, false)),
+        );
+
+        Instruction {
+            program_id: seraph::id(),
+            data: seraph::instruction::UpdatePoolBalance {}.data(),
+            accounts,
+        }
+    }
+
+    pub fn update_pool_history_ix(&self, stake_accounts: &[Pubkey]) -> Instruction {
+        let mut accounts = seraph::accounts::UpdatePoolHistory {
+            pool: self.pool,
+            pool_history: self.pool_history,
+            stake_registry: self.stake_registry,
+            pool_mint: self.pool_mint,
+            stake_history: stake_history::id(),
+            signer: self.keypair.pubkey(),
+        }
+        .to_account_metas(None);
+        accounts.extend(
+            stake_accounts
+                .iter()
+                .map(|stake_account| AccountMeta::new_readonly(*stake_account, false)),
+        );
+
+        Instruction {
+            program_id: seraph::id(),
+            data: seraph::instruction::UpdatePoolHistory {}.data(),
+            accounts,
+        }
+    }
+
+    pub fn delegate_stake_ix(&self, stake_account: Pubkey, validator_vote: Pubkey) -> Instruction {
+        Instruction {
+            program_id: seraph::id(),
+            data: seraph::instruction::DelegateStake {}.data(),
+            accounts: seraph::accounts::DelegateStake {
+                admin: self.admin.pubkey(),
+                stake_account,
+                clock: clock::id(),
+                validator_vote,
+                stake_history: stake_history::id(),
+                stake_config: stake::config::ID,
+                pool: self.pool,
+                stake_registry: self.stake_registry,
+                system_program: anchor_lang::solana_program::system_program::id(),
+                stake_program: stake::program::ID,
+            }
+            .to_account_metas(None),
+        }
+    }
+
+    pub fn deactivate_stake_ix(&self, stake_account: Pubkey) -> Instruction {
+        Instruction {
+            program_id: seraph::id(),
+            data: seraph::instruction::DeactivateStake {}.data(),
+            accounts: seraph::accounts::DeactivateStake {
+                admin: self.admin.pubkey(),
+                stake_account,
+                clock: clock::id(),
+                pool: self.pool,
+                stake_registry: self.stake_registry,
+                system_program: anchor_lang::solana_program::system_program::id(),
+                stake_program: stake::program::ID,
+            }
+            .to_account_metas(None),
+        }
+    }
+
+    pub fn split_stake_ix(
+        &self,
+        stake_account: Pubkey,
+        split_stake_account: Pubkey,
+        lamports: u64,
+    ) -> Instruction {
+        Instruction {
+            program_id: seraph::id(),
+            data: seraph::instruction::SplitStake { lamports }.data(),
+            accounts: seraph::accounts::SplitStake {
+                admin: self.admin.pubkey(),
+                pool: self.pool,
+                stake_registry: self.stake_registry,
+                stake_account,
+                split_stake_account,
+                system_program: anchor_lang::solana_program::system_program::id(),
+                stake_program: stake::program::ID,
+            }
+            .to_account_metas(None),
+        }
+    }
+
+    pub fn merge_stake_ix(
+        &self,
+        destination_stake_account: Pubkey,
+        source_stake_account: Pubkey,
+    ) -> Instruction {
+        Instruction {
+            program_id: seraph::id(),
+            data: seraph::instruction::MergeStake {}.data(),
+            accounts: seraph::accounts::MergeStake {
+                admin: self.admin.pubkey(),
+                pool: self.pool,
+                stake_registry: self.stake_registry,
+                destination_stake_account,
+                source_stake_account,
+                clock: clock::id(),
+                stake_history: stake_history::id(),
+                stake_program: stake::program::ID,
+            }
+            .to_account_metas(None),
+        }
+    }
+
+    pub fn set_rebalance_params_ix(
+        &self,
+        min_score_improvement_bps: u16,
+        max_stake_moved_bps: u16,
+    ) -> Instruction {
+        Instruction {
+            program_id: seraph::id(),
+            data: seraph::instruction::SetRebalanceParams {
+                min_score_improvement_bps,
+                max_stake_moved_bps,
+            }
+            .data(),
+            accounts: seraph::accounts::SetRebalanceParams {
+                admin: self.admin.pubkey(),
+                pool: self.pool,
+            }
+            .to_account_metas(None),
+        }
+    }
+
+    /// Closes the pool, passing the stake registry and pool history only if they were initialized
+    pub fn close_pool_ix(&self, stake_registry: bool, pool_history: bool) -> Instruction {
+        Instruction {
+            program_id: seraph::id(),
+            data: seraph::instruction::ClosePool {}.data(),
+            accounts: seraph::accounts::ClosePool {
+                admin: self.admin.pubkey(),
+                pool: self.pool,
+                v_list: self.v_list,
+                stake_registry: stake_registry.then_some(self.stake_registry),
+                pool_history: pool_history.then_some(self.pool_history),
+                pool_mint: self.pool_mint,
+                manager_fee_account: self.manager_fee_account,
+                reserve: self.reserve,
+                token_program: anchor_spl::token::ID,
+                system_program: anchor_lang::solana_program::system_program::id(),
+            }
+            .to_account_metas(None),
+        }
+    }
+
+    pub fn update_params_ix(&self, params: PoolParams) -> Instruction {
+        Instruction {
+            program_id: seraph::id(),
+            data: seraph::instruction::UpdateParams { params }.data(),
+            accounts: seraph::accounts::UpdateParams {
+                admin: self.admin.pubkey(),
+                pool: self.pool,
+                v_list: self.v_list,
+            }
+            .to_account_metas(None),
+        }
+    }
+
+    pub fn migrate_pool_ix(&self) -> Instruction {
+        Instruction {
+            program_id: seraph::id(),
+            data: seraph::instruction::MigratePool {}.data(),
+            accounts: seraph::accounts::MigratePool {
+                admin: self.admin.pubkey(),
+                pool: self.pool,
+                pool_mint: self.pool_mint,
+                manager_fee_account: self.manager_fee_account,
+                reserve: self.reserve,
+                token_program: anchor_spl::token::ID,
+                system_program: anchor_lang::solana_program::system_program::id(),
+            }
+            .to_account_metas(None),
+        }
+    }
+
+    pub fn migrate_vlist_ix(&self) -> Instruction {
+        Instruction {
+            program_id: seraph::id(),
+            data: seraph::instruction::MigrateVlist {}.data(),
+            accounts: seraph::accounts::MigrateVList {
+                admin: self.admin.pubkey(),
+                pool: self.pool,
+                v_list: self.v_list,
+                system_program: anchor_lang::solana_program::system_program::id(),
+            }
+            .to_account_metas(None),
+        }
+    }
+
+    pub fn set_guardian_ix(&self, guardian: Pubkey) -> Instruction {
+        Instruction {
+            program_id: seraph::id(),
+            data: seraph::instruction::SetGuardian { guardian }.data(),
+            accounts: seraph::accounts::SetGuardian {
+                admin: self.admin.pubkey(),
+                pool: self.pool,
+            }
+            .to_account_metas(None),
+        }
+    }
+
+    pub fn pause_ix(&self, guardian: Pubkey) -> Instruction {
+        Instruction {
+            program_id: seraph::id(),
+            data: seraph::instruction::Pause {}.data(),
+            accounts: seraph::accounts::Pause {
+                guardian,
+                pool: self.pool,
+            }
+            .to_account_metas(None),
+        }
+    }
+
+    pub fn unpause_ix(&self, guardian: Pubkey) -> Instruction {
+        Instruction {
+            program_id: seraph::id(),
+            data: seraph::instruction::Unpause {}.data(),
+            accounts: seraph::accounts::Unpause {
+                guardian,
+                pool: self.pool,
+            }
+            .to_account_metas(None),
+        }
+    }
+
+    pub fn redelegate_stake_ix(
+        &self,
+        stake_account: Pubkey,
+        validator_vote: Pubkey,
+        new_validator_vote: Pubkey,
+        lamports: u64,
+        nonce: u64,
+    ) -> Instruction {
+        Instruction {
+            program_id: seraph::id(),
+            data: seraph::instruction::RedelegateStake { lamports, nonce }.data(),
+            accounts: seraph::accounts::RedelegateStake {
+                admin: self.admin.pubkey(),
+                pool: self.pool,
+                stake_registry: self.stake_registry,
+                v_list: self.v_list,
+                stake_account,
+                validator_vote,
+                new_validator_vote,
+                transient_stake_account: Pool::transient_stake_pubkey(
+                    self.pool,
+                    validator_vote,
+                    nonce,
+                ),
+                clock: clock::id(),
+                system_program: anchor_lang::solana_program::system_program::id(),
+                stake_program: stake::program::ID,
+            }
+            .to_account_metas(None),
+        }
+    }
+
+    pub fn decrease_stake_ix(
+        &self,
+        stake_account: Pubkey,
+        validator_vote: Pubkey,
+        lamports: u64,
+        nonce: u64,
+    ) -> Instruction {
+        Instruction {
+            program_id: seraph::id(),
+            data: seraph::instruction::DecreaseStake { lamports, nonce }.data(),
+            accounts: seraph::accounts::DecreaseStake {
+                admin: self.admin.pubkey(),
+                pool: self.pool,
+                stake_registry: self.stake_registry,
+                stake_account,
+                validator_vote,
+                transient_stake_account: Pool::transient_stake_pubkey(
+                    self.pool,
+                    validator_vote,
+                    nonce,
+                ),
+                clock: clock::id(),
+                system_program: anchor_lang::solana_program::system_program::id(),
+                stake_program: stake::program::ID,
+            }
+            .to_account_metas(None),
+        }
+    }
+
+    pub fn finalize_transient_stake_ix(
+        &self,
+        transient_stake_account: Pubkey,
+        validator_vote: Pubkey,
+    ) -> Instruction {
+        Instruction {
+            program_id: seraph::id(),
+            data: seraph::instruction::FinalizeTransientStake {}.data(),
+            accounts: seraph::accounts::FinalizeTransientStake {
+                pool: self.pool,
+                stake_registry: self.stake_registry,
+                transient_stake_account,
+                validator_vote,
+                reserve: self.reserve,
+                clock: clock::id(),
+                stake_history: stake_history::id(),
+                stake_config: stake::config::ID,
+                stake_program: stake::program::ID,
+                signer: self.keypair.pubkey(),
+            }
+            .to_account_metas(None),
+        }
+    }
+
+    pub fn withdraw_stake_ix(&self, stake_account: Pubkey) -> Instruction {
+        Instruction {
+            program_id: seraph::id(),
+            data: seraph::instruction::WithdrawStake {}.data(),
+            accounts: seraph::accounts::WithdrawStake {
+                admin: self.admin.pubkey(),
+                pool: self.pool,
+                stake_registry: self.stake_registry,
+                stake_account,
+                reserve: self.reserve,
+                clock: clock::id(),
+                stake_history: stake_history::id(),
+                stake_program: stake::program::ID,
+            }
+            .to_account_metas(None),
+        }
+    }
+
+    pub fn harvest_ix(&self, stake_account: Pubkey) -> Instruction {
+        Instruction {
+            program_id: seraph::id(),
+            data: seraph::instruction::Harvest {}.data(),
+            accounts: seraph::accounts::Harvest {
+                pool: self.pool,
+                stake_registry: self.stake_registry,
+                stake_account,
+                reserve: self.reserve,
+                clock: clock::id(),
+                stake_history: stake_history::id(),
+                stake_program: stake::program::ID,
+            }
+            .to_account_metas(None),
+        }
+    }
+
+    /// Sets up an SPL stake pool managed by the admin, with the seraph pool as staker and
+    /// `reserve_lamports` in its reserve
+    pub async fn initialize_spl_stake_pool(&self, reserve_lamports: u64) -> SplStakePoolAccounts {
+        let stake_pool = Pubkey::new_unique();
+        let validator_list = Pubkey::new_unique();
+        let reserve_stake = Pubkey::new_unique();
+        let pool_mint = Pubkey::new_unique();
+        let manager_fee_account = Pubkey::new_unique();
+        let withdraw_authority = spl_stake_pool::withdraw_authority_pubkey(&stake_pool);
+        let rent = self.ctx.borrow_mut().banks_client.get_rent().await.unwrap();
+
+        // zeroed accounts for the stake pool program to initialize, sized for its 1.0 layout
+        let validator_list_len = 9 + 73 * SPL_STAKE_POOL_MAX_VALIDATORS as usize;
+        for (address, len) in [(stake_pool, 611), (validator_list, validator_list_len)] {
+            self.ctx.borrow_mut().set_account(
+                &address,
+                &Account {
+                    lamports: rent.minimum_balance(len),
+                    data: vec![0; len],
+                    owner: spl_stake_pool::ID,
+                    ..Account::default()
+                }
+                .into(),
+            );
+        }
+
+        let mut data = vec![0; spl_token::state::Mint::LEN];
+        spl_token::state::Mint {
+            mint_authority: COption::Some(withdraw_authority),
+            decimals: 9,
+            is_initialized: true,
+            ..spl_token::state::Mint::default()
+        }
+        .pack_into_slice(&mut data);
+        self.ctx.borrow_mut().set_account(
+            &pool_mint,
+            &Account {
+                lamports: rent.minimum_balance(data.len()),
+                data,
+                owner: spl_token::id(),
+                ..Account::default()
+            }
+            .into(),
+        );
+
+        let mut data = vec![0; spl_token::state::Account::LEN];
+        spl_token::state::Account {
+            mint: pool_mint,
+            owner: self.admin.pubkey(),
+            state: spl_token::state::AccountState::Initialized,
+            ..spl_token::state::Account::default()
+        }
+        .pack_into_slice(&mut data);
+        self.ctx.borrow_mut().set_account(
+            &manager_fee_account,
+            &Account {
+                lamports: rent.minimum_balance(data.len()),
+                data,
+                owner: spl_token::id(),
+                ..Account::default()
+            }
+            .into(),
+        );
+
+        let stake_rent = rent.minimum_balance(StakeState::size_of());
+        let reserve = StakeState::Initialized(Meta {
+            rent_exempt_reserve: stake_rent,
+            authorized: Authorized {
+                staker: withdraw_authority,
+                withdrawer: withdraw_authority,
+            },
+            lockup: Lockup::default(),
+        });
+        let mut data = vec![0; StakeState::size_of()];
+        bincode::serialize_into(&mut data[..], &reserve).unwrap();
+        self.ctx.borrow_mut().set_account(
+            &reserve_stake,
+            &Account {
+                lamports: stake_rent + reserve_lamports,
+                data,
+                owner: stake::program::ID,
+                ..Account::default()
+            }
+            .into(),
+        );
+
+        // `Initialize` with zero fees out of 100
+        let mut data = vec![0];
+        for _ in 0..3 {
+            data.extend_from_slice(&100u64.to_le_bytes());
+            data.extend_from_slice(&0u64.to_le_bytes());
+        }
+        data.push(0);
+        data.extend_from_slice(&SPL_STAKE_POOL_MAX_VALIDATORS.to_le_bytes());
+        let instruction = Instruction {
+            program_id: spl_stake_pool::ID,
+            accounts: vec![
+                AccountMeta::new(stake_pool, false),
+                AccountMeta::new_readonly(self.admin.pubkey(), true),
+                AccountMeta::new_readonly(self.pool, false),
+                AccountMeta::new_readonly(withdraw_authority, false),
+                AccountMeta::new(validator_list, false),
+                AccountMeta::new_readonly(reserve_stake, false),
+                AccountMeta::new(pool_mint, false),
+                AccountMeta::new(manager_fee_account, false),
+                AccountMeta::new_readonly(spl_token::id(), false),
+            ],
+            data,
+        };
+        let transaction = Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&self.admin.pubkey()),
+            &[&self.admin],
+            self.ctx
+                .borrow_mut()
+                .get_new_latest_blockhash()
+                .await
+                .unwrap(),
+        );
+        self.submit_transaction_assert_success(transaction).await;
+
+        SplStakePoolAccounts {
+            stake_pool,
+            validator_list,
+            reserve_stake,
+            withdraw_authority,
+        }
+    }
+
+    pub fn set_stake_pool_ix(&self, stake_pool: Pubkey) -> Instruction {
+        Instruction {
+            program_id: seraph::id(),
+            data: seraph::instruction::SetStakePool {}.data(),
+            accounts: seraph::accounts::SetStakePool {
+                admin: self.admin.pubkey(),
+                pool: self.pool,
+                stake_pool,
+            }
+            .to_account_metas(None),
+        }
+    }
+
+    pub fn add_stake_pool_validator_ix(
+        &self,
+        stake_pool: &SplStakePoolAccounts,
+        validator_vote: Pubkey,
+    ) -> Instruction {
+        Instruction {
+            program_id: seraph::id(),
+            data: seraph::instruction::AddStakePoolValidator {}.data(),
+            accounts: seraph::accounts::AddStakePoolValidator {
+                admin: self.admin.pubkey(),
+                pool: self.pool,
+                v_list: self.v_list,
+                stake_pool: stake_pool.stake_pool,
+                reserve_stake: stake_pool.reserve_stake,
+                withdraw_authority: stake_pool.withdraw_authority,
+                validator_list: stake_pool.validator_list,
+                validator_stake: spl_stake_pool::validator_stake_pubkey(
+                    &validator_vote,
+                    &stake_pool.stake_pool,
+                ),
+                validator_vote,
+                rent: anchor_lang::solana_program::sysvar::rent::id(),
+                clock: clock::id(),
+                stake_history: stake_history::id(),
+                stake_config: stake::config::ID,
+                system_program: anchor_lang::solana_program::system_program::id(),
+                stake_program: stake::program::ID,
+                spl_stake_pool_program: spl_stake_pool::ID,
+            }
+            .to_account_metas(None),
+        }
+    }
+
+    pub fn remove_stake_pool_validator_ix(
+        &self,
+        stake_pool: &SplStakePoolAccounts,
+        validator_vote: Pubkey,
+        transient_stake_seed: u64,
+    ) -> Instruction {
+        Instruction {
+            program_id: seraph::id(),
+            data: seraph::instruction::RemoveStakePoolValidator {}.data(),
+            accounts: seraph::accounts::RemoveStakePoolValidator {
+                admin: self.admin.pubkey(),
+                pool: self.pool,
+                v_list: self.v_list,
+                stake_pool: stake_pool.stake_pool,
+                withdraw_authority: stake_pool.withdraw_authority,
+                validator_list: stake_pool.validator_list,
+                validator_stake: spl_stake_pool::validator_stake_pubkey(
+                    &validator_vote,
+                    &stake_pool.stake_pool,
+                ),
+                transient_stake: spl_stake_pool::transient_stake_pubkey(
+                    &validator_vote,
+                    &stake_pool.stake_pool,
+                    transient_stake_seed,
+                ),
+                clock: clock::id(),
+                stake_program: stake::program::ID,
+                spl_stake_pool_program: spl_stake_pool::ID,
+            }
+            .to_account_metas(None),
+        }
+    }
+
+    pub fn increase_stake_pool_validator_stake_ix(
+        &self,
+        stake_pool: &SplStakePoolAccounts,
+        validator_vote: Pubkey,
+        lamports: u64,
+        transient_stake_seed: u64,
+    ) -> Instruction {
+        Instruction {
+            program_id: seraph::id(),
+            data: seraph::instruction::IncreaseStakePoolValidatorStake {
+                lamports,
+                transient_stake_seed,
+            }
+            .data(),
+            accounts: seraph::accounts::IncreaseStakePoolValidatorStake {
+                admin: self.admin.pubkey(),
+                pool: self.pool,
+                v_list: self.v_list,
+                stake_pool: stake_pool.stake_pool,
+                withdraw_authority: stake_pool.withdraw_authority,
+                validator_list: stake_pool.validator_list,
+                reserve_stake: stake_pool.reserve_stake,
+                transient_stake: spl_stake_pool::transient_stake_pubkey(
+                    &validator_vote,
+                    &stake_pool.stake_pool,
+                    transient_stake_seed,
+                ),
+                validator_stake: spl_stake_pool::validator_stake_pubkey(
+                    &validator_vote,
+                    &stake_pool.stake_pool,
+                ),
+                validator_vote,
+                clock: clock::id(),
+                rent: anchor_lang::solana_program::sysvar::rent::id(),
+                stake_history: stake_history::id(),
+                stake_config: stake::config::ID,
+                system_program: anchor_lang::solana_program::system_program::id(),
+                stake_program: stake::program::ID,
+                spl_stake_pool_program: spl_stake_pool::ID,
+            }
+            .to_account_metas(None),
+        }
+    }
+
+    pub fn decrease_stake_pool_validator_stake_ix(
+        &self,
+        stake_pool: &SplStakePoolAccounts,
+        validator_vote: Pubkey,
+        lamports: u64,
+        transient_stake_seed: u64,
+    ) -> Instruction {
+        Instruction {
+            program_id: seraph::id(),
+            data: seraph::instruction::DecreaseStakePoolValidatorStake {
+                lamports,
+                transient_stake_seed,
+            }
+            .data(),
+            accounts: seraph::accounts::DecreaseStakePoolValidatorStake {
+                admin: self.admin.pubkey(),
+                pool: self.pool,
+                stake_pool: stake_pool.stake_pool,
+                withdraw_authority: stake_pool.withdraw_authority,
+                validator_list: stake_pool.validator_list,
+                reserve_stake: stake_pool.reserve_stake,
+                validator_stake: spl_stake_pool::validator_stake_pubkey(
+                    &validator_vote,
+                    &stake_pool.stake_pool,
+                ),
+                transient_stake: spl_stake_pool::transient_stake_pubkey(
+                    &validator_vote,
+                    &stake_pool.stake_pool,
+                    transient_stake_seed,
+                ),
+                clock: clock::id(),
+                stake_history: stake_history::id(),
+                system_program: anchor_lang::solana_program::system_program::id(),
+                stake_program: stake::program::ID,
+                spl_stake_pool_program: spl_stake_pool::ID,
+            }
+            .to_account_metas(None),
+        }
+    }
+
+    pub async fn add_lamports(&self, address: &Pubkey, lamports: u64) {
+        let mut account = self
+            .ctx
+            .borrow_mut()
+            .banks_client
+            .get_account(*address)
+            .await
+            .unwrap()
+            .unwrap();
+        account.lamports += lamports;
+        self.ctx.borrow_mut().set_account(address, &account.into());
+    }
+
+    pub async fn initialize_config(&self) {
+        let instruction = Instruction {
+            program_id: validator_history::id(),
+            accounts: validator_history::accounts::InitializeConfig {
+                config: self.validator_history_config,
+                system_program: anchor_lang::solana_program::system_program::id(),
+                signer: self.keypair.pubkey(),
+            }
+            .to_account_metas(None),
+            data: validator_history::instruction::InitializeConfig {
+                authority: self.keypair.pubkey(),
+            }
+            .data(),
+        };
+        let set_tip_distribution_instruction = Instruction {
+            program_id: validator_history::id(),
+            accounts: validator_history::accounts::SetNewTipDistributionProgram {
+                config: self.validator_history_config,
+                new_tip_distribution_program: jito_tip_distribution::id(),
+                admin: self.keypair.pubkey(),
+            }
+            .to_account_metas(None),
+            data: validator_history::instruction::SetNewTipDistributionProgram {}.data(),
+        };
+        let transaction = Transaction::new_signed_with_payer(
+            &[instruction, set_tip_distribution_instruction],
+            Some(&self.keypair.pubkey()),
+            &[&self.keypair],
+            self.ctx
+                .borrow_mut()
+                .get_new_latest_blockhash()
+                .await
+                .unwrap(),
+        );
+        if let Err(e) = self
+            .ctx
+            .borrow_mut()
+            .banks_client
+            .process_transaction_with_preflight(transaction)
+            .await
+        {
+            panic!("Error: {}", e);
+        }
+    }
+
+    pub async fn initialize_validator_history_accounts(&self) {
+        for i in 0..TOTAL_VALIDATORS {
+            let instruction = Instruction {
+                program_id: validator_history::id(),
+                accounts: validator_history::accounts::InitializeValidatorHistoryAccount {
+                    validator_history_account: self.validator_history_accounts[i],
+                    vote_account: self.vote_accounts[i].pubkey(),
+                    system_program: anchor_lang::solana_program::system_program::id(),
+                    signer: self.keypair.pubkey(),
+                }
+                .to_account_metas(None),
+                data: validator_history::instruction::InitializeValidatorHistoryAccount {}.data(),
+            };
+
+            let mut ixs = vec![instruction];
+
+            // Realloc validator history account
+            let num_reallocs = (ValidatorHistory::SIZE - MAX_ALLOC_BYTES) / MAX_ALLOC_BYTES + 1;
+            ixs.extend(vec![
+                Instruction {
+                    program_id: validator_history::id(),
+                    accounts: validator_history::accounts::ReallocValidatorHistoryAccount {
+                        validator_history_account: self.validator_history_accounts[i],
+                        vote_account: self.vote_accounts[i].pubkey(),
+                        config: self.validator_history_config,
+                        system_program: anchor_lang::solana_program::system_program::id(),
+                        signer: self.keypair.pubkey(),
+                    }
+                    .to_account_metas(None),
+                    data: validator_history::instruction::ReallocValidatorHistoryAccount {}.data(),
+                };
+                num_reallocs
+            ]);
+
+            let transaction = Transaction::new_signed_with_payer(
+                &ixs,
+                Some(&self.keypair.pubkey()),
+                &[&self.keypair],
+                self.ctx
+                    .borrow_mut()
+                    .get_new_latest_blockhash()
+                    .await
+                    .unwrap(),
+            );
+            self.submit_transaction_assert_success(transaction).await;
+        }
+    }
+
+    pub async fn copy_vote_accounts(&self, total_epochs: usize) {
+        for i in 0..TOTAL_VALIDATORS {
+            self.ctx.borrow_mut().set_account(
+                &self.vote_accounts[i].pubkey(),
+                &new_vote_account(
+                    self.vote_accounts[i].pubkey(),
+                    self.vote_accounts[i].pubkey(),
+                    self.commisions[i][total_epochs],
+                    Some(self.epoch_credits[i][0..total_epochs].to_vec()),
+                )
+                .into(),
+            );
+            let instruction = Instruction {
+                program_id: validator_history::id(),
+                data: validator_history::instruction::CopyVoteAccount {}.data(),
+                accounts: validator_history::accounts::CopyVoteAccount {
+                    validator_history_account: self.validator_history_accounts[i],
+                    vote_account: self.vote_accounts[i].pubkey(),
+                    signer: self.keypair.pubkey(),
+                }
+                .to_account_metas(None),
+            };
+
+            let transaction = Transaction::new_signed_with_payer(
+                &[instruction],
+                Some(&self.keypair.pubkey()),
+                &[&self.keypair],
+                self.ctx
+                    .borrow_mut()
+                    .get_new_latest_blockhash()
+                    .await
+                    .unwrap(),
+            );
+            if let Err(e) = self
+                .ctx
+                .borrow_mut()
+                .banks_client
+                .process_transaction_with_preflight(transaction)
+                .await
+            {
+                panic!("Error: {}", e);
+            }
+        }
+    }
+
+    pub fn get_top_validators_ix(&self, limit: u8) -> Instruction {
+        Instruction {
+            program_id: seraph::id(),
+            data: seraph::instruction::GetTopValidators { limit }.data(),
+            accounts: seraph::accounts::GetTopValidators {
+                pool: self.pool,
+                v_list: self.v_list,
+            }
+            .to_account_metas(None),
+        }
+    }
+
+    pub fn calculate_score_ix(&self, validator: usize) -> Instruction {
+        Instruction {
+            program_id: seraph::id(),
+            data: seraph::instruction::CalculateScore {}.data(),
+            accounts: seraph::accounts::CalculateScore {
+                admin: self.admin.pubkey(),
+                validator_history_account: self.validator_history_accounts[validator],
+                vote_account: self.vote_accounts[validator].pubkey(),
+                cluster_history_account: self.cluster_history_account,
+                pool: self.pool,
+                v_list: self.v_list,
+            }
+            .to_account_metas(None),
+        }
+    }
+
+    /// Replaces the recorded history of a validator with `entries`, oldest first
+    pub async fn set_validator_history(&self, validator: usize, entries: &[ValidatorHistoryEntry]) {
+        let address = self.validator_history_accounts[validator];
+        let mut validator_history: ValidatorHistory = self.load_and_deserialize(&address).await;
+        validator_history.history.arr =
+            [ValidatorHistoryEntry::default(); ValidatorHistory::MAX_ITEMS];
+        validator_history.history.idx = ValidatorHistory::MAX_ITEMS as u64 - 1;
+        validator_history.history.is_empty = 1;
+        for entry in entries {
+            validator_history.history.push(*entry);
+        }
+
+        let mut account = self
+            .ctx
+            .borrow_mut()
+            .banks_client
+            .get_account(address)
+            .await
+            .unwrap()
+            .unwrap();
+        account.data[8..8 + std::mem::size_of::<ValidatorHistory>()]
+            .copy_from_slice(bytemuck::bytes_of(&validator_history));
+        self.ctx.borrow_mut().set_account(&address, &account.into());
+    }
+
+    pub fn set_scoring_params_ix(&self, params: ScoringParams) -> Instruction {
+        Instruction {
+            program_id: seraph::id(),
+            data: seraph::instruction::SetScoringParams { params }.data(),
+            accounts: seraph::accounts::SetScoringParams {
+                admin: self.admin.pubkey(),
+                pool: self.pool,
+            }
+            .to_account_metas(None),
+        }
+    }
+
+    /// Records one epoch of vote history for every validator and scores them into the VList
+    pub async fn initialize_and_score_validators(&self) {
+        self.initialize_config().await;
+        self.initialize_validator_history_accounts().await;
+        self.copy_vote_accounts(1).await;
+
+        for i in 0..TOTAL_VALIDATORS {
+            let transaction = Transaction::new_signed_with_payer(
+                &[self.calculate_score_ix(i)],
+                Some(&self.admin.pubkey()),
+                &[&self.admin],
+                self.ctx
+                    .borrow_mut()
+                    .get_new_latest_blockhash()
+                    .await
+                    .unwrap(),
+            );
+            self.submit_transaction_assert_success(transaction).await;
+        }
+    }
+
+    pub async fn initialize_cluster_history_account(&self) {
+        let instruction = Instruction {
+            program_id: validator_history::id(),
+            accounts: validator_history::accounts::InitializeClusterHistoryAccount {
+                cluster_history_account: self.cluster_history_account,
+                system_program: anchor_lang::solana_program::system_program::id(),
+                signer: self.keypair.pubkey(),
+            }
+            .to_account_metas(None),
+            data: validator_history::instruction::InitializeClusterHistoryAccount {}.data(),
+        };
+
+        let mut ixs = vec![instruction];
+
+        // Realloc cluster history account
+        let num_reallocs = (ClusterHistory::SIZE - MAX_ALLOC_BYTES) / MAX_ALLOC_BYTES + 1;
+        ixs.extend(vec![
+            Instruction {
+                program_id: validator_history::id(),
+                accounts: validator_history::accounts::ReallocClusterHistoryAccount {
+                    cluster_history_account: self.cluster_history_account,
+                    system_program: anchor_lang::solana_program::system_program::id(),
+                    signer: self.keypair.pubkey(),
+                }
+                .to_account_metas(None),
+                data: validator_history::instruction::ReallocClusterHistoryAccount {}.data(),
             };
             num_reallocs
         ]);
@@ -469,6 +1627,24 @@ impl STestFixture {
         self.submit_transaction_assert_success(transaction).await;
     }
 
+    /// Records the cluster's highest epoch credits for a past epoch, signed by the oracle authority
+    pub fn update_max_epoch_credits_ix(&self, epoch: u64, max_epoch_credits: u32) -> Instruction {
+        Instruction {
+            program_id: validator_history::id(),
+            data: validator_history::instruction::UpdateMaxEpochCredits {
+                epoch,
+                max_epoch_credits,
+            }
+            .data(),
+            accounts: validator_history::accounts::UpdateMaxEpochCredits {
+                cluster_history_account: self.cluster_history_account,
+                config: self.validator_history_config,
+                oracle_authority: self.keypair.pubkey(),
+            }
+            .to_account_metas(None),
+        }
+    }
+
     pub async fn advance_num_epochs(&self, num_epochs: u64) {
         let clock: Clock = self
             .ctx
@@ -515,6 +1691,93 @@ impl STestFixture {
             panic!("Error: Transaction succeeded. Expected {}", error_message);
         }
     }
+
+    /// Submits `transaction` and returns the program id and data of its return data
+    pub async fn submit_transaction_get_return_data(
+        &self,
+        transaction: Transaction,
+    ) -> (Pubkey, Vec<u8>) {
+        let result = self
+            .ctx
+            .borrow_mut()
+            .banks_client
+            .process_transaction_with_metadata(transaction)
+            .await
+            .expect("Failed submitting transaction");
+        if let Err(e) = result.result {
+            panic!("Error: {}", e);
+        }
+
+        let return_data = result
+            .metadata
+            .expect("Missing transaction metadata")
+            .return_data
+            .expect("Missing return data");
+        (return_data.program_id, return_data.data)
+    }
+
+    /// Submits `transaction` and decodes the `T` events it emitted, in order
+    pub async fn submit_transaction_get_events<T: anchor_lang::Event>(
+        &self,
+        transaction: Transaction,
+    ) -> Vec<T> {
+        decode_events(&self.submit_transaction_get_logs(transaction).await)
+    }
+
+    /// Submits a transaction expected to succeed and returns its logs, to decode events of several
+    /// types from with `decode_events`
+    pub async fn submit_transaction_get_logs(&self, transaction: Transaction) -> Vec<String> {
+        let result = self
+            .ctx
+            .borrow_mut()
+            .banks_client
+            .process_transaction_with_metadata(transaction)
+            .await
+            .expect("Failed submitting transaction");
+        if let Err(e) = result.result {
+            panic!("Error: {}", e);
+        }
+
+        result
+            .metadata
+            .expect("Missing transaction metadata")
+            .log_messages
+    }
+
+    /// Submits a transaction expected to fail with `error_message` and returns the events of type
+    /// `T` it emitted before failing
+    pub async fn submit_transaction_assert_error_get_events<T: anchor_lang::Event>(
+        &self,
+        transaction: Transaction,
+        error_message: &str,
+    ) -> Vec<T> {
+        let result = self
+            .ctx
+            .borrow_mut()
+            .banks_client
+            .process_transaction_with_metadata(transaction)
+            .await
+            .expect("Failed submitting transaction");
+        assert!(result.result.is_err(), "Expected {}", error_message);
+
+        let log_messages = result
+            .metadata
+            .expect("Missing transaction metadata")
+            .log_messages;
+        assert!(log_messages.iter().any(|log| log.contains(error_message)));
+        decode_events(&log_messages)
+    }
+}
+
+/// Anchor events of type `T` in a transaction's logs
+pub fn decode_events<T: anchor_lang::Event>(log_messages: &[String]) -> Vec<T> {
+    log_messages
+        .iter()
+        .filter_map(|log| log.strip_prefix("Program data: "))
+        .filter_map(|data| anchor_lang::__private::base64::decode(data).ok())
+        .filter(|data| data.starts_with(&T::DISCRIMINATOR))
+        .map(|data| T::try_from_slice(&data[8..]).expect("Failed decoding event"))
+        .collect()
 }
 
 pub fn system_account(lamports: u64) -> Account {
@@ -0,0 +1,65 @@
+//! Minimal program consuming seraph's validator ranking by CPI. It asks seraph for its top
+//! validators, decodes the return data and returns the best validator's vote account.
+use anchor_lang::{
+    solana_program::{
+        account_info::AccountInfo,
+        entrypoint::ProgramResult,
+        instruction::Instruction,
+        msg,
+        program::{get_return_data, invoke, set_return_data},
+        program_error::ProgramError,
+        pubkey::Pubkey,
+    },
+    AnchorDeserialize, InstructionData, ToAccountMetas,
+};
+use seraph::{TopValidators, TOP_VALIDATORS_VERSION};
+
+anchor_lang::declare_id!("67BSU5PpsCAtDax2TY4uj6t7RkSuqi2bQdUEV18q4hRe");
+
+/// Accounts: seraph program, pool, v_list. Data: the number of validators to request.
+pub fn process_instruction(
+    _program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: &[u8],
+) -> ProgramResult {
+    let [seraph_program, pool, v_list] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+    let limit = *data.first().ok_or(ProgramError::InvalidInstructionData)?;
+
+    invoke(
+        &Instruction {
+            program_id: seraph::id(),
+            accounts: seraph::accounts::GetTopValidators {
+                pool: *pool.key,
+                v_list: *v_list.key,
+            }
+            .to_account_metas(None),
+            data: seraph::instruction::GetTopValidators { limit }.data(),
+        },
+        &[seraph_program.clone(), pool.clone(), v_list.clone()],
+    )?;
+
+    // only trust return data set by seraph itself
+    let (program_id, return_data) = get_return_data().ok_or(ProgramError::InvalidAccountData)?;
+    if program_id != seraph::id() {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    let top_validators = TopValidators::try_from_slice(&return_data)?;
+    if top_validators.version != TOP_VALIDATORS_VERSION {
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    let best = top_validators
+        .validators
+        .first()
+        .ok_or(ProgramError::InvalidAccountData)?;
+    msg!(
+        "Best validator {} with score {}",
+        best.validator,
+        best.score
+    );
+    set_return_data(best.validator.as_ref());
+
+    Ok(())
+}
@@ -0,0 +1,90 @@
+#![allow(clippy::await_holding_refcell_ref)]
+use anchor_lang::{solana_program::instruction::Instruction, InstructionData, ToAccountMetas};
+use solana_program_test::*;
+use solana_sdk::{signer::Signer, transaction::Transaction};
+use tests::fixtures::TestFixture;
+use validator_history::ValidatorHistory;
+
+fn update_block_production_ix(
+    fixture: &TestFixture,
+    epoch: u64,
+    leader_slots: u32,
+    blocks_produced: u32,
+) -> Instruction {
+    Instruction {
+        program_id: validator_history::id(),
+        data: validator_history::instruction::UpdateBlockProduction {
+            epoch,
+            leader_slots,
+            blocks_produced,
+        }
+        .data(),
+        accounts: validator_history::accounts::UpdateBlockProduction {
+            validator_history_account: fixture.validator_history_account,
+            vote_account: fixture.vote_account,
+            config: fixture.validator_history_config,
+            oracle_authority: fixture.keypair.pubkey(),
+        }
+        .to_account_metas(None),
+    }
+}
+
+#[tokio::test]
+async fn test_block_production_update() {
+    // init fixture
+    let fixture = TestFixture::new().await;
+    let ctx = &fixture.ctx;
+    fixture.initialize_config().await;
+    fixture.initialize_validator_history_account().await;
+
+    fixture.advance_num_epochs(1).await;
+
+    // upload epochs 0 and 1, then correct epoch 0
+    let transaction = Transaction::new_signed_with_payer(
+        &[
+            update_block_production_ix(&fixture, 0, 10, 9),
+            update_block_production_ix(&fixture, 1, 20, 20),
+            update_block_production_ix(&fixture, 0, 10, 8),
+        ],
+        Some(&fixture.keypair.pubkey()),
+        &[&fixture.keypair],
+        ctx.borrow().last_blockhash,
+    );
+    fixture.submit_transaction_assert_success(transaction).await;
+
+    // assert value
+    let account: ValidatorHistory = fixture
+        .load_and_deserialize(&fixture.validator_history_account)
+        .await;
+    assert_eq!(
+        account.history.leader_slots_range(0, 1),
+        vec![Some(10), Some(20)]
+    );
+    assert_eq!(
+        account.history.blocks_produced_range(0, 1),
+        vec![Some(8), Some(20)]
+    );
+    assert_eq!(account.history.epoch_credits_range(0, 1), vec![None, None]);
+
+    // more blocks than leader slots
+    let transaction = Transaction::new_signed_with_payer(
+        &[update_block_production_ix(&fixture, 1, 20, 21)],
+        Some(&fixture.keypair.pubkey()),
+        &[&fixture.keypair],
+        ctx.borrow().last_blockhash,
+    );
+    fixture
+        .submit_transaction_assert_error(transaction, "InvalidBlockProduction")
+        .await;
+
+    // future epoch
+    let transaction = Transaction::new_signed_with_payer(
+        &[update_block_production_ix(&fixture, 2, 20, 20)],
+        Some(&fixture.keypair.pubkey()),
+        &[&fixture.keypair],
+        ctx.borrow().last_blockhash,
+    );
+    fixture
+        .submit_transaction_assert_error(transaction, "EpochOutOfRange")
+        .await;
+}
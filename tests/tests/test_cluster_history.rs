@@ -70,3 +70,61 @@ async fn test_copy_cluster_info() {
     assert!(account.history.arr[1].total_blocks == 2);
     assert_eq!(account.cluster_history_last_update_slot, latest_slot)
 }
+
+#[tokio::test]
+async fn test_update_max_epoch_credits() {
+    let fixture = TestFixture::new().await;
+    let ctx = &fixture.ctx;
+    fixture.initialize_config().await;
+    fixture.initialize_cluster_history_account().await;
+
+    fixture.advance_num_epochs(2).await;
+
+    let update_max_epoch_credits = |epoch: u64, max_epoch_credits: u32| Instruction {
+        program_id: validator_history::id(),
+        data: validator_history::instruction::UpdateMaxEpochCredits {
+            epoch,
+            max_epoch_credits,
+        }
+        .data(),
+        accounts: validator_history::accounts::UpdateMaxEpochCredits {
+            cluster_history_account: fixture.cluster_history_account,
+            config: fixture.validator_history_config,
+            oracle_authority: fixture.keypair.pubkey(),
+        }
+        .to_account_metas(None),
+    };
+
+    // Upload epochs 0 and 1, then correct epoch 1
+    let transaction = Transaction::new_signed_with_payer(
+        &[
+            update_max_epoch_credits(0, 1000),
+            update_max_epoch_credits(1, 2000),
+            update_max_epoch_credits(1, 3000),
+        ],
+        Some(&fixture.keypair.pubkey()),
+        &[&fixture.keypair],
+        ctx.borrow().last_blockhash,
+    );
+    fixture.submit_transaction_assert_success(transaction).await;
+
+    let account: ClusterHistory = fixture
+        .load_and_deserialize(&fixture.cluster_history_account)
+        .await;
+    assert_eq!(
+        account.history.max_epoch_credits_range(0, 2),
+        vec![Some(1000), Some(3000), None]
+    );
+    assert_eq!(account.history.arr[0].total_blocks, u32::MAX);
+
+    // The current epoch is not final yet
+    let transaction = Transaction::new_signed_with_payer(
+        &[update_max_epoch_credits(2, 1000)],
+        Some(&fixture.keypair.pubkey()),
+        &[&fixture.keypair],
+        ctx.borrow().last_blockhash,
+    );
+    fixture
+        .submit_transaction_assert_error(transaction, "EpochOutOfRange")
+        .await;
+}
@@ -1,6 +1,6 @@
 #![allow(clippy::await_holding_refcell_ref)]
 use anchor_lang::{prelude::Clock, system_program, InstructionData, ToAccountMetas};
-use seraph::VList;
+use seraph::{Pool, StakeRegistry, StakeStatus, VList};
 use solana_program_test::*;
 use solana_sdk::{
     instruction::Instruction,
@@ -22,7 +22,14 @@ async fn test_seraph() -> Result<(), Box<dyn std::error::Error>> {
 
     // seraph specific setup
     fixture.initialize_seraph().await;
-    fixture.init_and_auth_stake_accounts_to_admin().await;
+    fixture.init_and_auth_stake_accounts_to_pool().await;
+
+    let top_10_percentile = TOTAL_VALIDATORS / 10; // 20 / 10 => 2
+    let stake_accounts: Vec<_> = fixture.stake_accounts[0..top_10_percentile]
+        .iter()
+        .map(|stake_account| stake_account.pubkey())
+        .collect();
+    fixture.register_stake_accounts(&stake_accounts).await;
 
     fixture.copy_vote_accounts(1).await;
 
@@ -61,6 +68,7 @@ async fn test_seraph() -> Result<(), Box<dyn std::error::Error>> {
                 admin: fixture.admin.pubkey(),
                 validator_history_account: fixture.validator_history_accounts[i],
                 vote_account: fixture.vote_accounts[i].pubkey(),
+                cluster_history_account: fixture.cluster_history_account,
                 pool: fixture.pool,
                 v_list: fixture.v_list,
             }
@@ -89,7 +97,6 @@ async fn test_seraph() -> Result<(), Box<dyn std::error::Error>> {
     }
 
     // get top 10% performing validators
-    let top_10_percentile = TOTAL_VALIDATORS / 10; // 20 / 10 => 2
     let v_list_account: VList = fixture.load_and_deserialize(&fixture.v_list).await;
 
     // delegate stake accounts with admin
@@ -105,6 +112,7 @@ async fn test_seraph() -> Result<(), Box<dyn std::error::Error>> {
                 stake_history: stake_history::id(),
                 stake_config: config::ID,
                 pool: fixture.pool,
+                stake_registry: fixture.stake_registry,
                 system_program: system_program::ID,
                 stake_program: solana_sdk::stake::program::ID,
             }
@@ -132,51 +140,58 @@ async fn test_seraph() -> Result<(), Box<dyn std::error::Error>> {
         }
     }
 
-    // fixture.advance_num_epochs(1).await;
-
-    // redelegate stake accounts with admin
-    // for i in 0..top_10_percentile {
-    //     let instruction = Instruction {
-    //         program_id: seraph::id(),
-    //         data: seraph::instruction::RedelegateStake {}.data(),
-    //         accounts: seraph::accounts::RedelegateStake {
-    //             admin: fixture.admin.pubkey(),
-    //             stake_account: fixture.stake_accounts[i].pubkey(), // can be any of the stake accounts for for simplicity I put "i"
-    //             old_validator_vote: fixture.vote_accounts[i].pubkey(),
-    //             new_validator_vote: fixture.vote_accounts[i * 2].pubkey(),
-    //             clock: clock::id(),
-    //             stake_history: stake_history::id(),
-    //             stake_config: config::ID,
-    //             pool: fixture.pool,
-    //             redelegate_stake_account: fixture.redelegate_stake_accounts[i].pubkey(),
-    //             system_program: system_program::ID,
-    //             stake_program: solana_sdk::stake::program::ID,
-    //         }
-    //         .to_account_metas(None),
-    //     };
-
-    //     let transaction = Transaction::new_signed_with_payer(
-    //         &[instruction],
-    //         Some(&fixture.admin.pubkey()),
-    //         &[&fixture.admin, &fixture.redelegate_stake_accounts[i]],
-    //         fixture
-    //             .ctx
-    //             .borrow_mut()
-    //             .get_new_latest_blockhash()
-    //             .await
-    //             .unwrap(),
-    //     );
-    //     if let Err(e) = ctx
-    //         .borrow_mut()
-    //         .banks_client
-    //         .process_transaction_with_preflight(transaction)
-    //         .await
-    //     {
-    //         panic!("Error: {}", e);
-    //     }
-    // }
-
-    // fixture.advance_num_epochs(1).await;
+    // rebalance half of the stake on the other top validators into the best one. The stake was
+    // delegated this epoch, so the deactivated transient accounts are inactive right away.
+    let transaction = Transaction::new_signed_with_payer(
+        &[fixture.set_rebalance_params_ix(0, 10_000)],
+        Some(&fixture.admin.pubkey()),
+        &[&fixture.admin],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    fixture.submit_transaction_assert_success(transaction).await;
+
+    let best_validator_vote = v_list_account.validators[0].validator;
+    for i in 1..top_10_percentile {
+        let stake_account = ctx
+            .borrow_mut()
+            .banks_client
+            .get_account(fixture.stake_accounts[i].pubkey())
+            .await?
+            .unwrap();
+        let validator_vote = v_list_account.validators[i].validator;
+
+        let transaction = Transaction::new_signed_with_payer(
+            &[
+                fixture.redelegate_stake_ix(
+                    fixture.stake_accounts[i].pubkey(),
+                    validator_vote,
+                    best_validator_vote,
+                    stake_account.lamports / 2,
+                    0,
+                ),
+                fixture.finalize_transient_stake_ix(
+                    Pool::transient_stake_pubkey(fixture.pool, validator_vote, 0),
+                    best_validator_vote,
+                ),
+            ],
+            Some(&fixture.admin.pubkey()),
+            &[&fixture.admin, &fixture.keypair],
+            fixture
+                .ctx
+                .borrow_mut()
+                .get_new_latest_blockhash()
+                .await
+                .unwrap(),
+        );
+        if let Err(e) = ctx
+            .borrow_mut()
+            .banks_client
+            .process_transaction_with_preflight(transaction)
+            .await
+        {
+            panic!("Error: {}", e);
+        }
+    }
 
     // deactivate stake accounts with admin
     for i in 0..top_10_percentile {
@@ -188,6 +203,7 @@ async fn test_seraph() -> Result<(), Box<dyn std::error::Error>> {
                 stake_account: fixture.stake_accounts[i].pubkey(), // can be any of the stake accounts for for simplicity I put "i"
                 clock: clock::id(),
                 pool: fixture.pool,
+                stake_registry: fixture.stake_registry,
                 system_program: system_program::ID,
                 stake_program: solana_sdk::stake::program::ID,
             }
@@ -215,5 +231,25 @@ async fn test_seraph() -> Result<(), Box<dyn std::error::Error>> {
         }
     }
 
+    let stake_registry: StakeRegistry = fixture.load_and_deserialize(&fixture.stake_registry).await;
+    assert_eq!(stake_registry.entries().len(), 2 * top_10_percentile - 1);
+    for i in 0..top_10_percentile {
+        let entry = stake_registry.get(&stake_accounts[i]).unwrap();
+        assert_eq!(entry.vote_account, v_list_account.validators[i].validator);
+        assert_eq!(entry.status, StakeStatus::Deactivating);
+    }
+    for i in 1..top_10_percentile {
+        let transient_entry = stake_registry
+            .get(&Pool::transient_stake_pubkey(
+                fixture.pool,
+                v_list_account.validators[i].validator,
+                0,
+            ))
+            .unwrap();
+        assert_eq!(transient_entry.vote_account, best_validator_vote);
+        assert_eq!(transient_entry.status, StakeStatus::Activating);
+        assert!(!transient_entry.is_transient);
+    }
+
     Ok(())
 }
@@ -0,0 +1,274 @@
+#![allow(clippy::await_holding_refcell_ref)]
+use anchor_lang::solana_program::program_option::COption;
+use anchor_spl::token::{Mint, TokenAccount};
+use seraph::Pool;
+use solana_program_test::*;
+use solana_sdk::{account::Account, pubkey::Pubkey, signer::Signer, transaction::Transaction};
+use tests::seraph_fixtures::STestFixture;
+
+#[tokio::test]
+async fn test_close_empty_pool() {
+    let fixture = STestFixture::new().await;
+    let ctx = &fixture.ctx;
+    fixture.initialize_seraph().await;
+
+    let closed_accounts = [
+        fixture.pool,
+        fixture.v_list,
+        fixture.manager_fee_account,
+        fixture.reserve,
+    ];
+    let mut reclaimed = 0;
+    for account in closed_accounts {
+        reclaimed += ctx
+            .borrow_mut()
+            .banks_client
+            .get_balance(account)
+            .await
+            .unwrap();
+    }
+    let admin_lamports = ctx
+        .borrow_mut()
+        .banks_client
+        .get_balance(fixture.admin.pubkey())
+        .await
+        .unwrap();
+
+    let transaction = Transaction::new_signed_with_payer(
+        &[fixture.close_pool_ix(false, false)],
+        Some(&fixture.keypair.pubkey()),
+        &[&fixture.keypair, &fixture.admin],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    fixture.submit_transaction_assert_success(transaction).await;
+
+    for account in closed_accounts {
+        assert!(ctx
+            .borrow_mut()
+            .banks_client
+            .get_account(account)
+            .await
+            .unwrap()
+            .is_none());
+    }
+    assert_eq!(
+        ctx.borrow_mut()
+            .banks_client
+            .get_balance(fixture.admin.pubkey())
+            .await
+            .unwrap(),
+        admin_lamports + reclaimed
+    );
+
+    // the pool mint is left behind, and reused when the pool is initialized again
+    let transaction = Transaction::new_signed_with_payer(
+        &[fixture.initialize_seraph_ix()],
+        Some(&fixture.keypair.pubkey()),
+        &[&fixture.keypair, &fixture.admin],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    fixture.submit_transaction_assert_success(transaction).await;
+
+    let pool: Pool = fixture.load_and_deserialize(&fixture.pool).await;
+    assert_eq!(pool.pool_mint, fixture.pool_mint);
+    let pool_mint: Mint = fixture.load_and_deserialize(&fixture.pool_mint).await;
+    assert_eq!(pool_mint.mint_authority, COption::Some(fixture.pool));
+    assert_eq!(pool_mint.supply, 0);
+}
+
+#[tokio::test]
+async fn test_close_linked_pool() {
+    let fixture = STestFixture::new().await;
+    let ctx = &fixture.ctx;
+    fixture.initialize_seraph().await;
+
+    // a stake pool that handed its staker role to the pool
+    let stake_pool = Pubkey::new_unique();
+    let mut data = vec![0; 611];
+    data[0] = 1;
+    data[33..65].copy_from_slice(fixture.pool.as_ref());
+    ctx.borrow_mut().set_account(
+        &stake_pool,
+        &Account {
+            lamports: 1_000_000_000,
+            data,
+            owner: seraph::spl_stake_pool::ID,
+            ..Account::default()
+        }
+        .into(),
+    );
+
+    let transaction = Transaction::new_signed_with_payer(
+        &[
+            fixture.set_stake_pool_ix(stake_pool),
+            fixture.close_pool_ix(false, false),
+        ],
+        Some(&fixture.keypair.pubkey()),
+        &[&fixture.keypair, &fixture.admin],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    fixture
+        .submit_transaction_assert_error(transaction, "PoolNotEmpty")
+        .await;
+}
+
+#[tokio::test]
+async fn test_close_pool_with_stake() {
+    let fixture = STestFixture::new().await;
+    let ctx = &fixture.ctx;
+    fixture.initialize_seraph().await;
+    fixture.init_and_auth_stake_accounts_to_pool().await;
+    fixture.initialize_pool_history().await;
+
+    let stake_account = fixture.stake_accounts[0].pubkey();
+    fixture.register_stake_accounts(&[stake_account]).await;
+
+    let transaction = Transaction::new_signed_with_payer(
+        &[fixture.close_pool_ix(true, true)],
+        Some(&fixture.admin.pubkey()),
+        &[&fixture.admin],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    fixture
+        .submit_transaction_assert_error(transaction, "PoolNotEmpty")
+        .await;
+
+    let transaction = Transaction::new_signed_with_payer(
+        &[fixture.withdraw_stake_ix(stake_account)],
+        Some(&fixture.admin.pubkey()),
+        &[&fixture.admin],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    fixture.submit_transaction_assert_success(transaction).await;
+
+    // the depositor's pool tokens are outstanding until redeemed from the reserve
+    let transaction = Transaction::new_signed_with_payer(
+        &[fixture.close_pool_ix(true, true)],
+        Some(&fixture.admin.pubkey()),
+        &[&fixture.admin],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    fixture
+        .submit_transaction_assert_error(transaction, "PoolNotEmpty")
+        .await;
+
+    let depositor_account: TokenAccount = fixture
+        .load_and_deserialize(&fixture.depositor_token_account.pubkey())
+        .await;
+    let transaction = Transaction::new_signed_with_payer(
+        &[fixture.instant_unstake_ix(
+            fixture.keypair.pubkey(),
+            fixture.depositor_token_account.pubkey(),
+            depositor_account.amount,
+            0,
+        )],
+        Some(&fixture.keypair.pubkey()),
+        &[&fixture.keypair],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    fixture.submit_transaction_assert_success(transaction).await;
+
+    // the instant unstake fee stays in the pool, so only the registry can show it is empty
+    let transaction = Transaction::new_signed_with_payer(
+        &[fixture.close_pool_ix(false, true)],
+        Some(&fixture.admin.pubkey()),
+        &[&fixture.admin],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    fixture
+        .submit_transaction_assert_error(transaction, "PoolNotEmpty")
+        .await;
+
+    let reserve_lamports = ctx
+        .borrow_mut()
+        .banks_client
+        .get_balance(fixture.reserve)
+        .await
+        .unwrap();
+    let admin_lamports = ctx
+        .borrow_mut()
+        .banks_client
+        .get_balance(fixture.admin.pubkey())
+        .await
+        .unwrap();
+
+    let transaction = Transaction::new_signed_with_payer(
+        &[fixture.close_pool_ix(true, true)],
+        Some(&fixture.keypair.pubkey()),
+        &[&fixture.keypair, &fixture.admin],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    fixture.submit_transaction_assert_success(transaction).await;
+
+    for account in [
+        fixture.pool,
+        fixture.v_list,
+        fixture.stake_registry,
+        fixture.pool_history,
+        fixture.reserve,
+    ] {
+        assert!(ctx
+            .borrow_mut()
+            .banks_client
+            .get_account(account)
+            .await
+            .unwrap()
+            .is_none());
+    }
+    assert!(
+        ctx.borrow_mut()
+            .banks_client
+            .get_balance(fixture.admin.pubkey())
+            .await
+            .unwrap()
+            > admin_lamports + reserve_lamports
+    );
+}
+
+#[tokio::test]
+async fn test_close_pool_with_managed_stake() {
+    let fixture = STestFixture::new().await;
+    let ctx = &fixture.ctx;
+    fixture.initialize_seraph().await;
+    fixture.init_stake_account_to_staker(0).await;
+
+    let owner = &fixture.stakers[0];
+    let stake_account = fixture.stake_accounts[0].pubkey();
+    let transaction = Transaction::new_signed_with_payer(
+        &[fixture.register_managed_stake_ix(owner.pubkey(), stake_account)],
+        Some(&owner.pubkey()),
+        &[owner],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    fixture.submit_transaction_assert_success(transaction).await;
+
+    // the owner could no longer revoke once the pool is gone
+    let transaction = Transaction::new_signed_with_payer(
+        &[fixture.close_pool_ix(false, false)],
+        Some(&fixture.admin.pubkey()),
+        &[&fixture.admin],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    fixture
+        .submit_transaction_assert_error(transaction, "PoolNotEmpty")
+        .await;
+
+    let transaction = Transaction::new_signed_with_payer(
+        &[
+            fixture.revoke_managed_stake_ix(owner.pubkey(), stake_account),
+            fixture.close_pool_ix(false, false),
+        ],
+        Some(&fixture.admin.pubkey()),
+        &[&fixture.admin, owner],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    fixture.submit_transaction_assert_success(transaction).await;
+
+    assert!(ctx
+        .borrow_mut()
+        .banks_client
+        .get_account(fixture.pool)
+        .await
+        .unwrap()
+        .is_none());
+}
@@ -0,0 +1,207 @@
+#![allow(clippy::await_holding_refcell_ref)]
+use anchor_lang::prelude::Pubkey;
+use seraph::{
+    events::{
+        StakeDeactivated, StakeDelegated, StakeDeposited, StakeWithdrawn, ValidatorRemoved,
+        ValidatorScoreUpdated, ValidatorSelectionChanged,
+    },
+    Pool, PoolParams, RemovalReason, StakeStatus,
+};
+use solana_program_test::*;
+use solana_sdk::{signer::Signer, transaction::Transaction};
+use tests::seraph_fixtures::{decode_events, STestFixture};
+use validator_history::ValidatorHistoryEntry;
+
+#[tokio::test]
+async fn test_stake_lifecycle_events() {
+    let fixture = STestFixture::new().await;
+    let ctx = &fixture.ctx;
+    fixture.initialize_seraph().await;
+    fixture.init_and_auth_stake_accounts_to_pool().await;
+    fixture.initialize_stake_registry().await;
+
+    let stake_account = fixture.stake_accounts[0].pubkey();
+    let validator_vote = fixture.vote_accounts[0].pubkey();
+    let lamports = ctx
+        .borrow_mut()
+        .banks_client
+        .get_balance(stake_account)
+        .await
+        .unwrap();
+
+    let transaction = Transaction::new_signed_with_payer(
+        &[fixture.add_stake_account_ix(stake_account)],
+        Some(&fixture.admin.pubkey()),
+        &[&fixture.admin],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    let events: Vec<StakeDeposited> = fixture.submit_transaction_get_events(transaction).await;
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].pool, fixture.pool);
+    assert_eq!(events[0].stake_account, stake_account);
+    assert_eq!(events[0].validator, Pubkey::default());
+    assert_eq!(events[0].lamports, lamports);
+    assert_eq!(events[0].status, StakeStatus::Inactive);
+    assert_eq!(events[0].pool_tokens, lamports);
+    assert_eq!(events[0].old_total_lamports, 0);
+    assert_eq!(events[0].new_total_lamports, lamports);
+
+    let transaction = Transaction::new_signed_with_payer(
+        &[fixture.delegate_stake_ix(stake_account, validator_vote)],
+        Some(&fixture.admin.pubkey()),
+        &[&fixture.admin],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    let events: Vec<StakeDelegated> = fixture.submit_transaction_get_events(transaction).await;
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].stake_account, stake_account);
+    assert_eq!(events[0].old_validator, Pubkey::default());
+    assert_eq!(events[0].new_validator, validator_vote);
+    assert_eq!(events[0].lamports, lamports);
+    assert_eq!(events[0].old_status, StakeStatus::Inactive);
+    assert_eq!(events[0].new_status, StakeStatus::Activating);
+
+    let transaction = Transaction::new_signed_with_payer(
+        &[fixture.deactivate_stake_ix(stake_account)],
+        Some(&fixture.admin.pubkey()),
+        &[&fixture.admin],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    let events: Vec<StakeDeactivated> = fixture.submit_transaction_get_events(transaction).await;
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].stake_account, stake_account);
+    assert_eq!(events[0].validator, validator_vote);
+    assert_eq!(events[0].old_status, StakeStatus::Activating);
+    assert_eq!(events[0].new_status, StakeStatus::Deactivating);
+
+    // stake deactivated in its activation epoch is inactive right away
+    let transaction = Transaction::new_signed_with_payer(
+        &[fixture.withdraw_stake_ix(stake_account)],
+        Some(&fixture.admin.pubkey()),
+        &[&fixture.admin],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    let events: Vec<StakeWithdrawn> = fixture.submit_transaction_get_events(transaction).await;
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].stake_account, stake_account);
+    assert_eq!(events[0].lamports, lamports);
+    assert_eq!(events[0].old_reserve_lamports, 0);
+    assert_eq!(events[0].new_reserve_lamports, lamports);
+
+    // lamports only moved within the pool
+    let pool: Pool = fixture.load_and_deserialize(&fixture.pool).await;
+    assert_eq!(pool.total_lamports, lamports);
+}
+
+// One epoch of voting at no commission, earning `epoch_credits`
+fn history_entry(epoch_credits: u32) -> ValidatorHistoryEntry {
+    ValidatorHistoryEntry {
+        epoch: 0,
+        epoch_credits,
+        commission: 0,
+        ..ValidatorHistoryEntry::default()
+    }
+}
+
+#[tokio::test]
+async fn test_score_events() {
+    let fixture = STestFixture::new().await;
+    let ctx = &fixture.ctx;
+    fixture.initialize_seraph().await;
+    fixture.initialize_config().await;
+    fixture.initialize_validator_history_accounts().await;
+    fixture.copy_vote_accounts(1).await;
+    fixture
+        .set_validator_history(0, &[history_entry(1_000)])
+        .await;
+    fixture
+        .set_validator_history(1, &[history_entry(2_000)])
+        .await;
+
+    let validator_vote = fixture.vote_accounts[0].pubkey();
+
+    // the first score adds the validator to the list, where it is the only one eligible
+    let transaction = Transaction::new_signed_with_payer(
+        &[fixture.calculate_score_ix(0)],
+        Some(&fixture.admin.pubkey()),
+        &[&fixture.admin],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    let logs = fixture.submit_transaction_get_logs(transaction).await;
+    let events: Vec<ValidatorScoreUpdated> = decode_events(&logs);
+    let selection_events: Vec<ValidatorSelectionChanged> = decode_events(&logs);
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].pool, fixture.pool);
+    assert_eq!(events[0].validator, validator_vote);
+    assert_eq!(events[0].old_score, None);
+    assert_eq!(events[0].old_rank, None);
+    assert_eq!(events[0].new_rank, Some(0));
+    let score = events[0].new_score;
+    assert_eq!(selection_events.len(), 1);
+    assert_eq!(selection_events[0].validator, validator_vote);
+    assert!(selection_events[0].selected);
+    assert_eq!(selection_events[0].old_rank, None);
+    assert_eq!(selection_events[0].new_rank, Some(0));
+    assert_eq!(selection_events[0].old_selected_count, 0);
+    assert_eq!(selection_events[0].new_selected_count, 1);
+
+    // a higher score takes the only eligible place from the first validator
+    let transaction = Transaction::new_signed_with_payer(
+        &[fixture.calculate_score_ix(1)],
+        Some(&fixture.admin.pubkey()),
+        &[&fixture.admin],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    let events: Vec<ValidatorSelectionChanged> =
+        fixture.submit_transaction_get_events(transaction).await;
+    assert_eq!(events.len(), 2);
+    assert_eq!(events[0].validator, validator_vote);
+    assert!(!events[0].selected);
+    assert_eq!(events[0].old_rank, Some(0));
+    assert_eq!(events[0].new_rank, Some(1));
+    assert_eq!(events[1].validator, fixture.vote_accounts[1].pubkey());
+    assert!(events[1].selected);
+    assert_eq!(events[1].old_rank, None);
+    assert_eq!(events[1].new_rank, Some(0));
+    for event in events.iter() {
+        assert_eq!(event.old_selected_count, 1);
+        assert_eq!(event.new_selected_count, 1);
+    }
+
+    // scoring again only updates the score
+    let transaction = Transaction::new_signed_with_payer(
+        &[fixture.calculate_score_ix(0)],
+        Some(&fixture.admin.pubkey()),
+        &[&fixture.admin],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    let logs = fixture.submit_transaction_get_logs(transaction).await;
+    let events: Vec<ValidatorScoreUpdated> = decode_events(&logs);
+    let selection_events: Vec<ValidatorSelectionChanged> = decode_events(&logs);
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].old_score, Some(score));
+    assert_eq!(events[0].new_score, score);
+    assert_eq!(events[0].old_rank, Some(1));
+    assert_eq!(events[0].old_rank, events[0].new_rank);
+    assert!(selection_events.is_empty());
+    let components = events[0].components;
+
+    // shrinking the list removes the lower scored validator, with how it was scored
+    let pool: Pool = fixture.load_and_deserialize(&fixture.pool).await;
+    let transaction = Transaction::new_signed_with_payer(
+        &[fixture.update_params_ix(PoolParams {
+            max_validators: 1,
+            ..pool.params
+        })],
+        Some(&fixture.admin.pubkey()),
+        &[&fixture.admin],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    let events: Vec<ValidatorRemoved> = fixture.submit_transaction_get_events(transaction).await;
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].validator, validator_vote);
+    assert_eq!(events[0].reason, RemovalReason::ListTruncated);
+    assert_eq!(events[0].score, score);
+    assert_eq!(events[0].rank, 1);
+    assert_eq!(events[0].components, components);
+}
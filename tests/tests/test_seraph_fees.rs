@@ -0,0 +1,188 @@
+#![allow(clippy::await_holding_refcell_ref)]
+use anchor_lang::{InstructionData, ToAccountMetas};
+use anchor_spl::token::{Mint, TokenAccount};
+use seraph::{Fees, Pool, FEE_CHANGE_DELAY_EPOCHS};
+use solana_program_test::*;
+use solana_sdk::{instruction::Instruction, signer::Signer, transaction::Transaction};
+use tests::seraph_fixtures::STestFixture;
+
+fn set_fees_ix(
+    fixture: &STestFixture,
+    management_fee_bps: u16,
+    performance_fee_bps: u16,
+) -> Instruction {
+    Instruction {
+        program_id: seraph::id(),
+        data: seraph::instruction::SetFees {
+            management_fee_bps,
+            performance_fee_bps,
+        }
+        .data(),
+        accounts: seraph::accounts::SetFees {
+            admin: fixture.admin.pubkey(),
+            pool: fixture.pool,
+        }
+        .to_account_metas(None),
+    }
+}
+
+#[tokio::test]
+async fn test_fees_minted_on_rewards() -> Result<(), Box<dyn std::error::Error>> {
+    let fixture = STestFixture::new().await;
+    let ctx = &fixture.ctx;
+    fixture.initialize_seraph().await;
+    fixture.init_and_auth_stake_accounts_to_pool().await;
+
+    let stake_accounts: Vec<_> = fixture.stake_accounts[0..2]
+        .iter()
+        .map(|stake_account| stake_account.pubkey())
+        .collect();
+    fixture.register_stake_accounts(&stake_accounts).await;
+
+    // stage fees: 10% management, 20% performance
+    let transaction = Transaction::new_signed_with_payer(
+        &[set_fees_ix(&fixture, 1_000, 2_000)],
+        Some(&fixture.admin.pubkey()),
+        &[&fixture.admin],
+        ctx.borrow().last_blockhash,
+    );
+    fixture.submit_transaction_assert_success(transaction).await;
+
+    let pool: Pool = fixture.load_and_deserialize(&fixture.pool).await;
+    assert_eq!(pool.fees, Fees::default());
+    assert_eq!(
+        pool.next_fees,
+        Some(Fees {
+            management_fee_bps: 1_000,
+            performance_fee_bps: 2_000,
+        })
+    );
+    assert_eq!(pool.next_fees_epoch, FEE_CHANGE_DELAY_EPOCHS);
+
+    // registered stake is principal, so the first update charges nothing
+    fixture.advance_num_epochs(1).await;
+    let transaction = Transaction::new_signed_with_payer(
+        &[fixture.update_pool_balance_ix(&stake_accounts)],
+        Some(&fixture.admin.pubkey()),
+        &[&fixture.admin],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    fixture.submit_transaction_assert_success(transaction).await;
+
+    let pool: Pool = fixture.load_and_deserialize(&fixture.pool).await;
+    let mut baseline = 0;
+    for stake_account in stake_accounts.iter() {
+        baseline += ctx
+            .borrow_mut()
+            .banks_client
+            .get_account(*stake_account)
+            .await?
+            .unwrap()
+            .lamports;
+    }
+    assert_eq!(pool.total_lamports, baseline);
+    assert_eq!(pool.last_update_epoch, 1);
+    assert_eq!(pool.last_epoch_rewards, 0);
+    assert_eq!(pool.fees, Fees::default());
+
+    let fee_account: TokenAccount = fixture
+        .load_and_deserialize(&fixture.manager_fee_account)
+        .await;
+    assert_eq!(fee_account.amount, 0);
+
+    // second update in the same epoch fails
+    let transaction = Transaction::new_signed_with_payer(
+        &[fixture.update_pool_balance_ix(&stake_accounts)],
+        Some(&fixture.admin.pubkey()),
+        &[&fixture.admin],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    fixture
+        .submit_transaction_assert_error(transaction, "PoolAlreadyUpdated")
+        .await;
+
+    // rewards land after the fee delay has elapsed
+    let rewards = 1_000_000_000;
+    fixture.advance_num_epochs(FEE_CHANGE_DELAY_EPOCHS).await;
+    fixture.add_lamports(&stake_accounts[0], rewards).await;
+
+    let transaction = Transaction::new_signed_with_payer(
+        &[fixture.update_pool_balance_ix(&stake_accounts)],
+        Some(&fixture.admin.pubkey()),
+        &[&fixture.admin],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    fixture.submit_transaction_assert_success(transaction).await;
+
+    let pool: Pool = fixture.load_and_deserialize(&fixture.pool).await;
+    assert_eq!(pool.total_lamports, baseline + rewards);
+    assert_eq!(
+        pool.fees,
+        Fees {
+            management_fee_bps: 1_000,
+            performance_fee_bps: 2_000,
+        }
+    );
+    assert_eq!(pool.next_fees, None);
+
+    // deposits were minted 1:1, and the fee tokens leave the manager owning the fee lamports
+    let fee_lamports = rewards * 3 / 10;
+    let fee_account: TokenAccount = fixture
+        .load_and_deserialize(&fixture.manager_fee_account)
+        .await;
+    let depositor_account: TokenAccount = fixture
+        .load_and_deserialize(&fixture.depositor_token_account.pubkey())
+        .await;
+    let pool_mint: Mint = fixture.load_and_deserialize(&fixture.pool_mint).await;
+    assert_eq!(depositor_account.amount, baseline);
+    assert_eq!(
+        fee_account.amount,
+        Pool::pool_tokens_for_fee(fee_lamports, pool.total_lamports, baseline).unwrap()
+    );
+    assert_eq!(pool_mint.supply, baseline + fee_account.amount);
+    // up to rounding, the manager redeems the fee and depositors the rest
+    let manager_lamports = pool
+        .lamports_for_pool_tokens(fee_account.amount, pool_mint.supply)
+        .unwrap();
+    let depositor_lamports = pool
+        .lamports_for_pool_tokens(depositor_account.amount, pool_mint.supply)
+        .unwrap();
+    assert!(manager_lamports <= fee_lamports && fee_lamports - manager_lamports <= 2);
+    assert!(depositor_lamports >= pool.total_lamports - fee_lamports);
+    assert_eq!(
+        pool.high_water_mark,
+        Pool::exchange_rate(pool.total_lamports, pool_mint.supply).unwrap()
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_invalid_fees() {
+    let fixture = STestFixture::new().await;
+    let ctx = &fixture.ctx;
+    fixture.initialize_seraph().await;
+
+    let transaction = Transaction::new_signed_with_payer(
+        &[set_fees_ix(&fixture, 6_000, 5_000)],
+        Some(&fixture.admin.pubkey()),
+        &[&fixture.admin],
+        ctx.borrow().last_blockhash,
+    );
+    fixture
+        .submit_transaction_assert_error(transaction, "InvalidFee")
+        .await;
+
+    // only the admin can stage fees
+    let mut instruction = set_fees_ix(&fixture, 100, 0);
+    instruction.accounts[0].pubkey = fixture.keypair.pubkey();
+    let transaction = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&fixture.keypair.pubkey()),
+        &[&fixture.keypair],
+        ctx.borrow().last_blockhash,
+    );
+    fixture
+        .submit_transaction_assert_error(transaction, "ConstraintHasOne")
+        .await;
+}
@@ -0,0 +1,108 @@
+#![allow(clippy::await_holding_refcell_ref)]
+use seraph::{events::MevHarvested, Pool, StakeRegistry};
+use solana_program_test::*;
+use solana_sdk::{signer::Signer, transaction::Transaction};
+use tests::seraph_fixtures::STestFixture;
+
+#[tokio::test]
+async fn test_harvest_mev_tips() {
+    let fixture = STestFixture::new().await;
+    let ctx = &fixture.ctx;
+    fixture.initialize_seraph().await;
+    fixture.init_and_auth_stake_accounts_to_pool().await;
+
+    let stake_accounts: Vec<_> = fixture.stake_accounts[0..2]
+        .iter()
+        .map(|stake_account| stake_account.pubkey())
+        .collect();
+    fixture.register_stake_accounts(&stake_accounts).await;
+
+    let transaction = Transaction::new_signed_with_payer(
+        &[fixture.delegate_stake_ix(stake_accounts[0], fixture.vote_accounts[0].pubkey())],
+        Some(&fixture.admin.pubkey()),
+        &[&fixture.admin],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    fixture.submit_transaction_assert_success(transaction).await;
+
+    // nothing sits above the delegated stake yet
+    let transaction = Transaction::new_signed_with_payer(
+        &[fixture.harvest_ix(stake_accounts[0])],
+        Some(&fixture.keypair.pubkey()),
+        &[&fixture.keypair],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    fixture
+        .submit_transaction_assert_error(transaction, "NothingToHarvest")
+        .await;
+
+    // an MEV claim lands on the delegated account
+    let tips = 250_000_000;
+    fixture.add_lamports(&stake_accounts[0], tips).await;
+    let reserve_lamports = ctx
+        .borrow_mut()
+        .banks_client
+        .get_balance(fixture.reserve)
+        .await
+        .unwrap();
+
+    let transaction = Transaction::new_signed_with_payer(
+        &[fixture.harvest_ix(stake_accounts[0])],
+        Some(&fixture.keypair.pubkey()),
+        &[&fixture.keypair],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    let events: Vec<MevHarvested> = fixture.submit_transaction_get_events(transaction).await;
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].stake_account, stake_accounts[0]);
+    assert_eq!(events[0].validator, fixture.vote_accounts[0].pubkey());
+    assert_eq!(events[0].lamports, tips);
+
+    assert_eq!(
+        ctx.borrow_mut()
+            .banks_client
+            .get_balance(fixture.reserve)
+            .await
+            .unwrap(),
+        reserve_lamports + tips
+    );
+    let pool: Pool = fixture.load_and_deserialize(&fixture.pool).await;
+    assert_eq!(pool.harvested_mev_lamports, tips);
+    let stake_registry: StakeRegistry = fixture.load_and_deserialize(&fixture.stake_registry).await;
+    let stake_account_lamports = ctx
+        .borrow_mut()
+        .banks_client
+        .get_balance(stake_accounts[0])
+        .await
+        .unwrap();
+    assert_eq!(
+        stake_registry.get(&stake_accounts[0]).unwrap().lamports,
+        stake_account_lamports
+    );
+
+    // undelegated accounts are all principal
+    let transaction = Transaction::new_signed_with_payer(
+        &[fixture.harvest_ix(stake_accounts[1])],
+        Some(&fixture.keypair.pubkey()),
+        &[&fixture.keypair],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    fixture
+        .submit_transaction_assert_error(transaction, "InvalidStakeAccount")
+        .await;
+
+    // the next balance update books the tips as MEV rewards
+    fixture.advance_num_epochs(1).await;
+    let transaction = Transaction::new_signed_with_payer(
+        &[fixture.update_pool_balance_ix(&stake_accounts)],
+        Some(&fixture.admin.pubkey()),
+        &[&fixture.admin],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    fixture.submit_transaction_assert_success(transaction).await;
+
+    let pool: Pool = fixture.load_and_deserialize(&fixture.pool).await;
+    assert_eq!(pool.harvested_mev_lamports, 0);
+    assert_eq!(pool.last_epoch_mev_rewards, tips);
+    assert!(pool.last_epoch_rewards >= tips);
+}
@@ -0,0 +1,263 @@
+#![allow(clippy::await_holding_refcell_ref)]
+use seraph::{Pool, ReserveParams, StakeRegistry, StakeStatus, VList, BASIS_POINTS};
+use solana_program_test::*;
+use solana_sdk::{signer::Signer, stake::state::StakeState, transaction::Transaction};
+use tests::seraph_fixtures::STestFixture;
+
+async fn reserve_lamports(fixture: &STestFixture) -> u64 {
+    let ctx = &fixture.ctx;
+    let rent = ctx.borrow_mut().banks_client.get_rent().await.unwrap();
+    ctx.borrow_mut()
+        .banks_client
+        .get_balance(fixture.reserve)
+        .await
+        .unwrap()
+        - rent.minimum_balance(0)
+}
+
+#[tokio::test]
+async fn test_instant_unstake_fee_curve() {
+    let fixture = STestFixture::new().await;
+    let ctx = &fixture.ctx;
+    fixture.initialize_seraph().await;
+    fixture.init_and_auth_stake_accounts_to_pool().await;
+
+    let stake_accounts: Vec<_> = fixture.stake_accounts[0..2]
+        .iter()
+        .map(|stake_account| stake_account.pubkey())
+        .collect();
+    fixture.register_stake_accounts(&stake_accounts).await;
+
+    // half the pool sits in the reserve
+    let transaction = Transaction::new_signed_with_payer(
+        &[fixture.withdraw_stake_ix(stake_accounts[0])],
+        Some(&fixture.admin.pubkey()),
+        &[&fixture.admin],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    fixture.submit_transaction_assert_success(transaction).await;
+
+    let pool: Pool = fixture.load_and_deserialize(&fixture.pool).await;
+    assert_eq!(pool.reserve_params, ReserveParams::default());
+    // the deposits were minted 1:1 into the empty pool
+    let owner = &fixture.keypair;
+    let supply = pool.total_lamports;
+    let pool_token_account = fixture.depositor_token_account.pubkey();
+
+    // well above the target the minimum fee applies, so asking for the full amount fails
+    let reserve = reserve_lamports(&fixture).await;
+    let pool_tokens = reserve / 4;
+    let transaction =
+        Transaction::new_signed_with_payer(
+            &[fixture.instant_unstake_ix(
+                owner.pubkey(),
+                pool_token_account,
+                pool_tokens,
+                pool_tokens,
+            )],
+            Some(&owner.pubkey()),
+            &[owner],
+            ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+        );
+    fixture
+        .submit_transaction_assert_error(transaction, "InstantUnstakeBelowMinimum")
+        .await;
+
+    let owner_lamports = ctx
+        .borrow_mut()
+        .banks_client
+        .get_balance(owner.pubkey())
+        .await
+        .unwrap();
+    let transaction = Transaction::new_signed_with_payer(
+        &[fixture.instant_unstake_ix(owner.pubkey(), pool_token_account, pool_tokens, 0)],
+        Some(&fixture.admin.pubkey()),
+        &[&fixture.admin, owner],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    fixture.submit_transaction_assert_success(transaction).await;
+
+    let fee = pool_tokens * pool.reserve_params.min_fee_bps as u64 / BASIS_POINTS;
+    assert_eq!(
+        ctx.borrow_mut()
+            .banks_client
+            .get_balance(owner.pubkey())
+            .await
+            .unwrap(),
+        owner_lamports + pool_tokens - fee
+    );
+    // the fee stays behind for the remaining holders
+    let new_pool: Pool = fixture.load_and_deserialize(&fixture.pool).await;
+    assert_eq!(
+        new_pool.total_lamports,
+        pool.total_lamports - pool_tokens + fee
+    );
+    assert_eq!(
+        reserve_lamports(&fixture).await,
+        reserve - pool_tokens + fee
+    );
+
+    // draining below a raised target costs more
+    let params = ReserveParams {
+        target_reserve_bps: 5_000,
+        min_fee_bps: 0,
+        max_fee_bps: 1_000,
+    };
+    let transaction = Transaction::new_signed_with_payer(
+        &[fixture.set_reserve_params_ix(params)],
+        Some(&fixture.admin.pubkey()),
+        &[&fixture.admin],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    fixture.submit_transaction_assert_success(transaction).await;
+
+    let pool: Pool = fixture.load_and_deserialize(&fixture.pool).await;
+    let supply = supply - pool_tokens;
+    let reserve = reserve_lamports(&fixture).await;
+    let lamports = pool.lamports_for_pool_tokens(pool_tokens, supply).unwrap();
+    let fee_bps = params.fee_bps(
+        reserve - lamports,
+        params.target_reserve_lamports(pool.total_lamports),
+    );
+    assert!(fee_bps > 0 && fee_bps < params.max_fee_bps);
+
+    let owner_lamports = ctx
+        .borrow_mut()
+        .banks_client
+        .get_balance(owner.pubkey())
+        .await
+        .unwrap();
+    let transaction = Transaction::new_signed_with_payer(
+        &[fixture.instant_unstake_ix(owner.pubkey(), pool_token_account, pool_tokens, 0)],
+        Some(&fixture.admin.pubkey()),
+        &[&fixture.admin, owner],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    fixture.submit_transaction_assert_success(transaction).await;
+
+    let fee = lamports * fee_bps as u64 / BASIS_POINTS;
+    assert_eq!(
+        ctx.borrow_mut()
+            .banks_client
+            .get_balance(owner.pubkey())
+            .await
+            .unwrap(),
+        owner_lamports + lamports - fee
+    );
+
+    // the reserve cannot pay out more than it holds
+    let transaction = Transaction::new_signed_with_payer(
+        &[fixture.instant_unstake_ix(owner.pubkey(), pool_token_account, supply - pool_tokens, 0)],
+        Some(&owner.pubkey()),
+        &[owner],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    fixture
+        .submit_transaction_assert_error(transaction, "InsufficientReserve")
+        .await;
+}
+
+#[tokio::test]
+async fn test_invalid_reserve_params() {
+    let fixture = STestFixture::new().await;
+    let ctx = &fixture.ctx;
+    fixture.initialize_seraph().await;
+
+    for params in [
+        ReserveParams {
+            target_reserve_bps: 10_001,
+            ..ReserveParams::default()
+        },
+        ReserveParams {
+            min_fee_bps: 500,
+            max_fee_bps: 100,
+            ..ReserveParams::default()
+        },
+    ] {
+        let transaction = Transaction::new_signed_with_payer(
+            &[fixture.set_reserve_params_ix(params)],
+            Some(&fixture.admin.pubkey()),
+            &[&fixture.admin],
+            ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+        );
+        fixture
+            .submit_transaction_assert_error(transaction, "InvalidReserveParams")
+            .await;
+    }
+}
+
+#[tokio::test]
+async fn test_unstake_queue_tops_up_reserve() {
+    let fixture = STestFixture::new().await;
+    let ctx = &fixture.ctx;
+    fixture.initialize_seraph().await;
+    fixture.initialize_and_score_validators().await;
+    fixture.init_and_auth_stake_accounts_to_pool().await;
+
+    let stake_accounts: Vec<_> = fixture.stake_accounts[0..2]
+        .iter()
+        .map(|stake_account| stake_account.pubkey())
+        .collect();
+    fixture.register_stake_accounts(&stake_accounts).await;
+
+    let v_list: VList = fixture.load_and_deserialize(&fixture.v_list).await;
+    let transaction = Transaction::new_signed_with_payer(
+        &[
+            fixture.delegate_stake_ix(
+                stake_accounts[0],
+                v_list.validators[v_list.idx - 1].validator,
+            ),
+            fixture.delegate_stake_ix(stake_accounts[1], v_list.validators[0].validator),
+        ],
+        Some(&fixture.admin.pubkey()),
+        &[&fixture.admin],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    fixture.submit_transaction_assert_success(transaction).await;
+
+    // without tickets, only the reserve target is split off the lowest scored stake
+    let pool: Pool = fixture.load_and_deserialize(&fixture.pool).await;
+    let target_reserve = pool
+        .reserve_params
+        .target_reserve_lamports(pool.total_lamports);
+    let validator = v_list.validators[v_list.idx - 1].validator;
+    let transient_stake_account = Pool::transient_stake_pubkey(fixture.pool, validator, 0);
+    let transaction = Transaction::new_signed_with_payer(
+        &[fixture.process_unstake_queue_ix(&stake_accounts, Some(validator), 0)],
+        Some(&fixture.keypair.pubkey()),
+        &[&fixture.keypair],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    fixture.submit_transaction_assert_success(transaction).await;
+
+    let rent = ctx.borrow_mut().banks_client.get_rent().await.unwrap();
+    let stake_registry: StakeRegistry = fixture.load_and_deserialize(&fixture.stake_registry).await;
+    let entries = stake_registry.entries();
+    assert_eq!(
+        entries.iter().map(|entry| entry.status).collect::<Vec<_>>(),
+        vec![
+            StakeStatus::Activating,
+            StakeStatus::Activating,
+            StakeStatus::Deactivating
+        ]
+    );
+    assert_eq!(entries[2].stake_account, transient_stake_account);
+    assert_eq!(
+        entries[2].lamports,
+        target_reserve + rent.minimum_balance(std::mem::size_of::<StakeState>())
+    );
+
+    // the stake on its way to the reserve already covers the target
+    let registered = [stake_accounts.as_slice(), &[transient_stake_account]].concat();
+    let transaction = Transaction::new_signed_with_payer(
+        &[fixture.process_unstake_queue_ix(&registered, None, 0)],
+        Some(&fixture.keypair.pubkey()),
+        &[&fixture.keypair],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    fixture.submit_transaction_assert_success(transaction).await;
+
+    let new_stake_registry: StakeRegistry =
+        fixture.load_and_deserialize(&fixture.stake_registry).await;
+    assert_eq!(new_stake_registry.entries(), stake_registry.entries());
+}
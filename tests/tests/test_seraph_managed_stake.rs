@@ -0,0 +1,177 @@
+#![allow(clippy::await_holding_refcell_ref)]
+use anchor_spl::stake::StakeAccount;
+use seraph::{ManagedStake, Pool, PoolParams, VList};
+use solana_program_test::*;
+use solana_sdk::{signer::Signer, transaction::Transaction};
+use tests::seraph_fixtures::STestFixture;
+
+#[tokio::test]
+async fn test_managed_stake_lifecycle() {
+    let fixture = STestFixture::new().await;
+    let ctx = &fixture.ctx;
+    fixture.initialize_seraph().await;
+    fixture.initialize_and_score_validators().await;
+    fixture.init_stake_account_to_staker(0).await;
+    fixture.init_stake_account_to_staker(1).await;
+
+    let owner = &fixture.stakers[0];
+    let stake_account = fixture.stake_accounts[0].pubkey();
+
+    // only the stake account's authority can register it
+    let transaction = Transaction::new_signed_with_payer(
+        &[fixture.register_managed_stake_ix(owner.pubkey(), fixture.stake_accounts[1].pubkey())],
+        Some(&owner.pubkey()),
+        &[owner],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    fixture
+        .submit_transaction_assert_error(transaction, "InvalidStakeAccount")
+        .await;
+
+    let transaction = Transaction::new_signed_with_payer(
+        &[fixture.register_managed_stake_ix(owner.pubkey(), stake_account)],
+        Some(&owner.pubkey()),
+        &[owner],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    fixture.submit_transaction_assert_success(transaction).await;
+
+    let managed_stake: ManagedStake = fixture
+        .load_and_deserialize(&fixture.managed_stake(stake_account))
+        .await;
+    assert_eq!(managed_stake.pool, fixture.pool);
+    assert_eq!(managed_stake.stake_account, stake_account);
+    assert_eq!(managed_stake.owner, owner.pubkey());
+    let pool: Pool = fixture.load_and_deserialize(&fixture.pool).await;
+    assert_eq!(pool.managed_stake_count, 1);
+    let stake: StakeAccount = fixture.load_and_deserialize(&stake_account).await;
+    let authorized = stake.authorized().unwrap();
+    assert_eq!(authorized.staker, fixture.pool);
+    assert_eq!(authorized.withdrawer, owner.pubkey());
+
+    // stake only goes to the eligible top of the VList
+    let v_list: VList = fixture.load_and_deserialize(&fixture.v_list).await;
+    let transaction = Transaction::new_signed_with_payer(
+        &[fixture
+            .delegate_managed_stake_ix(stake_account, v_list.validators[v_list.idx - 1].validator)],
+        Some(&fixture.admin.pubkey()),
+        &[&fixture.admin],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    fixture
+        .submit_transaction_assert_error(transaction, "ValidatorNotInList")
+        .await;
+
+    let validator = v_list.validators[0].validator;
+    let transaction = Transaction::new_signed_with_payer(
+        &[fixture.delegate_managed_stake_ix(stake_account, validator)],
+        Some(&fixture.admin.pubkey()),
+        &[&fixture.admin],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    fixture.submit_transaction_assert_success(transaction).await;
+
+    let stake: StakeAccount = fixture.load_and_deserialize(&stake_account).await;
+    assert_eq!(stake.delegation().unwrap().voter_pubkey, validator);
+
+    // stake on an eligible validator is left alone
+    let transaction = Transaction::new_signed_with_payer(
+        &[fixture.deactivate_managed_stake_ix(stake_account)],
+        Some(&fixture.admin.pubkey()),
+        &[&fixture.admin],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    fixture
+        .submit_transaction_assert_error(transaction, "ValidatorStillEligible")
+        .await;
+
+    let transaction = Transaction::new_signed_with_payer(
+        &[fixture.revoke_managed_stake_ix(owner.pubkey(), stake_account)],
+        Some(&owner.pubkey()),
+        &[owner],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    fixture.submit_transaction_assert_success(transaction).await;
+
+    assert!(ctx
+        .borrow_mut()
+        .banks_client
+        .get_account(fixture.managed_stake(stake_account))
+        .await
+        .unwrap()
+        .is_none());
+    let pool: Pool = fixture.load_and_deserialize(&fixture.pool).await;
+    assert_eq!(pool.managed_stake_count, 0);
+    let stake: StakeAccount = fixture.load_and_deserialize(&stake_account).await;
+    assert_eq!(stake.authorized().unwrap().staker, owner.pubkey());
+    assert_eq!(stake.delegation().unwrap().voter_pubkey, validator);
+}
+
+#[tokio::test]
+async fn test_deactivate_ineligible_managed_stake() {
+    let fixture = STestFixture::new().await;
+    let ctx = &fixture.ctx;
+    fixture.initialize_seraph().await;
+    fixture.initialize_and_score_validators().await;
+    fixture.init_stake_account_to_staker(0).await;
+
+    let owner = &fixture.stakers[0];
+    let stake_account = fixture.stake_accounts[0].pubkey();
+    let v_list: VList = fixture.load_and_deserialize(&fixture.v_list).await;
+    let validator = v_list.validators[1].validator;
+
+    let transaction = Transaction::new_signed_with_payer(
+        &[fixture.register_managed_stake_ix(owner.pubkey(), stake_account)],
+        Some(&owner.pubkey()),
+        &[owner],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    fixture.submit_transaction_assert_success(transaction).await;
+
+    let transaction = Transaction::new_signed_with_payer(
+        &[fixture.delegate_managed_stake_ix(stake_account, validator)],
+        Some(&fixture.admin.pubkey()),
+        &[&fixture.admin],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    fixture.submit_transaction_assert_success(transaction).await;
+
+    // narrowing the eligible validators to the top one drops the delegated validator
+    let transaction = Transaction::new_signed_with_payer(
+        &[
+            fixture.update_params_ix(PoolParams {
+                percentile: 1,
+                ..PoolParams::default()
+            }),
+            fixture.deactivate_managed_stake_ix(stake_account),
+        ],
+        Some(&fixture.admin.pubkey()),
+        &[&fixture.admin],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    fixture.submit_transaction_assert_success(transaction).await;
+
+    let clock = ctx
+        .borrow_mut()
+        .banks_client
+        .get_sysvar::<anchor_lang::prelude::Clock>()
+        .await
+        .unwrap();
+    let stake: StakeAccount = fixture.load_and_deserialize(&stake_account).await;
+    assert_eq!(stake.delegation().unwrap().deactivation_epoch, clock.epoch);
+
+    // the owner can reclaim the stake account while the pool is paused
+    let transaction = Transaction::new_signed_with_payer(
+        &[
+            fixture.pause_ix(fixture.admin.pubkey()),
+            fixture.revoke_managed_stake_ix(owner.pubkey(), stake_account),
+        ],
+        Some(&owner.pubkey()),
+        &[owner, &fixture.admin],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    fixture.submit_transaction_assert_success(transaction).await;
+
+    let stake: StakeAccount = fixture.load_and_deserialize(&stake_account).await;
+    assert_eq!(stake.authorized().unwrap().staker, owner.pubkey());
+}
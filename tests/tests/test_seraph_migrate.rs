@@ -0,0 +1,357 @@
+#![allow(clippy::await_holding_refcell_ref)]
+use anchor_lang::{prelude::Pubkey, solana_program::program_option::COption, Discriminator};
+use anchor_spl::token::{Mint, TokenAccount};
+use seraph::{
+    events::AccountMigrated, Pool, PoolParams, PoolV0, ReserveParams, ScoreComponents,
+    ScoringParams, VList, POOL_VERSION, VLIST_VERSION,
+};
+use solana_program_test::*;
+use solana_sdk::{account::Account, signer::Signer, transaction::Transaction};
+use tests::seraph_fixtures::STestFixture;
+
+// VList size before versioning, as pinned by the static assertion of that layout
+const LEGACY_VLIST_SIZE: usize = 8 + 4880;
+// VList size at version 1, before its entries held score components
+const V1_VLIST_SIZE: usize = 8 + 5016;
+
+/// Rewrites `address` with `data` zero padded to `size`, with the rent exempt minimum for that
+/// size
+async fn write_account(fixture: &STestFixture, address: Pubkey, mut data: Vec<u8>, size: usize) {
+    let ctx = &fixture.ctx;
+    let rent = ctx.borrow_mut().banks_client.get_rent().await.unwrap();
+
+    data.resize(size, 0);
+    ctx.borrow_mut().set_account(
+        &address,
+        &Account {
+            lamports: rent.minimum_balance(size),
+            data,
+            owner: seraph::id(),
+            executable: false,
+            rent_epoch: 0,
+        }
+        .into(),
+    );
+}
+
+/// `v_list` in the version 1 layout, up to the version byte, which is also the layout from before
+/// versioning
+fn v1_v_list_data(v_list: &VList) -> Vec<u8> {
+    let mut data = VList::DISCRIMINATOR.to_vec();
+    for entry in v_list.validators.iter() {
+        data.extend_from_slice(entry.validator.as_ref());
+        data.extend_from_slice(&entry.last_scored_epoch.to_le_bytes());
+        data.extend_from_slice(&entry.score.to_le_bytes());
+    }
+    data.extend_from_slice(&(v_list.idx as u64).to_le_bytes());
+    data.extend_from_slice(v_list.admin.as_ref());
+    data.extend_from_slice(v_list.pool.as_ref());
+    data.push(v_list.bump);
+    data.extend_from_slice(&[0; 7]);
+    data
+}
+
+/// Rewrites the pool and VList in their layouts from before versioning, when the pool had no
+/// mint or manager fee account yet
+async fn write_legacy_accounts(fixture: &STestFixture) -> (Pool, VList) {
+    let pool: Pool = fixture.load_and_deserialize(&fixture.pool).await;
+    let v_list: VList = fixture.load_and_deserialize(&fixture.v_list).await;
+
+    let mut data = Pool::DISCRIMINATOR.to_vec();
+    data.extend_from_slice(pool.admin.as_ref());
+    data.extend_from_slice(&pool.start_slot.to_le_bytes());
+    data.extend_from_slice(&pool.start_epoch.to_le_bytes());
+    data.push(pool.bump);
+    write_account(fixture, fixture.pool, data, PoolV0::SIZE).await;
+    for address in [fixture.pool_mint, fixture.manager_fee_account] {
+        fixture
+            .ctx
+            .borrow_mut()
+            .set_account(&address, &Account::default().into());
+    }
+
+    write_account(
+        fixture,
+        fixture.v_list,
+        v1_v_list_data(&v_list),
+        LEGACY_VLIST_SIZE,
+    )
+    .await;
+    (pool, v_list)
+}
+
+fn assert_entries_migrated(migrated_v_list: &VList, v_list: &VList) {
+    assert_eq!(migrated_v_list.version, VLIST_VERSION);
+    assert_eq!(migrated_v_list.idx, v_list.idx);
+    assert_eq!(migrated_v_list.pool, v_list.pool);
+    for (migrated, entry) in migrated_v_list.validators[..v_list.idx]
+        .iter()
+        .zip(&v_list.validators[..v_list.idx])
+    {
+        assert_eq!(migrated.validator, entry.validator);
+        assert_eq!(migrated.score, entry.score);
+        assert_eq!(migrated.last_scored_epoch, entry.last_scored_epoch);
+        assert_eq!(migrated.components, ScoreComponents::default());
+    }
+}
+
+#[tokio::test]
+async fn test_migrate_pool_and_vlist() {
+    let fixture = STestFixture::new().await;
+    let ctx = &fixture.ctx;
+    fixture.initialize_seraph().await;
+    fixture.initialize_and_score_validators().await;
+
+    let pool: Pool = fixture.load_and_deserialize(&fixture.pool).await;
+    let v_list: VList = fixture.load_and_deserialize(&fixture.v_list).await;
+    assert_eq!(pool.version, POOL_VERSION);
+    assert_eq!(v_list.version, VLIST_VERSION);
+
+    let (pool, v_list) = write_legacy_accounts(&fixture).await;
+
+    // the legacy layout no longer deserializes
+    let transaction = Transaction::new_signed_with_payer(
+        &[fixture.set_rebalance_params_ix(0, 10_000)],
+        Some(&fixture.admin.pubkey()),
+        &[&fixture.admin],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    fixture
+        .submit_transaction_assert_error(transaction, "AccountDidNotDeserialize")
+        .await;
+
+    let transaction = Transaction::new_signed_with_payer(
+        &[fixture.migrate_vlist_ix(), fixture.migrate_pool_ix()],
+        Some(&fixture.admin.pubkey()),
+        &[&fixture.admin],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    fixture.submit_transaction_assert_success(transaction).await;
+
+    let rent = ctx.borrow_mut().banks_client.get_rent().await.unwrap();
+    for (address, size) in [(fixture.pool, Pool::SIZE), (fixture.v_list, VList::SIZE)] {
+        let account = ctx
+            .borrow_mut()
+            .banks_client
+            .get_account(address)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(account.data.len(), size);
+        assert!(account.lamports >= rent.minimum_balance(size));
+    }
+
+    // the old fields carry over, the accounts the old pool lacked are derived and created, and
+    // everything else starts as in a new pool
+    let migrated_pool: Pool = fixture.load_and_deserialize(&fixture.pool).await;
+    assert_eq!(migrated_pool.version, POOL_VERSION);
+    assert_eq!(migrated_pool.admin, pool.admin);
+    assert_eq!(migrated_pool.start_slot, pool.start_slot);
+    assert_eq!(migrated_pool.start_epoch, pool.start_epoch);
+    assert_eq!(migrated_pool.bump, pool.bump);
+    assert_eq!(migrated_pool.reserve_bump, pool.reserve_bump);
+    assert_eq!(migrated_pool.pool_mint, fixture.pool_mint);
+    assert_eq!(
+        migrated_pool.manager_fee_account,
+        fixture.manager_fee_account
+    );
+    assert_eq!(migrated_pool.guardian, pool.admin);
+    assert_eq!(migrated_pool.rebalance_params, pool.rebalance_params);
+    assert_eq!(migrated_pool.params, PoolParams::default());
+    assert!(migrated_pool.params.epochs_per_cycle > 0);
+    assert_eq!(migrated_pool.reserve_params, ReserveParams::default());
+    assert_eq!(migrated_pool.scoring_params, ScoringParams::default());
+    assert_eq!(migrated_pool.total_lamports, 0);
+
+    let pool_mint: Mint = fixture.load_and_deserialize(&fixture.pool_mint).await;
+    assert_eq!(pool_mint.mint_authority, COption::Some(fixture.pool));
+    assert_eq!(pool_mint.supply, 0);
+    let fee_account: TokenAccount = fixture
+        .load_and_deserialize(&fixture.manager_fee_account)
+        .await;
+    assert_eq!(fee_account.mint, fixture.pool_mint);
+    assert_eq!(fee_account.owner, fixture.admin.pubkey());
+
+    let migrated_v_list: VList = fixture.load_and_deserialize(&fixture.v_list).await;
+    assert_entries_migrated(&migrated_v_list, &v_list);
+
+    // migrated accounts work with the rest of the program
+    let transaction = Transaction::new_signed_with_payer(
+        &[fixture.set_rebalance_params_ix(0, 10_000)],
+        Some(&fixture.admin.pubkey()),
+        &[&fixture.admin],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    fixture.submit_transaction_assert_success(transaction).await;
+}
+
+#[tokio::test]
+async fn test_migrate_pool_without_derivable_accounts() {
+    let fixture = STestFixture::new().await;
+    let ctx = &fixture.ctx;
+    fixture.initialize_seraph().await;
+    fixture.init_and_auth_stake_accounts_to_pool().await;
+    fixture
+        .register_stake_accounts(&[fixture.stake_accounts[0].pubkey()])
+        .await;
+    let pool_mint = ctx
+        .borrow_mut()
+        .banks_client
+        .get_account(fixture.pool_mint)
+        .await
+        .unwrap()
+        .unwrap();
+    let reserve = ctx
+        .borrow_mut()
+        .banks_client
+        .get_account(fixture.reserve)
+        .await
+        .unwrap()
+        .unwrap();
+    write_legacy_accounts(&fixture).await;
+
+    // a mint at the pool mint address with tokens outstanding
+    ctx.borrow_mut()
+        .set_account(&fixture.pool_mint, &pool_mint.into());
+    let transaction = Transaction::new_signed_with_payer(
+        &[fixture.migrate_pool_ix()],
+        Some(&fixture.admin.pubkey()),
+        &[&fixture.admin],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    fixture
+        .submit_transaction_assert_error(transaction, "InvalidPoolMint")
+        .await;
+
+    // a reserve that is not a system account
+    ctx.borrow_mut()
+        .set_account(&fixture.pool_mint, &Account::default().into());
+    ctx.borrow_mut().set_account(
+        &fixture.reserve,
+        &Account {
+            owner: seraph::id(),
+            ..reserve
+        }
+        .into(),
+    );
+    let transaction = Transaction::new_signed_with_payer(
+        &[fixture.migrate_pool_ix()],
+        Some(&fixture.admin.pubkey()),
+        &[&fixture.admin],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    fixture
+        .submit_transaction_assert_error(transaction, "AccountNotSystemOwned")
+        .await;
+}
+
+#[tokio::test]
+async fn test_migrate_vlist_v1() {
+    let fixture = STestFixture::new().await;
+    let ctx = &fixture.ctx;
+    fixture.initialize_seraph().await;
+    fixture.initialize_and_score_validators().await;
+
+    let v_list: VList = fixture.load_and_deserialize(&fixture.v_list).await;
+    let mut data = v1_v_list_data(&v_list);
+    data.push(1);
+    write_account(&fixture, fixture.v_list, data, V1_VLIST_SIZE).await;
+
+    let transaction = Transaction::new_signed_with_payer(
+        &[fixture.migrate_vlist_ix()],
+        Some(&fixture.admin.pubkey()),
+        &[&fixture.admin],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    let events: Vec<AccountMigrated> = fixture.submit_transaction_get_events(transaction).await;
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].old_version, 1);
+    assert_eq!(events[0].new_version, VLIST_VERSION);
+
+    let account = ctx
+        .borrow_mut()
+        .banks_client
+        .get_account(fixture.v_list)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(account.data.len(), VList::SIZE);
+    let migrated_v_list: VList = fixture.load_and_deserialize(&fixture.v_list).await;
+    assert_entries_migrated(&migrated_v_list, &v_list);
+
+    // scoring again fills in the components
+    let transaction = Transaction::new_signed_with_payer(
+        &[fixture.calculate_score_ix(0)],
+        Some(&fixture.admin.pubkey()),
+        &[&fixture.admin],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    fixture.submit_transaction_assert_success(transaction).await;
+
+    let v_list: VList = fixture.load_and_deserialize(&fixture.v_list).await;
+    let entry = v_list.validators[..v_list.idx]
+        .iter()
+        .find(|entry| entry.validator == fixture.vote_accounts[0].pubkey())
+        .unwrap();
+    assert_eq!(entry.components.epochs_scored, 1);
+}
+
+#[tokio::test]
+async fn test_migrate_current_layout() {
+    let fixture = STestFixture::new().await;
+    let ctx = &fixture.ctx;
+    fixture.initialize_seraph().await;
+
+    // the version is read at the same offset whatever the fields after it hold
+    let data = ctx
+        .borrow_mut()
+        .banks_client
+        .get_account(fixture.pool)
+        .await
+        .unwrap()
+        .unwrap()
+        .data;
+    assert_eq!(data[Pool::VERSION_OFFSET], POOL_VERSION);
+
+    for ix in [fixture.migrate_pool_ix(), fixture.migrate_vlist_ix()] {
+        let transaction = Transaction::new_signed_with_payer(
+            &[ix],
+            Some(&fixture.admin.pubkey()),
+            &[&fixture.admin],
+            ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+        );
+        fixture
+            .submit_transaction_assert_error(transaction, "AccountAlreadyMigrated")
+            .await;
+    }
+}
+
+#[tokio::test]
+async fn test_migrate_unknown_pool_version() {
+    let fixture = STestFixture::new().await;
+    let ctx = &fixture.ctx;
+    fixture.initialize_seraph().await;
+    write_legacy_accounts(&fixture).await;
+
+    // a version no layout is known for is not mistaken for the one before versioning
+    let mut data = ctx
+        .borrow_mut()
+        .banks_client
+        .get_account(fixture.pool)
+        .await
+        .unwrap()
+        .unwrap()
+        .data;
+    data[Pool::VERSION_OFFSET] = POOL_VERSION + 1;
+    write_account(&fixture, fixture.pool, data, PoolV0::SIZE).await;
+
+    let transaction = Transaction::new_signed_with_payer(
+        &[fixture.migrate_pool_ix()],
+        Some(&fixture.admin.pubkey()),
+        &[&fixture.admin],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    fixture
+        .submit_transaction_assert_error(transaction, "AccountDidNotDeserialize")
+        .await;
+}
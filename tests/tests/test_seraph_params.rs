@@ -0,0 +1,156 @@
+#![allow(clippy::await_holding_refcell_ref)]
+use seraph::{Pool, PoolParams, VList, MAX_LOOKBACK_EPOCHS, MAX_VALIDATORS_IN_LIST};
+use solana_program_test::*;
+use solana_sdk::{signer::Signer, transaction::Transaction};
+use tests::seraph_fixtures::STestFixture;
+
+#[tokio::test]
+async fn test_update_params_bounds() {
+    let fixture = STestFixture::new().await;
+    let ctx = &fixture.ctx;
+    fixture.initialize_seraph().await;
+
+    let pool: Pool = fixture.load_and_deserialize(&fixture.pool).await;
+    assert_eq!(pool.params, PoolParams::default());
+
+    let defaults = PoolParams::default();
+    let invalid_params = [
+        PoolParams {
+            epochs_per_cycle: 0,
+            ..defaults
+        },
+        PoolParams {
+            lookback_epochs: 0,
+            ..defaults
+        },
+        PoolParams {
+            lookback_epochs: MAX_LOOKBACK_EPOCHS + 1,
+            ..defaults
+        },
+        PoolParams {
+            max_validators: 0,
+            ..defaults
+        },
+        PoolParams {
+            max_validators: MAX_VALIDATORS_IN_LIST as u16 + 1,
+            ..defaults
+        },
+        PoolParams {
+            percentile: 0,
+            ..defaults
+        },
+        PoolParams {
+            percentile: 101,
+            ..defaults
+        },
+        PoolParams {
+            min_epochs: 0,
+            ..defaults
+        },
+        PoolParams {
+            min_epochs: defaults.lookback_epochs + 1,
+            ..defaults
+        },
+    ];
+    for params in invalid_params {
+        let transaction = Transaction::new_signed_with_payer(
+            &[fixture.update_params_ix(params)],
+            Some(&fixture.admin.pubkey()),
+            &[&fixture.admin],
+            ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+        );
+        fixture
+            .submit_transaction_assert_error(transaction, "InvalidPoolParams")
+            .await;
+    }
+
+    let params = PoolParams {
+        epochs_per_cycle: 10,
+        lookback_epochs: MAX_LOOKBACK_EPOCHS,
+        max_validators: 50,
+        percentile: 25,
+        min_epochs: 3,
+    };
+    let transaction = Transaction::new_signed_with_payer(
+        &[fixture.update_params_ix(params)],
+        Some(&fixture.admin.pubkey()),
+        &[&fixture.admin],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    fixture.submit_transaction_assert_success(transaction).await;
+
+    let pool: Pool = fixture.load_and_deserialize(&fixture.pool).await;
+    assert_eq!(pool.params, params);
+}
+
+#[tokio::test]
+async fn test_params_limit_scoring() {
+    let fixture = STestFixture::new().await;
+    let ctx = &fixture.ctx;
+    fixture.initialize_seraph().await;
+    fixture.initialize_and_score_validators().await;
+
+    let v_list: VList = fixture.load_and_deserialize(&fixture.v_list).await;
+    let top_validators: Vec<_> = v_list.validators[0..5]
+        .iter()
+        .map(|v| v.validator)
+        .collect();
+
+    // shrinking the list keeps the top-scored validators
+    let transaction = Transaction::new_signed_with_payer(
+        &[fixture.update_params_ix(PoolParams {
+            max_validators: 5,
+            ..PoolParams::default()
+        })],
+        Some(&fixture.admin.pubkey()),
+        &[&fixture.admin],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    fixture.submit_transaction_assert_success(transaction).await;
+
+    let v_list: VList = fixture.load_and_deserialize(&fixture.v_list).await;
+    assert_eq!(v_list.idx, 5);
+    let listed: Vec<_> = v_list.validators[0..5]
+        .iter()
+        .map(|v| v.validator)
+        .collect();
+    assert_eq!(listed, top_validators);
+
+    // validators without enough history in the lookback are not scored
+    let unlisted = (0..fixture.vote_accounts.len())
+        .find(|i| !top_validators.contains(&fixture.vote_accounts[*i].pubkey()))
+        .unwrap();
+    let transaction = Transaction::new_signed_with_payer(
+        &[
+            fixture.update_params_ix(PoolParams {
+                min_epochs: 2,
+                ..PoolParams::default()
+            }),
+            fixture.calculate_score_ix(unlisted),
+        ],
+        Some(&fixture.admin.pubkey()),
+        &[&fixture.admin],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    fixture.submit_transaction_assert_success(transaction).await;
+
+    let v_list: VList = fixture.load_and_deserialize(&fixture.v_list).await;
+    assert_eq!(v_list.idx, 5);
+
+    let transaction = Transaction::new_signed_with_payer(
+        &[
+            fixture.update_params_ix(PoolParams::default()),
+            fixture.calculate_score_ix(unlisted),
+        ],
+        Some(&fixture.admin.pubkey()),
+        &[&fixture.admin],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    fixture.submit_transaction_assert_success(transaction).await;
+
+    let v_list: VList = fixture.load_and_deserialize(&fixture.v_list).await;
+    assert_eq!(v_list.idx, 6);
+    assert!(v_list
+        .get_score(&fixture.vote_accounts[unlisted].pubkey())
+        .is_some());
+}
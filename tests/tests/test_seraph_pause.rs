@@ -0,0 +1,508 @@
+#![allow(clippy::await_holding_refcell_ref)]
+use anchor_lang::{solana_program::system_program, InstructionData, ToAccountMetas};
+use anchor_spl::token::TokenAccount;
+use seraph::{Pool, PoolParams, ReserveParams, ScoringParams, StakeRegistry, StakeStatus, VList};
+use solana_program_test::*;
+use solana_sdk::{
+    instruction::Instruction, pubkey::Pubkey, signature::Keypair, signer::Signer,
+    transaction::Transaction,
+};
+use tests::seraph_fixtures::{STestFixture, SplStakePoolAccounts};
+
+async fn pause(fixture: &STestFixture) {
+    let transaction = Transaction::new_signed_with_payer(
+        &[fixture.pause_ix(fixture.admin.pubkey())],
+        Some(&fixture.admin.pubkey()),
+        &[&fixture.admin],
+        fixture
+            .ctx
+            .borrow_mut()
+            .get_new_latest_blockhash()
+            .await
+            .unwrap(),
+    );
+    fixture.submit_transaction_assert_success(transaction).await;
+}
+
+async fn assert_paused(fixture: &STestFixture, instruction: Instruction, signers: &[&Keypair]) {
+    let transaction = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&signers[0].pubkey()),
+        signers,
+        fixture
+            .ctx
+            .borrow_mut()
+            .get_new_latest_blockhash()
+            .await
+            .unwrap(),
+    );
+    fixture
+        .submit_transaction_assert_error(transaction, "PoolPaused")
+        .await;
+}
+
+#[tokio::test]
+async fn test_pause_authority() {
+    let fixture = STestFixture::new().await;
+    let ctx = &fixture.ctx;
+    fixture.initialize_seraph().await;
+
+    let pool: Pool = fixture.load_and_deserialize(&fixture.pool).await;
+    assert_eq!(pool.guardian, fixture.admin.pubkey());
+    assert!(!pool.paused);
+
+    let guardian = Keypair::new();
+    let transaction = Transaction::new_signed_with_payer(
+        &[fixture.pause_ix(guardian.pubkey())],
+        Some(&fixture.keypair.pubkey()),
+        &[&fixture.keypair, &guardian],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    fixture
+        .submit_transaction_assert_error(transaction, "ConstraintHasOne")
+        .await;
+
+    // once handed over, only the new guardian can pause
+    let transaction = Transaction::new_signed_with_payer(
+        &[fixture.set_guardian_ix(guardian.pubkey())],
+        Some(&fixture.admin.pubkey()),
+        &[&fixture.admin],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    fixture.submit_transaction_assert_success(transaction).await;
+
+    let transaction = Transaction::new_signed_with_payer(
+        &[fixture.pause_ix(fixture.admin.pubkey())],
+        Some(&fixture.admin.pubkey()),
+        &[&fixture.admin],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    fixture
+        .submit_transaction_assert_error(transaction, "ConstraintHasOne")
+        .await;
+
+    let transaction = Transaction::new_signed_with_payer(
+        &[fixture.pause_ix(guardian.pubkey())],
+        Some(&fixture.keypair.pubkey()),
+        &[&fixture.keypair, &guardian],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    fixture.submit_transaction_assert_success(transaction).await;
+
+    let pool: Pool = fixture.load_and_deserialize(&fixture.pool).await;
+    assert!(pool.paused);
+
+    let transaction = Transaction::new_signed_with_payer(
+        &[fixture.set_rebalance_params_ix(0, 10_000)],
+        Some(&fixture.admin.pubkey()),
+        &[&fixture.admin],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    fixture
+        .submit_transaction_assert_error(transaction, "PoolPaused")
+        .await;
+
+    let transaction = Transaction::new_signed_with_payer(
+        &[
+            fixture.unpause_ix(guardian.pubkey()),
+            fixture.set_rebalance_params_ix(0, 10_000),
+        ],
+        Some(&fixture.admin.pubkey()),
+        &[&fixture.admin, &guardian],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    fixture.submit_transaction_assert_success(transaction).await;
+
+    let pool: Pool = fixture.load_and_deserialize(&fixture.pool).await;
+    assert!(!pool.paused);
+}
+
+#[tokio::test]
+async fn test_paused_initialization() {
+    let fixture = STestFixture::new().await;
+    fixture.initialize_seraph().await;
+    pause(&fixture).await;
+
+    assert_paused(
+        &fixture,
+        Instruction {
+            program_id: seraph::id(),
+            accounts: seraph::accounts::InitializeStakeRegistry {
+                admin: fixture.admin.pubkey(),
+                pool: fixture.pool,
+                stake_registry: fixture.stake_registry,
+                system_program: system_program::id(),
+            }
+            .to_account_metas(None),
+            data: seraph::instruction::InitializeStakeRegistry {}.data(),
+        },
+        &[&fixture.admin],
+    )
+    .await;
+
+    assert_paused(
+        &fixture,
+        Instruction {
+            program_id: seraph::id(),
+            accounts: seraph::accounts::InitializePoolHistory {
+                admin: fixture.admin.pubkey(),
+                pool: fixture.pool,
+                pool_history: fixture.pool_history,
+                system_program: system_program::id(),
+            }
+            .to_account_metas(None),
+            data: seraph::instruction::InitializePoolHistory {}.data(),
+        },
+        &[&fixture.admin],
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_paused_instructions() {
+    let fixture = STestFixture::new().await;
+    let ctx = &fixture.ctx;
+    fixture.initialize_seraph().await;
+    fixture.initialize_and_score_validators().await;
+    fixture.init_and_auth_stake_accounts_to_pool().await;
+    fixture.initialize_pool_history().await;
+
+    let v_list: VList = fixture.load_and_deserialize(&fixture.v_list).await;
+    let validator_vote = v_list.validators[1].validator;
+    let new_validator_vote = v_list.validators[0].validator;
+    let stake_accounts: Vec<_> = fixture.stake_accounts[0..3]
+        .iter()
+        .map(|stake_account| stake_account.pubkey())
+        .collect();
+    fixture.register_stake_accounts(&stake_accounts[0..2]).await;
+
+    let transaction = Transaction::new_signed_with_payer(
+        &[
+            fixture.set_rebalance_params_ix(0, 10_000),
+            fixture.delegate_stake_ix(stake_accounts[0], validator_vote),
+        ],
+        Some(&fixture.admin.pubkey()),
+        &[&fixture.admin],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    fixture.submit_transaction_assert_success(transaction).await;
+
+    pause(&fixture).await;
+
+    let split_stake_account = Keypair::new();
+    // rejected on the pool, before the stake pool accounts are looked at
+    let stake_pool = SplStakePoolAccounts {
+        stake_pool: Pubkey::new_unique(),
+        validator_list: Pubkey::new_unique(),
+        reserve_stake: Pubkey::new_unique(),
+        withdraw_authority: Pubkey::new_unique(),
+    };
+    let admin: &[&Keypair] = &[&fixture.admin];
+    let split_signers: &[&Keypair] = &[&fixture.admin, &split_stake_account];
+    let cranker: &[&Keypair] = &[&fixture.keypair];
+    let staker: &[&Keypair] = &[&fixture.stakers[2]];
+    let gated = [
+        (fixture.calculate_score_ix(0), admin),
+        (fixture.add_stake_account_ix(stake_accounts[2]), admin),
+        (
+            fixture.delegate_stake_ix(stake_accounts[1], validator_vote),
+            admin,
+        ),
+        (
+            fixture.split_stake_ix(
+                stake_accounts[1],
+                split_stake_account.pubkey(),
+                1_000_000_000,
+            ),
+            split_signers,
+        ),
+        (
+            fixture.merge_stake_ix(stake_accounts[0], stake_accounts[1]),
+            admin,
+        ),
+        (
+            fixture.redelegate_stake_ix(
+                stake_accounts[0],
+                validator_vote,
+                new_validator_vote,
+                1_000_000_000,
+                0,
+            ),
+            admin,
+        ),
+        (
+            Instruction {
+                program_id: seraph::id(),
+                data: seraph::instruction::SetFees {
+                    management_fee_bps: 100,
+                    performance_fee_bps: 100,
+                }
+                .data(),
+                accounts: seraph::accounts::SetFees {
+                    admin: fixture.admin.pubkey(),
+                    pool: fixture.pool,
+                }
+                .to_account_metas(None),
+            },
+            admin,
+        ),
+        (fixture.set_rebalance_params_ix(0, 10_000), admin),
+        (fixture.update_params_ix(PoolParams::default()), admin),
+        (
+            fixture.update_pool_history_ix(&stake_accounts[0..2]),
+            cranker,
+        ),
+        (
+            fixture.register_managed_stake_ix(fixture.stakers[2].pubkey(), stake_accounts[2]),
+            staker,
+        ),
+        (
+            fixture.delegate_managed_stake_ix(stake_accounts[2], validator_vote),
+            admin,
+        ),
+        (
+            fixture.set_reserve_params_ix(ReserveParams::default()),
+            admin,
+        ),
+        (fixture.set_stake_pool_ix(stake_pool.stake_pool), admin),
+        (
+            fixture.add_stake_pool_validator_ix(&stake_pool, validator_vote),
+            admin,
+        ),
+        (
+            fixture.increase_stake_pool_validator_stake_ix(
+                &stake_pool,
+                validator_vote,
+                1_000_000_000,
+                0,
+            ),
+            admin,
+        ),
+        (
+            fixture.set_scoring_params_ix(ScoringParams::default()),
+            admin,
+        ),
+    ];
+    for (instruction, signers) in gated {
+        assert_paused(&fixture, instruction, signers).await;
+    }
+}
+
+#[tokio::test]
+async fn test_paused_deactivation_and_withdrawal() {
+    let fixture = STestFixture::new().await;
+    let ctx = &fixture.ctx;
+    fixture.initialize_seraph().await;
+    fixture.initialize_and_score_validators().await;
+    fixture.init_and_auth_stake_accounts_to_pool().await;
+
+    let v_list: VList = fixture.load_and_deserialize(&fixture.v_list).await;
+    let validator_vote = v_list.validators[1].validator;
+    let new_validator_vote = v_list.validators[0].validator;
+    let stake_accounts: Vec<_> = fixture.stake_accounts[0..2]
+        .iter()
+        .map(|stake_account| stake_account.pubkey())
+        .collect();
+    fixture.register_stake_accounts(&stake_accounts).await;
+
+    // a redelegation in flight when the pool is paused
+    let transaction = Transaction::new_signed_with_payer(
+        &[
+            fixture.set_rebalance_params_ix(0, 10_000),
+            fixture.delegate_stake_ix(stake_accounts[0], validator_vote),
+            fixture.delegate_stake_ix(stake_accounts[1], validator_vote),
+            fixture.redelegate_stake_ix(
+                stake_accounts[1],
+                validator_vote,
+                new_validator_vote,
+                1_000_000_000,
+                0,
+            ),
+        ],
+        Some(&fixture.admin.pubkey()),
+        &[&fixture.admin],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    fixture.submit_transaction_assert_success(transaction).await;
+
+    pause(&fixture).await;
+
+    let redelegated_stake_account = Pool::transient_stake_pubkey(fixture.pool, validator_vote, 0);
+    assert_paused(
+        &fixture,
+        fixture.finalize_transient_stake_ix(redelegated_stake_account, new_validator_vote),
+        &[&fixture.keypair],
+    )
+    .await;
+
+    // stake can still be unwound into the reserve
+    let decreased_stake_account = Pool::transient_stake_pubkey(fixture.pool, validator_vote, 1);
+    let transaction = Transaction::new_signed_with_payer(
+        &[
+            fixture.decrease_stake_ix(stake_accounts[0], validator_vote, 1_000_000_000, 1),
+            fixture.finalize_transient_stake_ix(decreased_stake_account, validator_vote),
+            fixture.deactivate_stake_ix(stake_accounts[0]),
+            fixture.withdraw_stake_ix(stake_accounts[0]),
+        ],
+        Some(&fixture.admin.pubkey()),
+        &[&fixture.admin, &fixture.keypair],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    fixture.submit_transaction_assert_success(transaction).await;
+
+    let stake_registry: StakeRegistry = fixture.load_and_deserialize(&fixture.stake_registry).await;
+    assert!(stake_registry.get(&stake_accounts[0]).is_none());
+    assert!(stake_registry.get(&decreased_stake_account).is_none());
+    assert!(stake_registry.get(&redelegated_stake_account).is_some());
+}
+
+#[tokio::test]
+async fn test_paused_withdrawals() {
+    let fixture = STestFixture::new().await;
+    let ctx = &fixture.ctx;
+    fixture.initialize_seraph().await;
+    fixture.initialize_and_score_validators().await;
+    fixture.init_and_auth_stake_accounts_to_pool().await;
+
+    let v_list: VList = fixture.load_and_deserialize(&fixture.v_list).await;
+    let validator_vote = v_list.validators[0].validator;
+    let stake_accounts: Vec<_> = fixture.stake_accounts[0..2]
+        .iter()
+        .map(|stake_account| stake_account.pubkey())
+        .collect();
+    fixture.register_stake_accounts(&stake_accounts).await;
+
+    let transaction = Transaction::new_signed_with_payer(
+        &[
+            fixture.delegate_stake_ix(stake_accounts[0], validator_vote),
+            fixture.delegate_stake_ix(stake_accounts[1], validator_vote),
+        ],
+        Some(&fixture.admin.pubkey()),
+        &[&fixture.admin],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    fixture.submit_transaction_assert_success(transaction).await;
+
+    pause(&fixture).await;
+
+    // depositors can still queue withdrawals
+    let owner = &fixture.keypair;
+    let pool_token_account = fixture.depositor_token_account.pubkey();
+    let token_account: TokenAccount = fixture.load_and_deserialize(&pool_token_account).await;
+    let transaction = Transaction::new_signed_with_payer(
+        &[fixture.request_withdrawal_ix(
+            owner.pubkey(),
+            pool_token_account,
+            0,
+            token_account.amount / 4,
+        )],
+        Some(&owner.pubkey()),
+        &[owner],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    fixture.submit_transaction_assert_success(transaction).await;
+
+    // MEV tips are still swept into the reserve
+    fixture.add_lamports(&stake_accounts[0], 250_000_000).await;
+    let transaction = Transaction::new_signed_with_payer(
+        &[fixture.harvest_ix(stake_accounts[0])],
+        Some(&fixture.keypair.pubkey()),
+        &[&fixture.keypair],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    fixture.submit_transaction_assert_success(transaction).await;
+
+    // the unstake queue keeps splitting off stake for tickets, which stay claimable
+    let transaction = Transaction::new_signed_with_payer(
+        &[fixture.process_unstake_queue_ix(&stake_accounts, Some(validator_vote), 0)],
+        Some(&fixture.keypair.pubkey()),
+        &[&fixture.keypair],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    fixture.submit_transaction_assert_success(transaction).await;
+
+    fixture.advance_num_epochs(1).await;
+
+    let transient_stake_account = Pool::transient_stake_pubkey(fixture.pool, validator_vote, 0);
+    let stake_registry: StakeRegistry = fixture.load_and_deserialize(&fixture.stake_registry).await;
+    assert_eq!(
+        stake_registry
+            .get(&transient_stake_account)
+            .map(|entry| entry.status),
+        Some(StakeStatus::Deactivating)
+    );
+    let transaction = Transaction::new_signed_with_payer(
+        &[
+            fixture.finalize_transient_stake_ix(transient_stake_account, validator_vote),
+            fixture.claim_ticket_ix(owner.pubkey(), 0),
+        ],
+        Some(&owner.pubkey()),
+        &[owner],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    fixture.submit_transaction_assert_success(transaction).await;
+
+    assert!(ctx
+        .borrow_mut()
+        .banks_client
+        .get_account(fixture.withdrawal_ticket(0))
+        .await
+        .unwrap()
+        .is_none());
+
+    // and, once the new epoch's balance is booked, unstake instantly from what the reserve keeps
+    let pool_tokens = 1_000_000_000;
+    let transaction = Transaction::new_signed_with_payer(
+        &[
+            fixture.update_pool_balance_ix(&stake_accounts),
+            fixture.instant_unstake_ix(owner.pubkey(), pool_token_account, pool_tokens, 0),
+        ],
+        Some(&owner.pubkey()),
+        &[owner, &fixture.admin],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    fixture.submit_transaction_assert_success(transaction).await;
+
+    let new_token_account: TokenAccount = fixture.load_and_deserialize(&pool_token_account).await;
+    assert_eq!(
+        new_token_account.amount,
+        token_account.amount - token_account.amount / 4 - pool_tokens
+    );
+}
+
+#[tokio::test]
+async fn test_paused_managed_stake_revoke() {
+    let fixture = STestFixture::new().await;
+    let ctx = &fixture.ctx;
+    fixture.initialize_seraph().await;
+    fixture.init_stake_account_to_staker(0).await;
+
+    let owner = &fixture.stakers[0];
+    let stake_account = fixture.stake_accounts[0].pubkey();
+    let transaction = Transaction::new_signed_with_payer(
+        &[fixture.register_managed_stake_ix(owner.pubkey(), stake_account)],
+        Some(&owner.pubkey()),
+        &[owner],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    fixture.submit_transaction_assert_success(transaction).await;
+
+    pause(&fixture).await;
+
+    // owners can always take their stake back
+    let transaction = Transaction::new_signed_with_payer(
+        &[fixture.revoke_managed_stake_ix(owner.pubkey(), stake_account)],
+        Some(&owner.pubkey()),
+        &[owner],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    fixture.submit_transaction_assert_success(transaction).await;
+
+    assert!(ctx
+        .borrow_mut()
+        .banks_client
+        .get_account(fixture.managed_stake(stake_account))
+        .await
+        .unwrap()
+        .is_none());
+}
@@ -0,0 +1,116 @@
+#![allow(clippy::await_holding_refcell_ref)]
+use anchor_lang::AccountDeserialize;
+use anchor_spl::stake::StakeAccount;
+use seraph::{Pool, PoolHistory, EXCHANGE_RATE_PRECISION};
+use solana_program_test::*;
+use solana_sdk::{signer::Signer, transaction::Transaction};
+use tests::seraph_fixtures::STestFixture;
+
+#[tokio::test]
+async fn test_update_pool_history() -> Result<(), Box<dyn std::error::Error>> {
+    let fixture = STestFixture::new().await;
+    let ctx = &fixture.ctx;
+    fixture.initialize_seraph().await;
+    fixture.initialize_pool_history().await;
+    fixture.init_and_auth_stake_accounts_to_pool().await;
+
+    let stake_accounts: Vec<_> = fixture.stake_accounts[0..2]
+        .iter()
+        .map(|stake_account| stake_account.pubkey())
+        .collect();
+    fixture.register_stake_accounts(&stake_accounts).await;
+
+    let pool_history: PoolHistory = fixture.load_and_deserialize(&fixture.pool_history).await;
+    assert_eq!(pool_history.pool, fixture.pool);
+    assert!(pool_history.history.is_empty());
+    assert!(pool_history.history.last().is_none());
+
+    for (i, stake_account) in stake_accounts.iter().enumerate() {
+        let transaction = Transaction::new_signed_with_payer(
+            &[fixture.delegate_stake_ix(*stake_account, fixture.vote_accounts[i].pubkey())],
+            Some(&fixture.admin.pubkey()),
+            &[&fixture.admin],
+            ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+        );
+        fixture.submit_transaction_assert_success(transaction).await;
+    }
+
+    fixture.advance_num_epochs(1).await;
+
+    // history cannot be recorded before the pool balance is updated
+    let transaction = Transaction::new_signed_with_payer(
+        &[fixture.update_pool_history_ix(&stake_accounts)],
+        Some(&fixture.keypair.pubkey()),
+        &[&fixture.keypair],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    fixture
+        .submit_transaction_assert_error(transaction, "PoolBalanceStale")
+        .await;
+
+    // anyone can crank the history once the balance is updated
+    let transaction = Transaction::new_signed_with_payer(
+        &[
+            fixture.update_pool_balance_ix(&stake_accounts),
+            fixture.update_pool_history_ix(&stake_accounts),
+        ],
+        Some(&fixture.keypair.pubkey()),
+        &[&fixture.keypair, &fixture.admin],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    fixture.submit_transaction_assert_success(transaction).await;
+
+    let pool: Pool = fixture.load_and_deserialize(&fixture.pool).await;
+    let pool_history: PoolHistory = fixture.load_and_deserialize(&fixture.pool_history).await;
+    let entry = *pool_history.history.last().unwrap();
+
+    let mut delegated_stake = 0;
+    for stake_account in stake_accounts.iter() {
+        let account = ctx
+            .borrow_mut()
+            .banks_client
+            .get_account(*stake_account)
+            .await?
+            .unwrap();
+        let stake_account = StakeAccount::try_deserialize(&mut account.data.as_slice())?;
+        delegated_stake += stake_account.delegation().unwrap().stake;
+    }
+
+    assert_eq!(pool_history.history.idx, 0);
+    assert_eq!(entry.epoch, 1);
+    assert_eq!(entry.total_lamports, pool.total_lamports);
+    assert_eq!(
+        entry.active_stake_lamports + entry.activating_stake_lamports,
+        delegated_stake
+    );
+    assert_eq!(entry.rewards_lamports, 0);
+    assert_eq!(entry.fee_lamports, 0);
+    // deposits were minted 1:1 into the empty pool
+    assert_eq!(entry.pool_token_supply, pool.total_lamports);
+    assert_eq!(entry.exchange_rate, EXCHANGE_RATE_PRECISION);
+
+    // rewards in the following epoch are recorded in a new entry
+    let rewards = 1_000_000_000;
+    fixture.advance_num_epochs(1).await;
+    fixture.add_lamports(&stake_accounts[1], rewards).await;
+
+    let transaction = Transaction::new_signed_with_payer(
+        &[
+            fixture.update_pool_balance_ix(&stake_accounts),
+            fixture.update_pool_history_ix(&stake_accounts),
+        ],
+        Some(&fixture.keypair.pubkey()),
+        &[&fixture.keypair, &fixture.admin],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    fixture.submit_transaction_assert_success(transaction).await;
+
+    let pool_history: PoolHistory = fixture.load_and_deserialize(&fixture.pool_history).await;
+    let latest = pool_history.history.last().unwrap();
+    assert_eq!(pool_history.history.idx, 1);
+    assert_eq!(latest.epoch, 2);
+    assert_eq!(latest.rewards_lamports, rewards);
+    assert_eq!(latest.total_lamports, entry.total_lamports + rewards);
+
+    Ok(())
+}
@@ -0,0 +1,214 @@
+#![allow(clippy::await_holding_refcell_ref)]
+use seraph::{
+    events::RebalanceRejected, Pool, RebalanceParams, RebalanceRejection, VList,
+    DEFAULT_EPOCHS_PER_CYCLE, DEFAULT_MAX_STAKE_MOVED_BPS,
+};
+use solana_program_test::*;
+use solana_sdk::{signer::Signer, transaction::Transaction};
+use tests::seraph_fixtures::STestFixture;
+
+#[tokio::test]
+async fn test_rebalance_limits() {
+    let fixture = STestFixture::new().await;
+    let ctx = &fixture.ctx;
+    fixture.initialize_seraph().await;
+    fixture.initialize_and_score_validators().await;
+    fixture.init_and_auth_stake_accounts_to_pool().await;
+
+    let v_list: VList = fixture.load_and_deserialize(&fixture.v_list).await;
+    let best_validator_vote = v_list.validators[0].validator;
+    let next_validator_vote = v_list.validators[1].validator;
+    let worst_validator_vote = v_list.validators[v_list.idx - 1].validator;
+    assert!(v_list.validators[0].score > v_list.validators[v_list.idx - 1].score);
+
+    let stake_account = fixture.stake_accounts[0].pubkey();
+    fixture.register_stake_accounts(&[stake_account]).await;
+
+    let transaction = Transaction::new_signed_with_payer(
+        &[fixture.delegate_stake_ix(stake_account, best_validator_vote)],
+        Some(&fixture.admin.pubkey()),
+        &[&fixture.admin],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    fixture.submit_transaction_assert_success(transaction).await;
+
+    let pool: Pool = fixture.load_and_deserialize(&fixture.pool).await;
+    let max_stake_moved = pool
+        .rebalance_params
+        .max_stake_moved(pool.total_lamports)
+        .unwrap();
+    assert_eq!(
+        pool.rebalance_params.max_stake_moved_bps,
+        DEFAULT_MAX_STAKE_MOVED_BPS
+    );
+    assert!(v_list.is_eligible(&next_validator_vote, &pool.params));
+    assert!(!v_list.is_eligible(&worst_validator_vote, &pool.params));
+
+    // stake never moves to a listed validator outside the eligible percentile
+    let transaction = Transaction::new_signed_with_payer(
+        &[fixture.redelegate_stake_ix(
+            stake_account,
+            best_validator_vote,
+            worst_validator_vote,
+            max_stake_moved / 2,
+            0,
+        )],
+        Some(&fixture.admin.pubkey()),
+        &[&fixture.admin],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    fixture
+        .submit_transaction_assert_error(transaction, "ValidatorNotInList")
+        .await;
+
+    // moving stake to a lower-scored validator is never an improvement
+    let transaction = Transaction::new_signed_with_payer(
+        &[fixture.redelegate_stake_ix(
+            stake_account,
+            best_validator_vote,
+            next_validator_vote,
+            max_stake_moved / 2,
+            0,
+        )],
+        Some(&fixture.admin.pubkey()),
+        &[&fixture.admin],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    let events: Vec<RebalanceRejected> = fixture
+        .submit_transaction_assert_error_get_events(transaction, "InsufficientScoreImprovement")
+        .await;
+    assert_eq!(events.len(), 1);
+    assert_eq!(
+        events[0].reason,
+        RebalanceRejection::InsufficientScoreImprovement
+    );
+    assert_eq!(events[0].stake_account, stake_account);
+    assert_eq!(events[0].from_validator, best_validator_vote);
+    assert_eq!(events[0].to_validator, next_validator_vote);
+    assert_eq!(events[0].from_score, v_list.validators[0].score);
+    assert_eq!(events[0].to_score, v_list.validators[1].score);
+    assert_eq!(
+        events[0].min_score_improvement_bps,
+        pool.rebalance_params.min_score_improvement_bps
+    );
+
+    // nor is staying on the same validator unless the hysteresis is disabled
+    let transaction = Transaction::new_signed_with_payer(
+        &[fixture.redelegate_stake_ix(
+            stake_account,
+            best_validator_vote,
+            best_validator_vote,
+            max_stake_moved / 2,
+            0,
+        )],
+        Some(&fixture.admin.pubkey()),
+        &[&fixture.admin],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    fixture
+        .submit_transaction_assert_error(transaction, "InsufficientScoreImprovement")
+        .await;
+
+    let transaction = Transaction::new_signed_with_payer(
+        &[fixture.set_rebalance_params_ix(0, DEFAULT_MAX_STAKE_MOVED_BPS)],
+        Some(&fixture.admin.pubkey()),
+        &[&fixture.admin],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    fixture.submit_transaction_assert_success(transaction).await;
+
+    let transaction = Transaction::new_signed_with_payer(
+        &[fixture.redelegate_stake_ix(
+            stake_account,
+            best_validator_vote,
+            best_validator_vote,
+            max_stake_moved / 2,
+            0,
+        )],
+        Some(&fixture.admin.pubkey()),
+        &[&fixture.admin],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    fixture.submit_transaction_assert_success(transaction).await;
+
+    let pool: Pool = fixture.load_and_deserialize(&fixture.pool).await;
+    assert_eq!(pool.stake_moved_in_cycle, max_stake_moved / 2);
+
+    // the rest of the cycle's budget is not enough for the same move again
+    let transaction = Transaction::new_signed_with_payer(
+        &[fixture.redelegate_stake_ix(
+            stake_account,
+            best_validator_vote,
+            best_validator_vote,
+            max_stake_moved / 2 + 1,
+            1,
+        )],
+        Some(&fixture.admin.pubkey()),
+        &[&fixture.admin],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    let events: Vec<RebalanceRejected> = fixture
+        .submit_transaction_assert_error_get_events(transaction, "RebalanceBudgetExceeded")
+        .await;
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].reason, RebalanceRejection::BudgetExceeded);
+    assert_eq!(events[0].lamports, max_stake_moved / 2 + 1);
+    assert_eq!(events[0].stake_moved_in_cycle, max_stake_moved / 2);
+    assert_eq!(events[0].max_stake_moved, max_stake_moved);
+
+    // the budget resets with the next cycle
+    fixture
+        .advance_num_epochs(DEFAULT_EPOCHS_PER_CYCLE as u64)
+        .await;
+    let transaction = Transaction::new_signed_with_payer(
+        &[fixture.redelegate_stake_ix(
+            stake_account,
+            best_validator_vote,
+            best_validator_vote,
+            max_stake_moved / 2 + 1,
+            1,
+        )],
+        Some(&fixture.admin.pubkey()),
+        &[&fixture.admin],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    fixture.submit_transaction_assert_success(transaction).await;
+
+    let pool: Pool = fixture.load_and_deserialize(&fixture.pool).await;
+    assert_eq!(pool.cycle_start_epoch % DEFAULT_EPOCHS_PER_CYCLE as u64, 0);
+    assert_eq!(pool.stake_moved_in_cycle, max_stake_moved / 2 + 1);
+}
+
+#[tokio::test]
+async fn test_invalid_rebalance_params() {
+    let fixture = STestFixture::new().await;
+    let ctx = &fixture.ctx;
+    fixture.initialize_seraph().await;
+
+    let transaction = Transaction::new_signed_with_payer(
+        &[fixture.set_rebalance_params_ix(0, 10_001)],
+        Some(&fixture.admin.pubkey()),
+        &[&fixture.admin],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    fixture
+        .submit_transaction_assert_error(transaction, "InvalidRebalanceParams")
+        .await;
+
+    let transaction = Transaction::new_signed_with_payer(
+        &[fixture.set_rebalance_params_ix(250, 500)],
+        Some(&fixture.admin.pubkey()),
+        &[&fixture.admin],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    fixture.submit_transaction_assert_success(transaction).await;
+
+    let pool: Pool = fixture.load_and_deserialize(&fixture.pool).await;
+    assert_eq!(
+        pool.rebalance_params,
+        RebalanceParams {
+            min_score_improvement_bps: 250,
+            max_stake_moved_bps: 500,
+        }
+    );
+}
@@ -0,0 +1,511 @@
+#![allow(clippy::await_holding_refcell_ref)]
+use anchor_lang::solana_program::native_token::LAMPORTS_PER_SOL;
+use seraph::{
+    events::{ValidatorRemoved, ValidatorScoreUpdated},
+    BonusCurve, MissingEpochPolicy, Pool, PoolParams, RemovalReason, ScoreComponents,
+    ScoringParams, VList,
+};
+use solana_program_test::*;
+use solana_sdk::{signer::Signer, transaction::Transaction};
+use tests::seraph_fixtures::STestFixture;
+use validator_history::ValidatorHistoryEntry;
+
+const STAKE_THRESHOLD: u64 = 100_000 * LAMPORTS_PER_SOL;
+
+// One epoch of perfect voting at no commission, with the given stake and rank
+fn history_entry(epoch: u16, activated_stake_lamports: u64, rank: u32) -> ValidatorHistoryEntry {
+    ValidatorHistoryEntry {
+        epoch,
+        epoch_credits: 1_000,
+        commission: 0,
+        activated_stake_lamports,
+        rank,
+        ..ValidatorHistoryEntry::default()
+    }
+}
+
+async fn score(fixture: &STestFixture, validator: usize) -> u32 {
+    let ctx = &fixture.ctx;
+    let transaction = Transaction::new_signed_with_payer(
+        &[fixture.calculate_score_ix(validator)],
+        Some(&fixture.admin.pubkey()),
+        &[&fixture.admin],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    fixture.submit_transaction_assert_success(transaction).await;
+
+    let v_list: VList = fixture.load_and_deserialize(&fixture.v_list).await;
+    v_list
+        .get_score(&fixture.vote_accounts[validator].pubkey())
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_decentralization_bonus() {
+    let fixture = STestFixture::new().await;
+    let ctx = &fixture.ctx;
+    fixture.initialize_seraph().await;
+    fixture.initialize_and_score_validators().await;
+
+    let pool: Pool = fixture.load_and_deserialize(&fixture.pool).await;
+    assert_eq!(pool.scoring_params, ScoringParams::default());
+
+    // a large top ranked validator, a small low ranked one, and one without recorded stake
+    fixture
+        .set_validator_history(0, &[history_entry(0, 10 * STAKE_THRESHOLD, 0)])
+        .await;
+    fixture
+        .set_validator_history(1, &[history_entry(0, STAKE_THRESHOLD / 2, 12)])
+        .await;
+    fixture
+        .set_validator_history(
+            2,
+            &[ValidatorHistoryEntry {
+                epoch: 0,
+                epoch_credits: 1_000,
+                commission: 0,
+                ..ValidatorHistoryEntry::default()
+            }],
+        )
+        .await;
+
+    // without a bonus configured, stake does not matter
+    for validator in 0..3 {
+        assert_eq!(score(&fixture, validator).await, 1_000);
+    }
+
+    let params = ScoringParams {
+        max_decentralization_bonus_bps: 5_000,
+        stake_threshold_lamports: STAKE_THRESHOLD,
+        top_n_rank: 10,
+        bonus_curve: BonusCurve::Step,
+        ..ScoringParams::default()
+    };
+    let transaction = Transaction::new_signed_with_payer(
+        &[fixture.set_scoring_params_ix(params)],
+        Some(&fixture.admin.pubkey()),
+        &[&fixture.admin],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    fixture.submit_transaction_assert_success(transaction).await;
+
+    let pool: Pool = fixture.load_and_deserialize(&fixture.pool).await;
+    assert_eq!(pool.scoring_params, params);
+    assert_eq!(score(&fixture, 0).await, 1_000);
+    assert_eq!(score(&fixture, 1).await, 1_500);
+    assert_eq!(score(&fixture, 2).await, 1_000);
+
+    // on the linear curve, half the threshold earns half the stake bonus, which beats the rank
+    // bonus two places outside the top 10
+    let transaction = Transaction::new_signed_with_payer(
+        &[fixture.set_scoring_params_ix(ScoringParams {
+            bonus_curve: BonusCurve::Linear,
+            ..params
+        })],
+        Some(&fixture.admin.pubkey()),
+        &[&fixture.admin],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    fixture.submit_transaction_assert_success(transaction).await;
+
+    assert_eq!(score(&fixture, 0).await, 1_000);
+    assert_eq!(score(&fixture, 1).await, 1_250);
+}
+
+#[tokio::test]
+async fn test_decayed_scoring() {
+    let fixture = STestFixture::new().await;
+    let ctx = &fixture.ctx;
+    fixture.initialize_seraph().await;
+    fixture.initialize_and_score_validators().await;
+    fixture.advance_num_epochs(10).await;
+
+    // over the default lookback of epochs 5 to 10, one validator missed the epoch before the
+    // current one and the other the oldest
+    let entries: Vec<_> = (5..=10)
+        .map(|epoch| history_entry(epoch, STAKE_THRESHOLD, 0))
+        .collect();
+    fixture
+        .set_validator_history(0, &[&entries[..4], &entries[5..]].concat())
+        .await;
+    fixture.set_validator_history(1, &entries[1..]).await;
+
+    // the flat average skips missing epochs
+    assert_eq!(score(&fixture, 0).await, 1_000);
+    assert_eq!(score(&fixture, 1).await, 1_000);
+
+    let params = ScoringParams {
+        decay_percent: 50,
+        ..ScoringParams::default()
+    };
+    let transaction = Transaction::new_signed_with_payer(
+        &[fixture.set_scoring_params_ix(params)],
+        Some(&fixture.admin.pubkey()),
+        &[&fixture.admin],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    fixture.submit_transaction_assert_success(transaction).await;
+
+    // halving the weight of each older epoch, missing the second latest costs a quarter of the
+    // score and missing the oldest under 2% of it
+    assert_eq!(score(&fixture, 0).await, 746);
+    assert_eq!(score(&fixture, 1).await, 984);
+}
+
+#[tokio::test]
+async fn test_missing_epoch_policy() {
+    let fixture = STestFixture::new().await;
+    let ctx = &fixture.ctx;
+    fixture.initialize_seraph().await;
+    fixture.initialize_and_score_validators().await;
+    fixture.advance_num_epochs(10).await;
+
+    // over the default lookback of epochs 5 to 10, one validator has no history yet for the
+    // current epoch, one an entry the oracle never filled in, and one a full history
+    let entries: Vec<_> = (5..=10)
+        .map(|epoch| history_entry(epoch, STAKE_THRESHOLD, 0))
+        .collect();
+    fixture.set_validator_history(0, &entries[..5]).await;
+    let mut unrecorded = entries.clone();
+    unrecorded[2] = ValidatorHistoryEntry {
+        epoch: 7,
+        ..ValidatorHistoryEntry::default()
+    };
+    fixture.set_validator_history(1, &unrecorded).await;
+    fixture.set_validator_history(2, &entries).await;
+
+    let transaction = Transaction::new_signed_with_payer(
+        &[fixture.set_scoring_params_ix(ScoringParams {
+            missing_epoch_policy: MissingEpochPolicy::Zero,
+            ..ScoringParams::default()
+        })],
+        Some(&fixture.admin.pubkey()),
+        &[&fixture.admin],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    fixture.submit_transaction_assert_success(transaction).await;
+
+    // the current epoch is not missed while it is in progress
+    assert_eq!(score(&fixture, 0).await, 1_000);
+    assert_eq!(score(&fixture, 1).await, 833);
+    assert_eq!(score(&fixture, 2).await, 1_000);
+
+    let transaction = Transaction::new_signed_with_payer(
+        &[fixture.set_scoring_params_ix(ScoringParams {
+            missing_epoch_policy: MissingEpochPolicy::Ineligible,
+            ..ScoringParams::default()
+        })],
+        Some(&fixture.admin.pubkey()),
+        &[&fixture.admin],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    fixture.submit_transaction_assert_success(transaction).await;
+
+    let v_list: VList = fixture.load_and_deserialize(&fixture.v_list).await;
+    let listed = v_list.idx;
+    let rank = v_list
+        .ranking()
+        .iter()
+        .position(|validator| *validator == fixture.vote_accounts[1].pubkey())
+        .unwrap();
+    let transaction = Transaction::new_signed_with_payer(
+        &[fixture.calculate_score_ix(0), fixture.calculate_score_ix(1)],
+        Some(&fixture.admin.pubkey()),
+        &[&fixture.admin],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    let events: Vec<ValidatorRemoved> = fixture.submit_transaction_get_events(transaction).await;
+
+    // the removal records which lookback had the missing epoch
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].validator, fixture.vote_accounts[1].pubkey());
+    assert_eq!(events[0].reason, RemovalReason::MissingEpochs);
+    assert_eq!(events[0].score, 833);
+    assert_eq!(events[0].rank, rank as u32);
+    assert_eq!(
+        events[0].components,
+        ScoreComponents {
+            start_epoch: 5,
+            end_epoch: 10,
+            epochs_scored: 5,
+            flags: ScoreComponents::MISSING_EPOCHS,
+            ..ScoreComponents::default()
+        }
+    );
+
+    let v_list: VList = fixture.load_and_deserialize(&fixture.v_list).await;
+    assert_eq!(v_list.idx, listed - 1);
+    assert_eq!(v_list.get_score(&fixture.vote_accounts[1].pubkey()), None);
+    assert_eq!(score(&fixture, 0).await, 1_000);
+    assert_eq!(score(&fixture, 2).await, 1_000);
+}
+
+#[tokio::test]
+async fn test_normalized_credits() {
+    let fixture = STestFixture::new().await;
+    let ctx = &fixture.ctx;
+    fixture.initialize_seraph().await;
+    fixture.initialize_and_score_validators().await;
+    fixture.initialize_cluster_history_account().await;
+    fixture.advance_num_epochs(10).await;
+
+    // over the default lookback of epochs 5 to 10, the cluster's best earned twice the credits in
+    // the first three epochs as in the next two, and the current epoch has no reference yet
+    let ixs: Vec<_> = (5..=9)
+        .map(|epoch| {
+            fixture.update_max_epoch_credits_ix(epoch, if epoch < 8 { 2_000 } else { 1_000 })
+        })
+        .collect();
+    let transaction = Transaction::new_signed_with_payer(
+        &ixs,
+        Some(&fixture.keypair.pubkey()),
+        &[&fixture.keypair],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    fixture.submit_transaction_assert_success(transaction).await;
+
+    let entries: Vec<_> = (5..=10)
+        .map(|epoch| history_entry(epoch, STAKE_THRESHOLD, 0))
+        .collect();
+    fixture.set_validator_history(0, &entries[..5]).await;
+    fixture.set_validator_history(1, &entries).await;
+    let best: Vec<_> = entries
+        .iter()
+        .map(|entry| ValidatorHistoryEntry {
+            epoch_credits: 2_000,
+            ..*entry
+        })
+        .collect();
+    fixture.set_validator_history(2, &best).await;
+    let mut missed = entries.clone();
+    missed.remove(2);
+    fixture.set_validator_history(3, &missed).await;
+
+    assert_eq!(score(&fixture, 0).await, 1_000);
+    assert_eq!(score(&fixture, 2).await, 2_000);
+
+    let transaction = Transaction::new_signed_with_payer(
+        &[fixture.set_scoring_params_ix(ScoringParams {
+            normalize_credits: true,
+            ..ScoringParams::default()
+        })],
+        Some(&fixture.admin.pubkey()),
+        &[&fixture.admin],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    fixture.submit_transaction_assert_success(transaction).await;
+
+    // half the reference for three epochs and all of it for two, with the current epoch left out
+    // for lack of a reference
+    assert_eq!(score(&fixture, 0).await, 700_000);
+    assert_eq!(score(&fixture, 1).await, 700_000);
+    assert_eq!(score(&fixture, 2).await, 1_000_000);
+
+    // so only a completed epoch without history makes a validator ineligible
+    let transaction = Transaction::new_signed_with_payer(
+        &[fixture.set_scoring_params_ix(ScoringParams {
+            normalize_credits: true,
+            missing_epoch_policy: MissingEpochPolicy::Ineligible,
+            ..ScoringParams::default()
+        })],
+        Some(&fixture.admin.pubkey()),
+        &[&fixture.admin],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    fixture.submit_transaction_assert_success(transaction).await;
+
+    assert_eq!(score(&fixture, 0).await, 700_000);
+    assert_eq!(score(&fixture, 1).await, 700_000);
+    let transaction = Transaction::new_signed_with_payer(
+        &[fixture.calculate_score_ix(3)],
+        Some(&fixture.admin.pubkey()),
+        &[&fixture.admin],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    fixture.submit_transaction_assert_success(transaction).await;
+
+    let v_list: VList = fixture.load_and_deserialize(&fixture.v_list).await;
+    assert_eq!(v_list.get_score(&fixture.vote_accounts[3].pubkey()), None);
+}
+
+#[tokio::test]
+async fn test_skip_rate() {
+    let fixture = STestFixture::new().await;
+    let ctx = &fixture.ctx;
+    fixture.initialize_seraph().await;
+    fixture.initialize_and_score_validators().await;
+
+    // a validator producing every block, one skipping a fifth of its leader slots, and one
+    // without recorded block production
+    let block_production = |leader_slots, blocks_produced| ValidatorHistoryEntry {
+        leader_slots,
+        blocks_produced,
+        ..history_entry(0, STAKE_THRESHOLD, 0)
+    };
+    fixture
+        .set_validator_history(0, &[block_production(100, 100)])
+        .await;
+    fixture
+        .set_validator_history(1, &[block_production(100, 80)])
+        .await;
+    fixture
+        .set_validator_history(2, &[history_entry(0, STAKE_THRESHOLD, 0)])
+        .await;
+
+    for validator in 0..3 {
+        assert_eq!(score(&fixture, validator).await, 1_000);
+    }
+
+    let transaction = Transaction::new_signed_with_payer(
+        &[fixture.set_scoring_params_ix(ScoringParams {
+            skip_rate_weight_bps: 5_000,
+            ..ScoringParams::default()
+        })],
+        Some(&fixture.admin.pubkey()),
+        &[&fixture.admin],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    fixture.submit_transaction_assert_success(transaction).await;
+
+    // a 20% skip rate at half weight costs 10% of the score
+    assert_eq!(score(&fixture, 0).await, 1_000);
+    assert_eq!(score(&fixture, 1).await, 900);
+    assert_eq!(score(&fixture, 2).await, 1_000);
+}
+
+#[tokio::test]
+async fn test_score_components() {
+    let fixture = STestFixture::new().await;
+    let ctx = &fixture.ctx;
+    fixture.initialize_seraph().await;
+    fixture.initialize_and_score_validators().await;
+
+    // a small validator at 10% commission, skipping a fifth of its leader slots
+    fixture
+        .set_validator_history(
+            1,
+            &[ValidatorHistoryEntry {
+                commission: 10,
+                leader_slots: 100,
+                blocks_produced: 80,
+                ..history_entry(0, STAKE_THRESHOLD / 2, 12)
+            }],
+        )
+        .await;
+
+    let transaction = Transaction::new_signed_with_payer(
+        &[fixture.set_scoring_params_ix(ScoringParams {
+            max_decentralization_bonus_bps: 5_000,
+            stake_threshold_lamports: STAKE_THRESHOLD,
+            skip_rate_weight_bps: 5_000,
+            ..ScoringParams::default()
+        })],
+        Some(&fixture.admin.pubkey()),
+        &[&fixture.admin],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    fixture.submit_transaction_assert_success(transaction).await;
+
+    let transaction = Transaction::new_signed_with_payer(
+        &[fixture.calculate_score_ix(1)],
+        Some(&fixture.admin.pubkey()),
+        &[&fixture.admin],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    let events: Vec<ValidatorScoreUpdated> =
+        fixture.submit_transaction_get_events(transaction).await;
+    assert_eq!(events.len(), 1);
+
+    // 1000 credits less 10% commission is 900, less 10% for the skip rate at half weight is 810,
+    // and the stake bonus adds half of that
+    let components = ScoreComponents {
+        credits: 1_000,
+        commission_penalty: 100,
+        skip_rate_penalty: 90,
+        decentralization_bonus: 405,
+        start_epoch: 0,
+        end_epoch: 0,
+        epochs_scored: 1,
+        flags: ScoreComponents::ELIGIBLE,
+        ..ScoreComponents::default()
+    };
+    assert_eq!(events[0].new_score, 1_215);
+    assert_eq!(events[0].components, components);
+
+    let v_list: VList = fixture.load_and_deserialize(&fixture.v_list).await;
+    let entry = v_list.validators[..v_list.idx]
+        .iter()
+        .find(|entry| entry.validator == fixture.vote_accounts[1].pubkey())
+        .unwrap();
+    assert_eq!(entry.score, 1_215);
+    assert_eq!(entry.components, components);
+
+    let pool: Pool = fixture.load_and_deserialize(&fixture.pool).await;
+    assert!(v_list.is_eligible(&fixture.vote_accounts[1].pubkey(), &pool.params));
+
+    // narrowing the percentile clears the stored flag of every validator it no longer covers
+    let transaction = Transaction::new_signed_with_payer(
+        &[fixture.update_params_ix(PoolParams {
+            percentile: 1,
+            ..pool.params
+        })],
+        Some(&fixture.admin.pubkey()),
+        &[&fixture.admin],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    fixture.submit_transaction_assert_success(transaction).await;
+
+    let v_list: VList = fixture.load_and_deserialize(&fixture.v_list).await;
+    for (rank, entry) in v_list.validators[..v_list.idx].iter().enumerate() {
+        assert_eq!(
+            entry.components.flags & ScoreComponents::ELIGIBLE != 0,
+            rank == 0
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_invalid_scoring_params() {
+    let fixture = STestFixture::new().await;
+    let ctx = &fixture.ctx;
+    fixture.initialize_seraph().await;
+
+    let transaction = Transaction::new_signed_with_payer(
+        &[fixture.set_scoring_params_ix(ScoringParams {
+            max_decentralization_bonus_bps: 10_001,
+            ..ScoringParams::default()
+        })],
+        Some(&fixture.admin.pubkey()),
+        &[&fixture.admin],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    fixture
+        .submit_transaction_assert_error(transaction, "InvalidScoringParams")
+        .await;
+
+    let transaction = Transaction::new_signed_with_payer(
+        &[fixture.set_scoring_params_ix(ScoringParams {
+            decay_percent: 101,
+            ..ScoringParams::default()
+        })],
+        Some(&fixture.admin.pubkey()),
+        &[&fixture.admin],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    fixture
+        .submit_transaction_assert_error(transaction, "InvalidScoringParams")
+        .await;
+
+    let transaction = Transaction::new_signed_with_payer(
+        &[fixture.set_scoring_params_ix(ScoringParams {
+            skip_rate_weight_bps: 10_001,
+            ..ScoringParams::default()
+        })],
+        Some(&fixture.admin.pubkey()),
+        &[&fixture.admin],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    fixture
+        .submit_transaction_assert_error(transaction, "InvalidScoringParams")
+        .await;
+}
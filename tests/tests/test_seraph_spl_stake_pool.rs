@@ -0,0 +1,172 @@
+#![allow(clippy::await_holding_refcell_ref)]
+use anchor_lang::solana_program::native_token::LAMPORTS_PER_SOL;
+use anchor_spl::stake::StakeAccount;
+use seraph::{spl_stake_pool, Pool, PoolParams, VList};
+use solana_program_test::*;
+use solana_sdk::{signer::Signer, transaction::Transaction};
+use tests::seraph_fixtures::STestFixture;
+
+#[tokio::test]
+async fn test_drive_spl_stake_pool() {
+    let fixture = STestFixture::new_with_spl_stake_pool().await;
+    let ctx = &fixture.ctx;
+    fixture.initialize_seraph().await;
+    fixture.initialize_and_score_validators().await;
+    let stake_pool = fixture
+        .initialize_spl_stake_pool(100 * LAMPORTS_PER_SOL)
+        .await;
+
+    // nothing is linked yet
+    let v_list: VList = fixture.load_and_deserialize(&fixture.v_list).await;
+    let validator = v_list.validators[1].validator;
+    let transaction = Transaction::new_signed_with_payer(
+        &[fixture.add_stake_pool_validator_ix(&stake_pool, validator)],
+        Some(&fixture.admin.pubkey()),
+        &[&fixture.admin],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    fixture
+        .submit_transaction_assert_error(transaction, "InvalidStakePool")
+        .await;
+
+    let transaction = Transaction::new_signed_with_payer(
+        &[
+            fixture.set_stake_pool_ix(stake_pool.stake_pool),
+            fixture.add_stake_pool_validator_ix(&stake_pool, validator),
+            fixture.increase_stake_pool_validator_stake_ix(
+                &stake_pool,
+                validator,
+                2 * LAMPORTS_PER_SOL,
+                0,
+            ),
+        ],
+        Some(&fixture.admin.pubkey()),
+        &[&fixture.admin],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    fixture.submit_transaction_assert_success(transaction).await;
+
+    let pool: Pool = fixture.load_and_deserialize(&fixture.pool).await;
+    assert_eq!(pool.stake_pool, stake_pool.stake_pool);
+    let validator_stake =
+        spl_stake_pool::validator_stake_pubkey(&validator, &stake_pool.stake_pool);
+    let stake: StakeAccount = fixture.load_and_deserialize(&validator_stake).await;
+    assert_eq!(stake.delegation().unwrap().voter_pubkey, validator);
+    let transient_stake =
+        spl_stake_pool::transient_stake_pubkey(&validator, &stake_pool.stake_pool, 0);
+    let stake: StakeAccount = fixture.load_and_deserialize(&transient_stake).await;
+    assert_eq!(stake.delegation().unwrap().stake, 2 * LAMPORTS_PER_SOL);
+
+    // stake is moved off an eligible validator only by decreasing it
+    let transaction = Transaction::new_signed_with_payer(
+        &[fixture.remove_stake_pool_validator_ix(&stake_pool, validator, 0)],
+        Some(&fixture.admin.pubkey()),
+        &[&fixture.admin],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    fixture
+        .submit_transaction_assert_error(transaction, "ValidatorStillEligible")
+        .await;
+
+    let top_validator = v_list.validators[0].validator;
+    let transaction = Transaction::new_signed_with_payer(
+        &[fixture.add_stake_pool_validator_ix(&stake_pool, top_validator)],
+        Some(&fixture.admin.pubkey()),
+        &[&fixture.admin],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    fixture.submit_transaction_assert_success(transaction).await;
+
+    // lamports above the minimum delegation, for the decrease to split off
+    let top_validator_stake =
+        spl_stake_pool::validator_stake_pubkey(&top_validator, &stake_pool.stake_pool);
+    fixture
+        .add_lamports(&top_validator_stake, 10 * LAMPORTS_PER_SOL)
+        .await;
+    let transaction = Transaction::new_signed_with_payer(
+        &[fixture.decrease_stake_pool_validator_stake_ix(
+            &stake_pool,
+            top_validator,
+            2 * LAMPORTS_PER_SOL,
+            0,
+        )],
+        Some(&fixture.admin.pubkey()),
+        &[&fixture.admin],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    fixture.submit_transaction_assert_success(transaction).await;
+
+    let clock = ctx
+        .borrow_mut()
+        .banks_client
+        .get_sysvar::<anchor_lang::prelude::Clock>()
+        .await
+        .unwrap();
+    let transient_stake =
+        spl_stake_pool::transient_stake_pubkey(&top_validator, &stake_pool.stake_pool, 0);
+    let stake: StakeAccount = fixture.load_and_deserialize(&transient_stake).await;
+    assert_eq!(stake.delegation().unwrap().deactivation_epoch, clock.epoch);
+
+    // narrowing the eligible validators to the top one drops the first validator, which can no
+    // longer receive stake and is removed while paused
+    let transaction = Transaction::new_signed_with_payer(
+        &[fixture.update_params_ix(PoolParams {
+            percentile: 1,
+            ..PoolParams::default()
+        })],
+        Some(&fixture.admin.pubkey()),
+        &[&fixture.admin],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    fixture.submit_transaction_assert_success(transaction).await;
+
+    let transaction = Transaction::new_signed_with_payer(
+        &[fixture.increase_stake_pool_validator_stake_ix(
+            &stake_pool,
+            validator,
+            LAMPORTS_PER_SOL,
+            1,
+        )],
+        Some(&fixture.admin.pubkey()),
+        &[&fixture.admin],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    fixture
+        .submit_transaction_assert_error(transaction, "ValidatorNotInList")
+        .await;
+
+    let transaction = Transaction::new_signed_with_payer(
+        &[
+            fixture.pause_ix(fixture.admin.pubkey()),
+            fixture.remove_stake_pool_validator_ix(&stake_pool, validator, 0),
+        ],
+        Some(&fixture.admin.pubkey()),
+        &[&fixture.admin],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    fixture.submit_transaction_assert_success(transaction).await;
+
+    let stake: StakeAccount = fixture.load_and_deserialize(&validator_stake).await;
+    assert_eq!(stake.delegation().unwrap().deactivation_epoch, clock.epoch);
+}
+
+#[tokio::test]
+async fn test_set_invalid_stake_pool() {
+    let fixture = STestFixture::new_with_spl_stake_pool().await;
+    let ctx = &fixture.ctx;
+    fixture.initialize_seraph().await;
+    let stake_pool = fixture.initialize_spl_stake_pool(LAMPORTS_PER_SOL).await;
+
+    // the stake pool's other accounts, owned by the stake pool program or not, are rejected
+    for address in [stake_pool.validator_list, stake_pool.reserve_stake] {
+        let transaction = Transaction::new_signed_with_payer(
+            &[fixture.set_stake_pool_ix(address)],
+            Some(&fixture.admin.pubkey()),
+            &[&fixture.admin],
+            ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+        );
+        fixture
+            .submit_transaction_assert_error(transaction, "InvalidStakePool")
+            .await;
+    }
+}
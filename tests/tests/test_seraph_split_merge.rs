@@ -0,0 +1,98 @@
+#![allow(clippy::await_holding_refcell_ref)]
+use seraph::{Pool, StakeRegistry};
+use solana_program_test::*;
+use solana_sdk::{signature::Keypair, signer::Signer, transaction::Transaction};
+use tests::seraph_fixtures::STestFixture;
+
+#[tokio::test]
+async fn test_split_and_merge_stake() {
+    let fixture = STestFixture::new().await;
+    let ctx = &fixture.ctx;
+    fixture.initialize_seraph().await;
+    fixture.init_and_auth_stake_accounts_to_pool().await;
+
+    let stake_account = fixture.stake_accounts[0].pubkey();
+    fixture.register_stake_accounts(&[stake_account]).await;
+
+    let transaction = Transaction::new_signed_with_payer(
+        &[fixture.delegate_stake_ix(stake_account, fixture.vote_accounts[0].pubkey())],
+        Some(&fixture.admin.pubkey()),
+        &[&fixture.admin],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    fixture.submit_transaction_assert_success(transaction).await;
+
+    let stake_registry: StakeRegistry = fixture.load_and_deserialize(&fixture.stake_registry).await;
+    let source_entry = *stake_registry.get(&stake_account).unwrap();
+    let pool: Pool = fixture.load_and_deserialize(&fixture.pool).await;
+    let total_lamports = pool.total_lamports;
+
+    // splitting everything would leave the source without its rent-exempt reserve
+    let split_stake_account = Keypair::new();
+    let transaction = Transaction::new_signed_with_payer(
+        &[fixture.split_stake_ix(
+            stake_account,
+            split_stake_account.pubkey(),
+            source_entry.lamports,
+        )],
+        Some(&fixture.admin.pubkey()),
+        &[&fixture.admin, &split_stake_account],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    fixture
+        .submit_transaction_assert_error(transaction, "InvalidStakeAmount")
+        .await;
+
+    let split_lamports = source_entry.lamports / 2;
+    let transaction = Transaction::new_signed_with_payer(
+        &[fixture.split_stake_ix(stake_account, split_stake_account.pubkey(), split_lamports)],
+        Some(&fixture.admin.pubkey()),
+        &[&fixture.admin, &split_stake_account],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    fixture.submit_transaction_assert_success(transaction).await;
+
+    let split_account = ctx
+        .borrow_mut()
+        .banks_client
+        .get_account(split_stake_account.pubkey())
+        .await
+        .unwrap()
+        .unwrap();
+    let split_rent = split_account.lamports - split_lamports;
+
+    let stake_registry: StakeRegistry = fixture.load_and_deserialize(&fixture.stake_registry).await;
+    let pool: Pool = fixture.load_and_deserialize(&fixture.pool).await;
+    let entry = stake_registry.get(&stake_account).unwrap();
+    let split_entry = stake_registry.get(&split_stake_account.pubkey()).unwrap();
+    assert_eq!(stake_registry.entries().len(), 2);
+    assert_eq!(entry.lamports, source_entry.lamports - split_lamports);
+    assert_eq!(split_entry.lamports, split_account.lamports);
+    assert_eq!(split_entry.vote_account, source_entry.vote_account);
+    assert_eq!(split_entry.status, source_entry.status);
+    assert_eq!(pool.total_lamports, total_lamports + split_rent);
+
+    // merging the split back removes it from the registry
+    let transaction = Transaction::new_signed_with_payer(
+        &[fixture.merge_stake_ix(stake_account, split_stake_account.pubkey())],
+        Some(&fixture.admin.pubkey()),
+        &[&fixture.admin],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    fixture.submit_transaction_assert_success(transaction).await;
+
+    let stake_registry: StakeRegistry = fixture.load_and_deserialize(&fixture.stake_registry).await;
+    assert_eq!(stake_registry.entries().len(), 1);
+    assert!(stake_registry.get(&split_stake_account.pubkey()).is_none());
+    assert_eq!(
+        stake_registry.get(&stake_account).unwrap().lamports,
+        source_entry.lamports + split_rent
+    );
+    assert!(ctx
+        .borrow_mut()
+        .banks_client
+        .get_account(split_stake_account.pubkey())
+        .await
+        .unwrap()
+        .is_none());
+}
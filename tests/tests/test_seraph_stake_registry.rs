@@ -0,0 +1,159 @@
+#![allow(clippy::await_holding_refcell_ref)]
+use seraph::{Pool, StakeRegistry, StakeStatus};
+use solana_program_test::*;
+use solana_sdk::{signature::Keypair, signer::Signer, transaction::Transaction};
+use tests::seraph_fixtures::{new_stake_account, STestFixture};
+
+#[tokio::test]
+async fn test_stake_registry_tracks_stake_accounts() {
+    let fixture = STestFixture::new().await;
+    let ctx = &fixture.ctx;
+    fixture.initialize_seraph().await;
+    fixture.init_and_auth_stake_accounts_to_pool().await;
+
+    let stake_accounts: Vec<_> = fixture.stake_accounts[0..2]
+        .iter()
+        .map(|stake_account| stake_account.pubkey())
+        .collect();
+    fixture.register_stake_accounts(&stake_accounts).await;
+
+    let stake_registry: StakeRegistry = fixture.load_and_deserialize(&fixture.stake_registry).await;
+    let pool: Pool = fixture.load_and_deserialize(&fixture.pool).await;
+    assert_eq!(stake_registry.pool, fixture.pool);
+    assert_eq!(stake_registry.entries().len(), 2);
+    assert_eq!(
+        pool.total_lamports,
+        stake_registry
+            .entries()
+            .iter()
+            .map(|entry| entry.lamports)
+            .sum::<u64>()
+    );
+    for (entry, stake_account) in stake_registry.entries().iter().zip(stake_accounts.iter()) {
+        assert_eq!(entry.stake_account, *stake_account);
+        assert_eq!(entry.status, StakeStatus::Inactive);
+    }
+
+    // registering the same account twice fails
+    let transaction = Transaction::new_signed_with_payer(
+        &[fixture.add_stake_account_ix(stake_accounts[0])],
+        Some(&fixture.admin.pubkey()),
+        &[&fixture.admin],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    fixture
+        .submit_transaction_assert_error(transaction, "DuplicateStakeAccount")
+        .await;
+
+    let transaction = Transaction::new_signed_with_payer(
+        &[fixture.delegate_stake_ix(stake_accounts[1], fixture.vote_accounts[1].pubkey())],
+        Some(&fixture.admin.pubkey()),
+        &[&fixture.admin],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    fixture.submit_transaction_assert_success(transaction).await;
+
+    let stake_registry: StakeRegistry = fixture.load_and_deserialize(&fixture.stake_registry).await;
+    let entry = stake_registry.get(&stake_accounts[1]).unwrap();
+    assert_eq!(entry.vote_account, fixture.vote_accounts[1].pubkey());
+    assert_eq!(entry.status, StakeStatus::Activating);
+
+    // the balance update refreshes lamports from the stake accounts
+    let rewards = 1_000_000_000;
+    fixture.advance_num_epochs(1).await;
+    fixture.add_lamports(&stake_accounts[0], rewards).await;
+    let transaction = Transaction::new_signed_with_payer(
+        &[fixture.update_pool_balance_ix(&stake_accounts)],
+        Some(&fixture.admin.pubkey()),
+        &[&fixture.admin],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    fixture.submit_transaction_assert_success(transaction).await;
+
+    let previous_lamports = stake_registry.get(&stake_accounts[0]).unwrap().lamports;
+    let stake_registry: StakeRegistry = fixture.load_and_deserialize(&fixture.stake_registry).await;
+    let entry = stake_registry.get(&stake_accounts[0]).unwrap();
+    assert_eq!(entry.lamports, previous_lamports + rewards);
+    assert_eq!(entry.status, StakeStatus::Inactive);
+
+    let transaction = Transaction::new_signed_with_payer(
+        &[fixture.deactivate_stake_ix(stake_accounts[1])],
+        Some(&fixture.admin.pubkey()),
+        &[&fixture.admin],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    fixture.submit_transaction_assert_success(transaction).await;
+
+    let stake_registry: StakeRegistry = fixture.load_and_deserialize(&fixture.stake_registry).await;
+    let entry = stake_registry.get(&stake_accounts[1]).unwrap();
+    assert_eq!(entry.vote_account, fixture.vote_accounts[1].pubkey());
+    assert_eq!(entry.status, StakeStatus::Deactivating);
+}
+
+#[tokio::test]
+async fn test_stake_registry_rejects_unregistered_stake() {
+    let fixture = STestFixture::new().await;
+    let ctx = &fixture.ctx;
+    fixture.initialize_seraph().await;
+    fixture.init_and_auth_stake_accounts_to_pool().await;
+
+    let stake_accounts: Vec<_> = fixture.stake_accounts[0..2]
+        .iter()
+        .map(|stake_account| stake_account.pubkey())
+        .collect();
+    fixture.register_stake_accounts(&stake_accounts).await;
+
+    // uninitialized stake account not owned by the pool
+    let foreign_stake_account = Keypair::new();
+    ctx.borrow_mut().set_account(
+        &foreign_stake_account.pubkey(),
+        &new_stake_account(1_000_000_000).into(),
+    );
+    let transaction = Transaction::new_signed_with_payer(
+        &[fixture.add_stake_account_ix(foreign_stake_account.pubkey())],
+        Some(&fixture.admin.pubkey()),
+        &[&fixture.admin],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    fixture
+        .submit_transaction_assert_error(transaction, "InvalidStakeAccount")
+        .await;
+
+    // stake accounts outside the registry cannot be delegated
+    let transaction = Transaction::new_signed_with_payer(
+        &[fixture.delegate_stake_ix(
+            fixture.stake_accounts[2].pubkey(),
+            fixture.vote_accounts[2].pubkey(),
+        )],
+        Some(&fixture.admin.pubkey()),
+        &[&fixture.admin],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    fixture
+        .submit_transaction_assert_error(transaction, "StakeAccountNotRegistered")
+        .await;
+
+    fixture.advance_num_epochs(1).await;
+
+    // balance updates must list exactly the registered accounts, in order
+    for remaining_accounts in [
+        vec![stake_accounts[0]],
+        vec![stake_accounts[1], stake_accounts[0]],
+        vec![stake_accounts[0], stake_accounts[0]],
+        vec![
+            stake_accounts[0],
+            stake_accounts[1],
+            foreign_stake_account.pubkey(),
+        ],
+    ] {
+        let transaction = Transaction::new_signed_with_payer(
+            &[fixture.update_pool_balance_ix(&remaining_accounts)],
+            Some(&fixture.admin.pubkey()),
+            &[&fixture.admin],
+            ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+        );
+        fixture
+            .submit_transaction_assert_error(transaction, "StakeRegistryMismatch")
+            .await;
+    }
+}
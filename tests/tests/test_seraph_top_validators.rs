@@ -0,0 +1,111 @@
+#![allow(clippy::await_holding_refcell_ref)]
+use anchor_lang::{prelude::Pubkey, AnchorDeserialize};
+use seraph::{PoolParams, TopValidators, VList, MAX_TOP_VALIDATORS, TOP_VALIDATORS_VERSION};
+use solana_program_test::*;
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    signer::Signer,
+    transaction::Transaction,
+};
+use tests::{
+    seraph_fixtures::{STestFixture, TOTAL_VALIDATORS},
+    top_validators_consumer,
+};
+
+#[tokio::test]
+async fn test_get_top_validators() {
+    let fixture = STestFixture::new().await;
+    let ctx = &fixture.ctx;
+    fixture.initialize_seraph().await;
+    fixture.initialize_and_score_validators().await;
+
+    // make the top half of the list eligible
+    let transaction = Transaction::new_signed_with_payer(
+        &[fixture.update_params_ix(PoolParams {
+            percentile: 50,
+            ..PoolParams::default()
+        })],
+        Some(&fixture.admin.pubkey()),
+        &[&fixture.admin],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    fixture.submit_transaction_assert_success(transaction).await;
+
+    let v_list: VList = fixture.load_and_deserialize(&fixture.v_list).await;
+
+    let transaction = Transaction::new_signed_with_payer(
+        &[fixture.get_top_validators_ix(5)],
+        Some(&fixture.keypair.pubkey()),
+        &[&fixture.keypair],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    let (program_id, data) = fixture
+        .submit_transaction_get_return_data(transaction)
+        .await;
+    assert_eq!(program_id, seraph::id());
+
+    let top_validators = TopValidators::try_from_slice(&data).unwrap();
+    assert_eq!(top_validators.version, TOP_VALIDATORS_VERSION);
+    assert_eq!(top_validators.pool, fixture.pool);
+    assert_eq!(top_validators.list_len as usize, TOTAL_VALIDATORS);
+    assert_eq!(top_validators.validators.len(), 5);
+    for (returned, entry) in top_validators
+        .validators
+        .iter()
+        .zip(v_list.validators.iter())
+    {
+        assert_eq!(returned.validator, entry.validator);
+        assert_eq!(returned.score, entry.score);
+        assert_eq!(returned.last_scored_epoch, entry.last_scored_epoch);
+    }
+
+    // larger requests are capped to the eligible validators and to fit in return data
+    let transaction = Transaction::new_signed_with_payer(
+        &[fixture.get_top_validators_ix(u8::MAX)],
+        Some(&fixture.keypair.pubkey()),
+        &[&fixture.keypair],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    let (_, data) = fixture
+        .submit_transaction_get_return_data(transaction)
+        .await;
+    let top_validators = TopValidators::try_from_slice(&data).unwrap();
+    assert_eq!(
+        top_validators.validators.len(),
+        MAX_TOP_VALIDATORS.min(TOTAL_VALIDATORS / 2)
+    );
+}
+
+#[tokio::test]
+async fn test_get_top_validators_by_cpi() {
+    let fixture = STestFixture::new().await;
+    let ctx = &fixture.ctx;
+    fixture.initialize_seraph().await;
+    fixture.initialize_and_score_validators().await;
+
+    let v_list: VList = fixture.load_and_deserialize(&fixture.v_list).await;
+
+    let instruction = Instruction {
+        program_id: top_validators_consumer::id(),
+        accounts: vec![
+            AccountMeta::new_readonly(seraph::id(), false),
+            AccountMeta::new_readonly(fixture.pool, false),
+            AccountMeta::new_readonly(fixture.v_list, false),
+        ],
+        data: vec![3],
+    };
+    let transaction = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&fixture.keypair.pubkey()),
+        &[&fixture.keypair],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    let (program_id, data) = fixture
+        .submit_transaction_get_return_data(transaction)
+        .await;
+    assert_eq!(program_id, top_validators_consumer::id());
+    assert_eq!(
+        Pubkey::try_from(data.as_slice()).unwrap(),
+        v_list.validators[0].validator
+    );
+}
@@ -0,0 +1,257 @@
+#![allow(clippy::await_holding_refcell_ref)]
+use seraph::{Pool, PoolHistory, StakeRegistry, StakeStatus, VList};
+use solana_program_test::*;
+use solana_sdk::{signature::Keypair, signer::Signer, transaction::Transaction};
+use tests::seraph_fixtures::STestFixture;
+
+#[tokio::test]
+async fn test_redelegate_through_transient_stake() {
+    let fixture = STestFixture::new().await;
+    let ctx = &fixture.ctx;
+    fixture.initialize_seraph().await;
+    fixture.initialize_and_score_validators().await;
+    fixture.init_and_auth_stake_accounts_to_pool().await;
+
+    // move stake up the list without hysteresis or a move budget in the way
+    let v_list: VList = fixture.load_and_deserialize(&fixture.v_list).await;
+    let stake_account = fixture.stake_accounts[0].pubkey();
+    let old_validator_vote = v_list.validators[1].validator;
+    let new_validator_vote = v_list.validators[0].validator;
+    let transient_stake_account = Pool::transient_stake_pubkey(fixture.pool, old_validator_vote, 0);
+    fixture.register_stake_accounts(&[stake_account]).await;
+
+    let transaction = Transaction::new_signed_with_payer(
+        &[
+            fixture.set_rebalance_params_ix(0, 10_000),
+            fixture.delegate_stake_ix(stake_account, old_validator_vote),
+        ],
+        Some(&fixture.admin.pubkey()),
+        &[&fixture.admin],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    fixture.submit_transaction_assert_success(transaction).await;
+
+    let stake_registry: StakeRegistry = fixture.load_and_deserialize(&fixture.stake_registry).await;
+    let source_lamports = stake_registry.get(&stake_account).unwrap().lamports;
+
+    // the source must keep its rent-exempt reserve and minimum delegation
+    let transaction = Transaction::new_signed_with_payer(
+        &[fixture.redelegate_stake_ix(
+            stake_account,
+            old_validator_vote,
+            new_validator_vote,
+            source_lamports,
+            0,
+        )],
+        Some(&fixture.admin.pubkey()),
+        &[&fixture.admin],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    fixture
+        .submit_transaction_assert_error(transaction, "InvalidStakeAmount")
+        .await;
+
+    // transient accounts are derived from the validator the stake is delegated to
+    let transaction = Transaction::new_signed_with_payer(
+        &[fixture.redelegate_stake_ix(
+            stake_account,
+            new_validator_vote,
+            new_validator_vote,
+            source_lamports / 2,
+            0,
+        )],
+        Some(&fixture.admin.pubkey()),
+        &[&fixture.admin],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    fixture
+        .submit_transaction_assert_error(transaction, "InvalidValidatorVote")
+        .await;
+
+    let transaction = Transaction::new_signed_with_payer(
+        &[fixture.redelegate_stake_ix(
+            stake_account,
+            old_validator_vote,
+            new_validator_vote,
+            source_lamports / 2,
+            0,
+        )],
+        Some(&fixture.admin.pubkey()),
+        &[&fixture.admin],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    fixture.submit_transaction_assert_success(transaction).await;
+
+    let stake_registry: StakeRegistry = fixture.load_and_deserialize(&fixture.stake_registry).await;
+    let transient_entry = stake_registry.get(&transient_stake_account).unwrap();
+    assert!(transient_entry.is_transient);
+    assert_eq!(transient_entry.vote_account, old_validator_vote);
+    assert_eq!(transient_entry.transient_vote_account, new_validator_vote);
+    assert_eq!(transient_entry.status, StakeStatus::Deactivating);
+
+    // transient stake can only be delegated to its target
+    let transaction = Transaction::new_signed_with_payer(
+        &[fixture.delegate_stake_ix(transient_stake_account, new_validator_vote)],
+        Some(&fixture.admin.pubkey()),
+        &[&fixture.admin],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    fixture
+        .submit_transaction_assert_error(transaction, "TransientStakeInFlight")
+        .await;
+
+    // nor split while in flight
+    let split_stake_account = Keypair::new();
+    let transaction = Transaction::new_signed_with_payer(
+        &[fixture.split_stake_ix(
+            transient_stake_account,
+            split_stake_account.pubkey(),
+            transient_entry.lamports / 2,
+        )],
+        Some(&fixture.admin.pubkey()),
+        &[&fixture.admin, &split_stake_account],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    fixture
+        .submit_transaction_assert_error(transaction, "TransientStakeInFlight")
+        .await;
+
+    let transaction = Transaction::new_signed_with_payer(
+        &[fixture.finalize_transient_stake_ix(transient_stake_account, old_validator_vote)],
+        Some(&fixture.keypair.pubkey()),
+        &[&fixture.keypair],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    fixture
+        .submit_transaction_assert_error(transaction, "InvalidValidatorVote")
+        .await;
+
+    // the stake never became effective, so the transient account is already inactive
+    let transaction = Transaction::new_signed_with_payer(
+        &[fixture.finalize_transient_stake_ix(transient_stake_account, new_validator_vote)],
+        Some(&fixture.keypair.pubkey()),
+        &[&fixture.keypair],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    fixture.submit_transaction_assert_success(transaction).await;
+
+    let stake_registry: StakeRegistry = fixture.load_and_deserialize(&fixture.stake_registry).await;
+    let transient_entry = stake_registry.get(&transient_stake_account).unwrap();
+    assert!(!transient_entry.is_transient);
+    assert_eq!(transient_entry.vote_account, new_validator_vote);
+    assert_eq!(transient_entry.status, StakeStatus::Activating);
+
+    let transaction = Transaction::new_signed_with_payer(
+        &[fixture.finalize_transient_stake_ix(transient_stake_account, new_validator_vote)],
+        Some(&fixture.keypair.pubkey()),
+        &[&fixture.keypair],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    fixture
+        .submit_transaction_assert_error(transaction, "NotTransientStake")
+        .await;
+}
+
+#[tokio::test]
+async fn test_decrease_stake_to_reserve() {
+    let fixture = STestFixture::new().await;
+    let ctx = &fixture.ctx;
+    fixture.initialize_seraph().await;
+    fixture.initialize_pool_history().await;
+    fixture.init_and_auth_stake_accounts_to_pool().await;
+
+    let stake_account = fixture.stake_accounts[0].pubkey();
+    let validator_vote = fixture.vote_accounts[0].pubkey();
+    let transient_stake_account = Pool::transient_stake_pubkey(fixture.pool, validator_vote, 7);
+    fixture.register_stake_accounts(&[stake_account]).await;
+
+    let stake_registry: StakeRegistry = fixture.load_and_deserialize(&fixture.stake_registry).await;
+    let decrease_lamports = stake_registry.get(&stake_account).unwrap().lamports / 4;
+
+    let transaction = Transaction::new_signed_with_payer(
+        &[
+            fixture.delegate_stake_ix(stake_account, validator_vote),
+            fixture.decrease_stake_ix(stake_account, validator_vote, decrease_lamports, 7),
+        ],
+        Some(&fixture.admin.pubkey()),
+        &[&fixture.admin],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    fixture.submit_transaction_assert_success(transaction).await;
+
+    let transient_account = ctx
+        .borrow_mut()
+        .banks_client
+        .get_account(transient_stake_account)
+        .await
+        .unwrap()
+        .unwrap();
+    let pool: Pool = fixture.load_and_deserialize(&fixture.pool).await;
+    let total_lamports = pool.total_lamports;
+
+    // in-flight lamports are recorded separately in the pool history
+    fixture.advance_num_epochs(1).await;
+    let stake_accounts = [stake_account, transient_stake_account];
+    let transaction = Transaction::new_signed_with_payer(
+        &[
+            fixture.update_pool_balance_ix(&stake_accounts),
+            fixture.update_pool_history_ix(&stake_accounts),
+        ],
+        Some(&fixture.keypair.pubkey()),
+        &[&fixture.keypair, &fixture.admin],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    fixture.submit_transaction_assert_success(transaction).await;
+
+    let pool_history: PoolHistory = fixture.load_and_deserialize(&fixture.pool_history).await;
+    let entry = pool_history.history.last().unwrap();
+    assert_eq!(entry.transient_stake_lamports, transient_account.lamports);
+    assert_eq!(
+        entry.total_lamports,
+        total_lamports + entry.rewards_lamports
+    );
+
+    let reserve_lamports = ctx
+        .borrow_mut()
+        .banks_client
+        .get_balance(fixture.reserve)
+        .await
+        .unwrap();
+
+    // the transient account is not a regular stake account
+    let transaction = Transaction::new_signed_with_payer(
+        &[fixture.withdraw_stake_ix(transient_stake_account)],
+        Some(&fixture.admin.pubkey()),
+        &[&fixture.admin],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    fixture
+        .submit_transaction_assert_error(transaction, "TransientStakeInFlight")
+        .await;
+
+    let transaction = Transaction::new_signed_with_payer(
+        &[fixture.finalize_transient_stake_ix(transient_stake_account, validator_vote)],
+        Some(&fixture.keypair.pubkey()),
+        &[&fixture.keypair],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    fixture.submit_transaction_assert_success(transaction).await;
+
+    assert!(ctx
+        .borrow_mut()
+        .banks_client
+        .get_account(transient_stake_account)
+        .await
+        .unwrap()
+        .is_none());
+    assert_eq!(
+        ctx.borrow_mut()
+            .banks_client
+            .get_balance(fixture.reserve)
+            .await
+            .unwrap(),
+        reserve_lamports + transient_account.lamports
+    );
+    let stake_registry: StakeRegistry = fixture.load_and_deserialize(&fixture.stake_registry).await;
+    assert_eq!(stake_registry.entries().len(), 1);
+}
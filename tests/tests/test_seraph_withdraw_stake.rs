@@ -0,0 +1,110 @@
+#![allow(clippy::await_holding_refcell_ref)]
+use seraph::{Pool, StakeRegistry};
+use solana_program_test::*;
+use solana_sdk::{signer::Signer, transaction::Transaction};
+use tests::seraph_fixtures::STestFixture;
+
+#[tokio::test]
+async fn test_withdraw_stake_to_reserve() {
+    let fixture = STestFixture::new().await;
+    let ctx = &fixture.ctx;
+    fixture.initialize_seraph().await;
+    fixture.init_and_auth_stake_accounts_to_pool().await;
+
+    let stake_accounts: Vec<_> = fixture.stake_accounts[0..2]
+        .iter()
+        .map(|stake_account| stake_account.pubkey())
+        .collect();
+    fixture.register_stake_accounts(&stake_accounts).await;
+
+    let reserve_rent = ctx
+        .borrow_mut()
+        .banks_client
+        .get_balance(fixture.reserve)
+        .await
+        .unwrap();
+    let stake_registry: StakeRegistry = fixture.load_and_deserialize(&fixture.stake_registry).await;
+    let undelegated_lamports = stake_registry.get(&stake_accounts[0]).unwrap().lamports;
+    let delegated_lamports = stake_registry.get(&stake_accounts[1]).unwrap().lamports;
+
+    // undelegated stake is withdrawn in full and the stake account is closed
+    let transaction = Transaction::new_signed_with_payer(
+        &[fixture.withdraw_stake_ix(stake_accounts[0])],
+        Some(&fixture.admin.pubkey()),
+        &[&fixture.admin],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    fixture.submit_transaction_assert_success(transaction).await;
+
+    assert!(ctx
+        .borrow_mut()
+        .banks_client
+        .get_account(stake_accounts[0])
+        .await
+        .unwrap()
+        .is_none());
+    assert_eq!(
+        ctx.borrow_mut()
+            .banks_client
+            .get_balance(fixture.reserve)
+            .await
+            .unwrap(),
+        reserve_rent + undelegated_lamports
+    );
+    let stake_registry: StakeRegistry = fixture.load_and_deserialize(&fixture.stake_registry).await;
+    assert_eq!(stake_registry.entries().len(), 1);
+    assert!(stake_registry.get(&stake_accounts[0]).is_none());
+
+    // delegated stake cannot be withdrawn until it is fully inactive
+    let transaction = Transaction::new_signed_with_payer(
+        &[fixture.delegate_stake_ix(stake_accounts[1], fixture.vote_accounts[1].pubkey())],
+        Some(&fixture.admin.pubkey()),
+        &[&fixture.admin],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    fixture.submit_transaction_assert_success(transaction).await;
+
+    let transaction = Transaction::new_signed_with_payer(
+        &[fixture.withdraw_stake_ix(stake_accounts[1])],
+        Some(&fixture.admin.pubkey()),
+        &[&fixture.admin],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    fixture
+        .submit_transaction_assert_error(transaction, "StakeNotInactive")
+        .await;
+
+    let transaction = Transaction::new_signed_with_payer(
+        &[fixture.deactivate_stake_ix(stake_accounts[1])],
+        Some(&fixture.admin.pubkey()),
+        &[&fixture.admin],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    fixture.submit_transaction_assert_success(transaction).await;
+
+    // stake deactivated in its activation epoch never becomes effective
+    let transaction = Transaction::new_signed_with_payer(
+        &[fixture.withdraw_stake_ix(stake_accounts[1])],
+        Some(&fixture.admin.pubkey()),
+        &[&fixture.admin],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    fixture.submit_transaction_assert_success(transaction).await;
+
+    // withdrawn lamports stay in the pool's accounting through the reserve
+    fixture.advance_num_epochs(1).await;
+    let transaction = Transaction::new_signed_with_payer(
+        &[fixture.update_pool_balance_ix(&[])],
+        Some(&fixture.admin.pubkey()),
+        &[&fixture.admin],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    fixture.submit_transaction_assert_success(transaction).await;
+
+    let pool: Pool = fixture.load_and_deserialize(&fixture.pool).await;
+    assert_eq!(
+        pool.total_lamports,
+        undelegated_lamports + delegated_lamports
+    );
+    assert_eq!(pool.last_epoch_rewards, 0);
+}
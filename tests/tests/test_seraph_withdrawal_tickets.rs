@@ -0,0 +1,377 @@
+#![allow(clippy::await_holding_refcell_ref)]
+use anchor_spl::token::TokenAccount;
+use seraph::{Pool, StakeRegistry, StakeStatus, VList, WithdrawalTicket};
+use solana_program_test::*;
+use solana_sdk::{
+    signature::Keypair, signer::Signer, stake::state::StakeState, transaction::Transaction,
+};
+use tests::seraph_fixtures::STestFixture;
+
+#[tokio::test]
+async fn test_withdrawal_ticket_lifecycle() {
+    let fixture = STestFixture::new().await;
+    let ctx = &fixture.ctx;
+    fixture.initialize_seraph().await;
+    fixture.initialize_and_score_validators().await;
+    fixture.init_and_auth_stake_accounts_to_pool().await;
+
+    let stake_accounts: Vec<_> = fixture.stake_accounts[0..3]
+        .iter()
+        .map(|stake_account| stake_account.pubkey())
+        .collect();
+    fixture.register_stake_accounts(&stake_accounts).await;
+
+    // the middle account goes to the lowest scored validator in the VList
+    let v_list: VList = fixture.load_and_deserialize(&fixture.v_list).await;
+    let validators = [
+        v_list.validators[0].validator,
+        v_list.validators[v_list.idx - 1].validator,
+        v_list.validators[1].validator,
+    ];
+    let transaction = Transaction::new_signed_with_payer(
+        &stake_accounts
+            .iter()
+            .zip(validators)
+            .map(|(stake_account, validator)| fixture.delegate_stake_ix(*stake_account, validator))
+            .collect::<Vec<_>>(),
+        Some(&fixture.admin.pubkey()),
+        &[&fixture.admin],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    fixture.submit_transaction_assert_success(transaction).await;
+
+    // the deposits were minted 1:1 into the empty pool
+    let pool: Pool = fixture.load_and_deserialize(&fixture.pool).await;
+    let owner = &fixture.keypair;
+    let pool_token_account = fixture.depositor_token_account.pubkey();
+    let token_account: TokenAccount = fixture.load_and_deserialize(&pool_token_account).await;
+    assert_eq!(token_account.amount, pool.total_lamports);
+    let stake_registry: StakeRegistry = fixture.load_and_deserialize(&fixture.stake_registry).await;
+    let pool_tokens = stake_registry.entries()[1].lamports / 2;
+
+    let transaction = Transaction::new_signed_with_payer(
+        &[fixture.request_withdrawal_ix(owner.pubkey(), pool_token_account, 0, pool_tokens)],
+        Some(&owner.pubkey()),
+        &[owner],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    fixture.submit_transaction_assert_success(transaction).await;
+
+    let ticket: WithdrawalTicket = fixture
+        .load_and_deserialize(&fixture.withdrawal_ticket(0))
+        .await;
+    let new_pool: Pool = fixture.load_and_deserialize(&fixture.pool).await;
+    assert_eq!(ticket.owner, owner.pubkey());
+    assert_eq!(ticket.lamports, pool_tokens);
+    assert_eq!(new_pool.next_ticket_id, 1);
+    assert_eq!(new_pool.pending_withdrawal_lamports, ticket.lamports);
+    assert_eq!(
+        new_pool.total_lamports,
+        pool.total_lamports - ticket.lamports
+    );
+
+    // only the shortfall is split off the stake on the worst validator to cover the ticket
+    let reserve_lamports = ctx
+        .borrow_mut()
+        .banks_client
+        .get_balance(fixture.reserve)
+        .await
+        .unwrap();
+    let rent = ctx.borrow_mut().banks_client.get_rent().await.unwrap();
+    let stake_rent = rent.minimum_balance(std::mem::size_of::<StakeState>());
+    let shortfall = new_pool.pending_withdrawal_lamports
+        + new_pool
+            .reserve_params
+            .target_reserve_lamports(new_pool.total_lamports)
+        - (reserve_lamports - rent.minimum_balance(0));
+    let transaction = Transaction::new_signed_with_payer(
+        &[fixture.process_unstake_queue_ix(&stake_accounts, Some(validators[1]), 0)],
+        Some(&fixture.keypair.pubkey()),
+        &[&fixture.keypair],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    fixture.submit_transaction_assert_success(transaction).await;
+
+    let transient_stake_account = Pool::transient_stake_pubkey(fixture.pool, validators[1], 0);
+    let stake_registry: StakeRegistry = fixture.load_and_deserialize(&fixture.stake_registry).await;
+    let entries = stake_registry.entries();
+    assert_eq!(
+        entries.iter().map(|entry| entry.status).collect::<Vec<_>>(),
+        vec![
+            StakeStatus::Activating,
+            StakeStatus::Activating,
+            StakeStatus::Activating,
+            StakeStatus::Deactivating
+        ]
+    );
+    assert_eq!(entries[3].stake_account, transient_stake_account);
+    assert_eq!(entries[3].vote_account, validators[1]);
+    assert_eq!(entries[3].lamports, shortfall + stake_rent);
+
+    // the ticket waits for the deactivation epoch
+    let transaction = Transaction::new_signed_with_payer(
+        &[fixture.claim_ticket_ix(owner.pubkey(), 0)],
+        Some(&owner.pubkey()),
+        &[owner],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    fixture
+        .submit_transaction_assert_error(transaction, "TicketNotClaimable")
+        .await;
+
+    fixture.advance_num_epochs(1).await;
+
+    // and for the stake to reach the reserve
+    let transaction = Transaction::new_signed_with_payer(
+        &[fixture.claim_ticket_ix(owner.pubkey(), 0)],
+        Some(&owner.pubkey()),
+        &[owner],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    fixture
+        .submit_transaction_assert_error(transaction, "InsufficientReserve")
+        .await;
+
+    let transaction = Transaction::new_signed_with_payer(
+        &[fixture.finalize_transient_stake_ix(transient_stake_account, validators[1])],
+        Some(&fixture.keypair.pubkey()),
+        &[&fixture.keypair],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    fixture.submit_transaction_assert_success(transaction).await;
+
+    // claims are user withdrawals, so they go through while paused
+    let owner_lamports = ctx
+        .borrow_mut()
+        .banks_client
+        .get_balance(owner.pubkey())
+        .await
+        .unwrap();
+    let ticket_rent = ctx
+        .borrow_mut()
+        .banks_client
+        .get_balance(fixture.withdrawal_ticket(0))
+        .await
+        .unwrap();
+    let transaction = Transaction::new_signed_with_payer(
+        &[
+            fixture.pause_ix(fixture.admin.pubkey()),
+            fixture.claim_ticket_ix(owner.pubkey(), 0),
+        ],
+        Some(&fixture.admin.pubkey()),
+        &[&fixture.admin, owner],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    fixture.submit_transaction_assert_success(transaction).await;
+
+    assert_eq!(
+        ctx.borrow_mut()
+            .banks_client
+            .get_balance(owner.pubkey())
+            .await
+            .unwrap(),
+        owner_lamports + ticket_rent + ticket.lamports
+    );
+    assert!(ctx
+        .borrow_mut()
+        .banks_client
+        .get_account(fixture.withdrawal_ticket(0))
+        .await
+        .unwrap()
+        .is_none());
+    let pool: Pool = fixture.load_and_deserialize(&fixture.pool).await;
+    assert_eq!(pool.pending_withdrawal_lamports, 0);
+}
+
+#[tokio::test]
+async fn test_ticket_waits_for_unstake_queue() {
+    let fixture = STestFixture::new().await;
+    let ctx = &fixture.ctx;
+    fixture.initialize_seraph().await;
+    fixture.initialize_and_score_validators().await;
+    fixture.init_and_auth_stake_accounts_to_pool().await;
+
+    let stake_accounts: Vec<_> = fixture.stake_accounts[0..3]
+        .iter()
+        .map(|stake_account| stake_account.pubkey())
+        .collect();
+    fixture.register_stake_accounts(&stake_accounts).await;
+
+    let v_list: VList = fixture.load_and_deserialize(&fixture.v_list).await;
+    let validators = [
+        v_list.validators[0].validator,
+        v_list.validators[v_list.idx - 1].validator,
+        v_list.validators[1].validator,
+    ];
+    let transaction = Transaction::new_signed_with_payer(
+        &stake_accounts
+            .iter()
+            .zip(validators)
+            .map(|(stake_account, validator)| fixture.delegate_stake_ix(*stake_account, validator))
+            .collect::<Vec<_>>(),
+        Some(&fixture.admin.pubkey()),
+        &[&fixture.admin],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    fixture.submit_transaction_assert_success(transaction).await;
+
+    let owner = &fixture.keypair;
+    let pool_token_account = fixture.depositor_token_account.pubkey();
+    let stake_registry: StakeRegistry = fixture.load_and_deserialize(&fixture.stake_registry).await;
+    let pool_tokens = stake_registry.entries()[1].lamports / 4;
+    let transaction = Transaction::new_signed_with_payer(
+        &[fixture.request_withdrawal_ix(owner.pubkey(), pool_token_account, 0, pool_tokens)],
+        Some(&owner.pubkey()),
+        &[owner],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    fixture.submit_transaction_assert_success(transaction).await;
+
+    fixture.advance_num_epochs(1).await;
+
+    // a new epoch alone does not make the ticket claimable while no stake was deactivated for it
+    let transaction = Transaction::new_signed_with_payer(
+        &[fixture.claim_ticket_ix(owner.pubkey(), 0)],
+        Some(&owner.pubkey()),
+        &[owner],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    fixture
+        .submit_transaction_assert_error(transaction, "TicketNotClaimable")
+        .await;
+
+    // a ticket requested after the queue was processed is not covered by that deactivation
+    let transaction = Transaction::new_signed_with_payer(
+        &[
+            fixture.update_pool_balance_ix(&stake_accounts),
+            fixture.process_unstake_queue_ix(&stake_accounts, Some(validators[1]), 0),
+            fixture.request_withdrawal_ix(owner.pubkey(), pool_token_account, 1, pool_tokens),
+        ],
+        Some(&owner.pubkey()),
+        &[owner, &fixture.admin],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    fixture.submit_transaction_assert_success(transaction).await;
+
+    fixture.advance_num_epochs(1).await;
+
+    let clock = ctx
+        .borrow_mut()
+        .banks_client
+        .get_sysvar::<anchor_lang::prelude::Clock>()
+        .await
+        .unwrap();
+    let pool: Pool = fixture.load_and_deserialize(&fixture.pool).await;
+    assert!(pool.is_ticket_claimable(0, clock.epoch));
+    assert!(!pool.is_ticket_claimable(1, clock.epoch));
+
+    let transaction = Transaction::new_signed_with_payer(
+        &[fixture.claim_ticket_ix(owner.pubkey(), 1)],
+        Some(&owner.pubkey()),
+        &[owner],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    fixture
+        .submit_transaction_assert_error(transaction, "TicketNotClaimable")
+        .await;
+}
+
+#[tokio::test]
+async fn test_invalid_withdrawal_request() {
+    let fixture = STestFixture::new().await;
+    let ctx = &fixture.ctx;
+    fixture.initialize_seraph().await;
+    fixture.init_and_auth_stake_accounts_to_pool().await;
+    fixture
+        .register_stake_accounts(&[fixture.stake_accounts[0].pubkey()])
+        .await;
+
+    // a request must redeem some lamports
+    let owner = &fixture.keypair;
+    let transaction = Transaction::new_signed_with_payer(
+        &[fixture.request_withdrawal_ix(
+            owner.pubkey(),
+            fixture.depositor_token_account.pubkey(),
+            0,
+            0,
+        )],
+        Some(&owner.pubkey()),
+        &[owner],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    fixture
+        .submit_transaction_assert_error(transaction, "InvalidWithdrawalAmount")
+        .await;
+
+    // only the token account's owner can burn its tokens
+    let other = &fixture.stakers[0];
+    let transaction = Transaction::new_signed_with_payer(
+        &[fixture.request_withdrawal_ix(
+            other.pubkey(),
+            fixture.depositor_token_account.pubkey(),
+            0,
+            1_000,
+        )],
+        Some(&other.pubkey()),
+        &[other],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    fixture
+        .submit_transaction_assert_error(transaction, "ConstraintTokenOwner")
+        .await;
+}
+
+#[tokio::test]
+async fn test_stale_pool_balance() {
+    let fixture = STestFixture::new().await;
+    let ctx = &fixture.ctx;
+    fixture.initialize_seraph().await;
+    fixture.init_and_auth_stake_accounts_to_pool().await;
+
+    let stake_accounts: Vec<_> = fixture.stake_accounts[0..2]
+        .iter()
+        .map(|stake_account| stake_account.pubkey())
+        .collect();
+    fixture.register_stake_accounts(&stake_accounts[0..1]).await;
+
+    fixture.advance_num_epochs(1).await;
+
+    // pool tokens are not priced across an epoch boundary until its rewards and fees are booked
+    let owner = &fixture.keypair;
+    let pool_token_account = fixture.depositor_token_account.pubkey();
+    let admin: &[&Keypair] = &[&fixture.admin];
+    let depositor: &[&Keypair] = &[owner];
+    for (instruction, signers) in [
+        (fixture.add_stake_account_ix(stake_accounts[1]), admin),
+        (
+            fixture.request_withdrawal_ix(owner.pubkey(), pool_token_account, 0, 1_000),
+            depositor,
+        ),
+        (
+            fixture.instant_unstake_ix(owner.pubkey(), pool_token_account, 1_000, 0),
+            depositor,
+        ),
+    ] {
+        let transaction = Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&signers[0].pubkey()),
+            signers,
+            ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+        );
+        fixture
+            .submit_transaction_assert_error(transaction, "PoolBalanceStale")
+            .await;
+    }
+
+    let transaction = Transaction::new_signed_with_payer(
+        &[
+            fixture.update_pool_balance_ix(&stake_accounts[0..1]),
+            fixture.add_stake_account_ix(stake_accounts[1]),
+            fixture.request_withdrawal_ix(owner.pubkey(), pool_token_account, 0, 1_000),
+        ],
+        Some(&owner.pubkey()),
+        &[owner, &fixture.admin],
+        ctx.borrow_mut().get_new_latest_blockhash().await.unwrap(),
+    );
+    fixture.submit_transaction_assert_success(transaction).await;
+}